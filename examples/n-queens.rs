@@ -1,4 +1,5 @@
 extern crate proto_vulcan;
+use proto_vulcan::operator::label_ff;
 use proto_vulcan::prelude::*;
 use proto_vulcan::relation::diseqfd;
 use proto_vulcan::relation::distinctfd;
@@ -69,7 +70,25 @@ fn nqueenso<U: User, E: Engine<U>>(
 
 fn main() {
     let n: isize = 8;
+
+    // Compare the number of failed branches before the first solution, when the queens are
+    // enumerated in declaration order before reification, and when labeled first-fail.
     let query = proto_vulcan_query!(|queens| { nqueenso(queens, { n }, { n }, []) });
+    let mut iter = query.run().record_failures();
+    iter.next();
+    let in_order = iter.take_failures().len();
+
+    let ff_query = proto_vulcan_query!(|queens| {
+        nqueenso(queens, { n }, { n }, []),
+        label_ff(queens),
+    });
+    let mut iter = ff_query.run().record_failures();
+    iter.next();
+    let first_fail = iter.take_failures().len();
+    println!(
+        "Failed branches before first solution: {} in order, {} first-fail",
+        in_order, first_fail
+    );
 
     for (i, result) in query.run().enumerate() {
         println!("{}: {}", i, result.queens);
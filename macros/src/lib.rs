@@ -209,6 +209,84 @@ impl ToTokens for Fresh {
     }
 }
 
+#[allow(dead_code)]
+#[derive(Clone)]
+struct FreshIn {
+    fresh_in: Ident,
+    or1_token: Token![|],
+    variables: Punctuated<Ident, Token![,]>,
+    or2_token: Token![|],
+    in_token: Token![in],
+    domain: syn::Expr,
+    brace_token: Brace,
+    body: Punctuated<Clause, Token![,]>,
+}
+
+impl Parse for FreshIn {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let fresh_in: Ident = input.parse()?;
+        if fresh_in.to_string().as_str() != "fresh_in" {
+            return Err(Error::new(
+                fresh_in.span(),
+                "Identifier \"fresh_in\" expected",
+            ));
+        }
+
+        let or1_token: Token![|] = input.parse()?;
+        let mut variables = Punctuated::new();
+        loop {
+            if input.peek(Token![|]) {
+                break;
+            }
+            let var: Ident = input.parse()?;
+            variables.push_value(var);
+            if input.peek(Token![|]) {
+                break;
+            }
+            let punct: Token![,] = input.parse()?;
+            variables.push_punct(punct);
+        }
+        let or2_token: Token![|] = input.parse()?;
+        let in_token: Token![in] = input.parse()?;
+        let domain = syn::Expr::parse_without_eager_brace(input)?;
+
+        let content;
+        Ok(FreshIn {
+            fresh_in,
+            or1_token,
+            variables,
+            or2_token,
+            in_token,
+            domain,
+            brace_token: braced!(content in input),
+            body: content.parse_terminated(Clause::parse)?,
+        })
+    }
+}
+
+impl ToTokens for FreshIn {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let variables: Vec<&Ident> = self.variables.iter().collect();
+        let domain = &self.domain;
+        let body: Vec<&Clause> = self.body.iter().collect();
+        // The domains are posted before the body, so that the variables carry their domains
+        // in all clauses of the body.
+        let output = quote! {{
+            #( let #variables: ::proto_vulcan::lterm::LTerm<_, _> = ::proto_vulcan::compound::CompoundTerm::new_var(stringify!(#variables)); )*
+            let __domain = #domain;
+            ::proto_vulcan::operator::fresh::Fresh::new(vec![ #( ::std::clone::Clone::clone(&#variables) ),* ],
+                ::proto_vulcan::GoalCast::cast_into(
+                    ::proto_vulcan::operator::conj::InferredConj::from_array(&[
+                        #( ::proto_vulcan::GoalCast::cast_into(
+                            ::proto_vulcan::relation::clpfd::infd::infdrange(::std::clone::Clone::clone(&#variables), &__domain)) ),*
+                        #( , ::proto_vulcan::GoalCast::cast_into( #body ) )*
+                    ]))
+                )
+        }};
+        output.to_tokens(tokens);
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Clone)]
 struct Conjunction {
@@ -259,6 +337,10 @@ impl ToTokens for UnnamedCompoundConstructorArgument {
                     let output = quote! { ::proto_vulcan::compound::CompoundTerm::new_wildcard() };
                     output.to_tokens(tokens);
                 }
+                term if term.is_empty() => {
+                    let output = quote! { ::proto_vulcan::compound::CompoundTerm::new_none() };
+                    output.to_tokens(tokens);
+                }
                 _ => treeterm.to_tokens(tokens),
             },
             _ => self.pattern.to_tokens(tokens),
@@ -317,6 +399,10 @@ impl ToTokens for NamedCompoundConstructorArgument {
                     let output = quote! { #ident #colon_token ::proto_vulcan::compound::CompoundTerm::new_wildcard() };
                     output.to_tokens(tokens);
                 }
+                term if term.is_empty() => {
+                    let output = quote! { #ident #colon_token ::proto_vulcan::compound::CompoundTerm::new_none() };
+                    output.to_tokens(tokens);
+                }
                 _ => {
                     let output = quote! { #ident #colon_token #treeterm };
                     output.to_tokens(tokens);
@@ -649,10 +735,23 @@ struct Operator {
 impl Parse for Operator {
     fn parse(input: ParseStream) -> Result<Self> {
         let content;
+        let name: Ident = input.parse()?;
+        let brace_token = braced!(content in input);
+        let body: Punctuated<ClauseInOperator, Token![,]> =
+            content.parse_terminated(ClauseInOperator::parse)?;
+        if body.is_empty() {
+            return Err(Error::new(
+                name.span(),
+                format!(
+                    "Operator \"{}\" requires a body of at least one clause or clause list `[...]`",
+                    name
+                ),
+            ));
+        }
         Ok(Operator {
-            name: input.parse()?,
-            brace_token: braced!(content in input),
-            body: content.parse_terminated(ClauseInOperator::parse)?,
+            name,
+            brace_token,
+            body,
         })
     }
 }
@@ -1102,10 +1201,43 @@ impl ToTokens for Pattern {
     }
 }
 
+// if x in 0..=9
+#[allow(dead_code)]
+#[derive(Clone)]
+struct PatternGuard {
+    if_token: Token![if],
+    term: TreeTerm,
+    in_token: Token![in],
+    domain: syn::Expr,
+}
+
+impl Parse for PatternGuard {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(PatternGuard {
+            if_token: input.parse()?,
+            term: input.parse()?,
+            in_token: input.parse()?,
+            domain: input.call(syn::Expr::parse_without_eager_brace)?,
+        })
+    }
+}
+
+impl ToTokens for PatternGuard {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let term = &self.term;
+        let domain = &self.domain;
+        let output = quote! {
+            ::proto_vulcan::relation::clpfd::infd::infdrange(#term, &(#domain))
+        };
+        output.to_tokens(tokens);
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Clone)]
 struct PatternArm {
     patterns: Vec<Pattern>,
+    guard: Option<PatternGuard>,
     arrow: Token![=>],
     brace_token: Option<Brace>,
     body: Punctuated<Clause, Token![,]>,
@@ -1120,11 +1252,14 @@ impl Parse for PatternArm {
 
             if input.peek(Token![|]) {
                 let _: Token![|] = input.parse()?;
-            } else if input.peek(Token![=>]) {
+            } else if input.peek(Token![=>]) || input.peek(Token![if]) {
                 break;
             }
         }
 
+        // The body is shared by all of the alternative patterns, and therefore each of them
+        // must bind the same set of variables.
+        let mut first_vars: Option<PatternVariableSet> = None;
         for pattern in patterns.iter() {
             let mut pattern_vars = PatternVariableSet::new();
             pattern.get_vars(&mut pattern_vars);
@@ -1136,8 +1271,33 @@ impl Parse for PatternArm {
                     ));
                 }
             }
+
+            match &first_vars {
+                Some(first_vars) if **first_vars != *pattern_vars => {
+                    let mut idents: Vec<&Ident> =
+                        first_vars.symmetric_difference(&pattern_vars).collect();
+                    idents.sort_by_key(|ident| ident.to_string());
+                    let names: Vec<String> =
+                        idents.iter().map(|ident| format!("`{}`", ident)).collect();
+                    return Err(Error::new(
+                        idents[0].span(),
+                        format!(
+                            "Alternative patterns must bind the same variables; {} not bound in all alternatives",
+                            names.join(", ")
+                        ),
+                    ));
+                }
+                Some(_) => (),
+                None => first_vars = Some(pattern_vars),
+            }
         }
 
+        let guard = if input.peek(Token![if]) {
+            Some(input.parse()?)
+        } else {
+            None
+        };
+
         let arrow: Token![=>] = input.parse()?;
 
         if input.peek(Brace) {
@@ -1146,6 +1306,7 @@ impl Parse for PatternArm {
             let body = content.parse_terminated(Clause::parse)?;
             Ok(PatternArm {
                 patterns,
+                guard,
                 arrow,
                 brace_token: Some(brace_token),
                 body,
@@ -1153,6 +1314,7 @@ impl Parse for PatternArm {
         } else if input.peek(Token![,]) {
             Ok(PatternArm {
                 patterns,
+                guard,
                 arrow,
                 brace_token: None,
                 body: Punctuated::new(),
@@ -1162,6 +1324,7 @@ impl Parse for PatternArm {
             body.push(input.parse()?);
             Ok(PatternArm {
                 patterns,
+                guard,
                 arrow,
                 brace_token: None,
                 body,
@@ -1206,6 +1369,7 @@ impl ToTokens for PatternMatchOperator {
         let mut patterns: Vec<Pattern> = vec![];
         let mut vars: Vec<Vec<Ident>> = vec![];
         let mut compounds: Vec<Vec<Ident>> = vec![];
+        let mut heads: Vec<proc_macro2::TokenStream> = vec![];
         let mut clauses: Vec<Punctuated<proc_macro2::TokenStream, Token![,]>> = vec![];
         for arm in self.arms.iter() {
             // Repeat |-expression patterns with multiple single pattern entries
@@ -1224,6 +1388,22 @@ impl ToTokens for PatternMatchOperator {
                 });
                 vars.push(treeterm_pattern_vars);
                 compounds.push(compound_pattern_vars);
+                heads.push(match &arm.guard {
+                    // The guard is part of the match-goal, so that the arm is not chosen when
+                    // the guard fails.
+                    Some(guard) => quote! {
+                        ::proto_vulcan::GoalCast::cast_into(
+                            ::proto_vulcan::operator::conj::InferredConj::from_array(&[
+                                ::proto_vulcan::GoalCast::cast_into(
+                                    ::proto_vulcan::relation::eq(__term__, __pattern__)),
+                                ::proto_vulcan::GoalCast::cast_into(#guard),
+                            ]))
+                    },
+                    None => quote! {
+                        ::proto_vulcan::GoalCast::cast_into(
+                            ::proto_vulcan::relation::eq(__term__, __pattern__))
+                    },
+                });
                 let mut arm_clauses: Punctuated<proc_macro2::TokenStream, Token![,]> =
                     Punctuated::new();
                 for clause in arm.body.iter() {
@@ -1247,9 +1427,7 @@ impl ToTokens for PatternMatchOperator {
                         #( let #vars = ::proto_vulcan::lterm::LTerm::var(stringify!(#vars)); )*
                         #( let #compounds = ::proto_vulcan::compound::CompoundTerm::new_var(stringify!(#compounds)); )*
                         let __pattern__ = #patterns;
-                        [::proto_vulcan::GoalCast::cast_into(
-                            ::proto_vulcan::relation::eq(__term__, __pattern__)),
-                         #clauses]
+                        [#heads, #clauses]
                     } ),* ],
                 )
             }
@@ -1264,9 +1442,7 @@ impl ToTokens for PatternMatchOperator {
                         #( let #vars = ::proto_vulcan::lterm::LTerm::var(stringify!(#vars)); )*
                         #( let #compounds = ::proto_vulcan::compound::CompoundTerm::new_var(stringify!(#compounds)); )*
                         let __pattern__ = #patterns;
-                        [::proto_vulcan::GoalCast::cast_into(
-                            ::proto_vulcan::relation::eq(__term__, __pattern__)),
-                         #clauses]
+                        [#heads, #clauses]
                     } ),* ],
                 ))
             }
@@ -1321,10 +1497,66 @@ impl ToTokens for For {
     }
 }
 
+#[allow(dead_code)]
+#[derive(Clone)]
+struct CondeFor {
+    conde: Ident,
+    for_token: Token![for],
+    pattern: Ident,
+    in_token: Token![in],
+    iter: syn::Expr,
+    brace_token: Brace,
+    body: Punctuated<ClauseInOperator, Token![,]>,
+}
+
+impl Parse for CondeFor {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let conde: Ident = input.parse()?;
+        if conde.to_string().as_str() != "conde" {
+            return Err(Error::new(conde.span(), "Identifier \"conde\" expected"));
+        }
+        let for_token: Token![for] = input.parse()?;
+        let pattern = input.parse()?;
+        let in_token: Token![in] = input.parse()?;
+        let iter = input.call(syn::Expr::parse_without_eager_brace)?;
+        let content;
+        let brace_token = braced!(content in input);
+        let body = content.parse_terminated(ClauseInOperator::parse)?;
+        Ok(CondeFor {
+            conde,
+            for_token,
+            pattern,
+            in_token,
+            iter,
+            brace_token,
+            body,
+        })
+    }
+}
+
+impl ToTokens for CondeFor {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let pattern = &self.pattern;
+        let iter = &self.iter;
+        let body: Vec<&ClauseInOperator> = self.body.iter().collect();
+        let output = quote!({
+            ::proto_vulcan::operator::conde_for(::proto_vulcan::operator::CondeForOperatorParam::new(
+                ::std::iter::IntoIterator::into_iter(#iter),
+                ::std::rc::Rc::new(move |#pattern| {
+                    let #pattern: ::proto_vulcan::lterm::LTerm<_, _> = ::std::convert::Into::into(#pattern);
+                    ::proto_vulcan::GoalCast::cast_into(::proto_vulcan::operator::conj::InferredConj::from_conjunctions(&[ #( #body ),* ]))
+                }),
+            ))
+        });
+        output.to_tokens(tokens);
+    }
+}
+
 #[derive(Clone, Debug)]
 enum Value {
     Bool(syn::LitBool),
     Number(syn::LitInt),
+    Float(syn::LitFloat),
     Char(syn::LitChar),
     String(syn::LitStr),
 }
@@ -1336,6 +1568,7 @@ impl Parse for Value {
             syn::Lit::Str(s) => Ok(Value::String(s)),
             syn::Lit::Char(c) => Ok(Value::Char(c)),
             syn::Lit::Int(n) => Ok(Value::Number(n)),
+            syn::Lit::Float(x) => Ok(Value::Float(x)),
             syn::Lit::Bool(b) => Ok(Value::Bool(b)),
             _ => Err(Error::new(lit.span(), "Invalid literal")),
         }
@@ -1347,6 +1580,7 @@ impl ToTokens for Value {
         match self {
             Value::Bool(b) => b.to_tokens(tokens),
             Value::Number(n) => n.to_tokens(tokens),
+            Value::Float(x) => x.to_tokens(tokens),
             Value::Char(c) => c.to_tokens(tokens),
             Value::String(s) => s.to_tokens(tokens),
         }
@@ -1502,6 +1736,24 @@ impl Parse for TreeTerm {
             let mut items: Vec<InnerTreeTerm> = vec![];
             let mut is_proper = true;
             while !content.is_empty() {
+                if content.peek(Token![..]) {
+                    // [a, b, ..rest] is equal to [a, b | rest], and [a, b, ..] to [a, b | _]
+                    let dots: Token![..] = content.parse()?;
+                    if items.is_empty() {
+                        return Err(Error::new(
+                            dots.span(),
+                            "Rest-pattern `..` requires at least one leading element",
+                        ));
+                    }
+                    if content.is_empty() {
+                        items.push(InnerTreeTerm(TreeTerm::Any(Token![_](dots.span()))));
+                    } else {
+                        let rest: InnerTreeTerm = content.parse()?;
+                        items.push(rest);
+                    }
+                    is_proper = false;
+                    break;
+                }
                 let term: InnerTreeTerm = content.parse()?;
                 items.push(term);
                 if content.peek(Token![,]) {
@@ -1628,12 +1880,16 @@ impl ToTokens for Diseq {
 enum Clause {
     /// for x in coll { }
     For(For),
+    /// conde for x in iter { }
+    CondeFor(CondeFor),
     /// project |x, y, z| { }
     Project(Project),
     // fngoal |state| { }
     FnGoal(FnGoal),
     /// |x, y, z| { }
     Fresh(Fresh),
+    /// fresh_in |x, y, z| in 0..=9 { }
+    FreshIn(FreshIn),
     // x == y
     Eq(Eq),
     // x != y
@@ -1674,12 +1930,24 @@ impl Parse for Clause {
         {
             let fngoal: FnGoal = input.parse()?;
             Ok(Clause::FnGoal(fngoal))
+        } else if input.peek(Ident)
+            && input.peek2(Token![|])
+            && maybe_ident == Some(String::from("fresh_in"))
+        {
+            let fresh_in: FreshIn = input.parse()?;
+            Ok(Clause::FreshIn(fresh_in))
         } else if input.peek(Ident)
             && input.peek2(Brace)
             && maybe_ident == Some(String::from("closure"))
         {
             let closure: Closure = input.parse()?;
             Ok(Clause::Closure(closure))
+        } else if input.peek(Ident)
+            && input.peek2(Token![for])
+            && maybe_ident == Some(String::from("conde"))
+        {
+            let conde_for: CondeFor = input.parse()?;
+            Ok(Clause::CondeFor(conde_for))
         } else if input.peek(Token![for]) {
             let for_clause: For = input.parse()?;
             Ok(Clause::For(for_clause))
@@ -1728,6 +1996,9 @@ impl ToTokens for Clause {
             Clause::For(for_clause) => {
                 for_clause.to_tokens(tokens);
             }
+            Clause::CondeFor(conde_for) => {
+                conde_for.to_tokens(tokens);
+            }
             Clause::Project(project) => {
                 project.to_tokens(tokens);
             }
@@ -1737,6 +2008,9 @@ impl ToTokens for Clause {
             Clause::Fresh(fresh) => {
                 fresh.to_tokens(tokens);
             }
+            Clause::FreshIn(fresh_in) => {
+                fresh_in.to_tokens(tokens);
+            }
             Clause::Eq(eq) => {
                 eq.to_tokens(tokens);
             }
@@ -1785,6 +2059,19 @@ struct ClauseInOperator(Clause);
 impl Parse for ClauseInOperator {
     fn parse(input: ParseStream) -> Result<Self> {
         let clause: Clause = input.parse()?;
+        if let Clause::Expression(expr) = &clause {
+            match expr {
+                syn::Expr::Tuple(_) | syn::Expr::Lit(_) => {
+                    // Tuples and literals are never goals; a tuple is usually a clause list
+                    // written with parenthesis instead of brackets.
+                    return Err(Error::new(
+                        expr.span(),
+                        "Operator body must be a comma-separated sequence of clauses or clause lists `[...]`",
+                    ));
+                }
+                _ => (),
+            }
+        }
         Ok(ClauseInOperator(clause))
     }
 }
@@ -1796,6 +2083,10 @@ impl ToTokens for ClauseInOperator {
                 let output = quote! { &[ ::proto_vulcan::GoalCast::cast_into(#for_clause) ] };
                 output.to_tokens(tokens);
             }
+            Clause::CondeFor(conde_for) => {
+                let output = quote! { &[ ::proto_vulcan::GoalCast::cast_into(#conde_for) ] };
+                output.to_tokens(tokens);
+            }
             Clause::Project(project) => {
                 let output = quote! { &[ ::proto_vulcan::GoalCast::cast_into(#project) ] };
                 output.to_tokens(tokens);
@@ -1808,6 +2099,10 @@ impl ToTokens for ClauseInOperator {
                 let output = quote! { &[ ::proto_vulcan::GoalCast::cast_into(#fresh) ] };
                 output.to_tokens(tokens);
             }
+            Clause::FreshIn(fresh_in) => {
+                let output = quote! { &[ ::proto_vulcan::GoalCast::cast_into(#fresh_in) ] };
+                output.to_tokens(tokens);
+            }
             Clause::Eq(eq) => {
                 let output = quote! { &[ ::proto_vulcan::GoalCast::cast_into(#eq) ] };
                 output.to_tokens(tokens);
@@ -1896,6 +2191,7 @@ struct Query {
     or2_token: Token![|],
     brace_token: Brace,
     body: Punctuated<Clause, Token![,]>,
+    dfs: bool,
 }
 
 impl Parse for Query {
@@ -1924,6 +2220,7 @@ impl Parse for Query {
             or2_token,
             brace_token: braced!(content in input),
             body: content.parse_terminated(Clause::parse)?,
+            dfs: false,
         })
     }
 }
@@ -1934,6 +2231,22 @@ impl ToTokens for Query {
         let query_types: Vec<syn::Path> = self.variables.iter().map(|x| &x.path).cloned().collect();
         let body: Vec<&Clause> = self.body.iter().collect();
 
+        let body_goal = if self.dfs {
+            quote! {
+                ::proto_vulcan::GoalCast::cast_into(
+                    ::proto_vulcan::operator::dfs::dfs(::proto_vulcan::operator::OperatorParam::new(&[&[
+                        #( ::proto_vulcan::GoalCast::cast_into( #body ) ),*
+                    ]]))
+                )
+            }
+        } else {
+            quote! {
+                ::proto_vulcan::operator::conj::Conj::from_array(&[
+                    #( ::proto_vulcan::GoalCast::cast_into( #body ) ),*
+                ])
+            }
+        };
+
         let output = quote! {
             #(let #query: #query_types <_, _> = ::proto_vulcan::compound::CompoundTerm::new_var(stringify!(#query)); )*
 
@@ -1953,9 +2266,7 @@ impl ToTokens for Query {
 
                                     )
                                 ),
-                                ::proto_vulcan::operator::conj::Conj::from_array(&[
-                                    #( ::proto_vulcan::GoalCast::cast_into( #body ) ),*
-                                ]),
+                                #body_goal,
                                 ::proto_vulcan::state::reify(::std::clone::Clone::clone(&__query__)),
                             ]),
                         )
@@ -1979,6 +2290,16 @@ impl ToTokens for Query {
                 }
             }
 
+            impl<U: ::proto_vulcan::user::User, E: ::proto_vulcan::engine::Engine<U>> QResult<U, E> {
+                /// Returns names of the query variables that were left unbound by the solution.
+                #[allow(dead_code)]
+                pub fn free_vars(&self) -> Vec<&'static str> {
+                    let mut free = vec![];
+                    #( if self.#query.is_any() { free.push(stringify!(#query)); } )*
+                    free
+                }
+            }
+
             impl<U: ::proto_vulcan::user::User, E: ::proto_vulcan::engine::Engine<U>> fmt::Display for QResult<U, E> {
                 #[allow(unused_variables, unused_assignments)]
                 fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
@@ -2004,6 +2325,16 @@ pub fn proto_vulcan_query(input: TokenStream) -> TokenStream {
     output.into()
 }
 
+#[proc_macro]
+pub fn proto_vulcan_query_dfs(input: TokenStream) -> TokenStream {
+    let mut query = parse_macro_input!(input as Query);
+    query.dfs = true;
+    let output = quote! {{
+        #query
+    }};
+    output.into()
+}
+
 fn make_compound_modifications_to_path(path: &mut syn::Path) -> std::result::Result<(), Error> {
     match path.segments.iter_mut().last() {
         Some(last_segment) => match last_segment.arguments {
@@ -2471,17 +2802,415 @@ fn make_compound_struct(mut itemstruct: syn::ItemStruct) -> TokenStream {
     }
 }
 
+fn make_compound_modifications_to_itemenum(
+    itemenum: &mut syn::ItemEnum,
+) -> std::result::Result<(), Error> {
+    if itemenum.generics.params.is_empty() {
+        let new_generics: syn::Generics = syn::parse_quote! {<U: ::proto_vulcan::user::User, E: ::proto_vulcan::engine::Engine<U>>};
+        itemenum.generics = new_generics;
+    }
+    for variant in itemenum.variants.iter_mut() {
+        if let syn::Fields::Unit = variant.fields {
+            return Err(Error::new(
+                variant.span(),
+                "Compound enum variants must have fields; use `[]` for an empty term",
+            ));
+        }
+        for field in variant.fields.iter_mut() {
+            field.vis = syn::Visibility::Public(syn::VisPublic {
+                pub_token: syn::parse_quote!(pub),
+            });
+            make_compound_modifications_to_type(&mut field.ty)?;
+        }
+    }
+    itemenum.vis = syn::parse_quote!(pub);
+    Ok(())
+}
+
+/// The type of an enum variant is a compound object in the `{Enum}_compound`-module, named
+/// after the variant.
+fn make_compound_enum_variant(
+    enum_name: &Ident,
+    generics: &syn::Generics,
+    variant: &syn::Variant,
+) -> proc_macro2::TokenStream {
+    let variant_name = &variant.ident;
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+    let fields = &variant.fields;
+
+    match fields {
+        syn::Fields::Named(_) => {
+            let field_names: Vec<syn::Ident> = fields
+                .iter()
+                .map(|field| field.ident.as_ref().unwrap().clone())
+                .collect();
+            quote!(
+                #[derive(Eq)]
+                pub struct #variant_name #impl_generics #where_clause #fields
+
+                impl #impl_generics ::std::clone::Clone for #variant_name #type_generics #where_clause {
+                    fn clone(&self) -> #variant_name #type_generics {
+                        #variant_name {
+                            #( #field_names: ::std::clone::Clone::clone(&self.#field_names) ),*
+                        }
+                    }
+                }
+
+                impl #impl_generics ::proto_vulcan::compound::CompoundObject #type_generics for #variant_name #type_generics #where_clause {
+                    fn type_name(&self) -> &'static str {
+                        concat!(stringify!(#enum_name), "::", stringify!(#variant_name))
+                    }
+
+                    fn children<'a>(&'a self) -> Box<dyn Iterator<Item = &'a dyn ::proto_vulcan::compound::CompoundObject #type_generics> + 'a> {
+                        Box::new(vec![#(&self.#field_names as &dyn ::proto_vulcan::compound::CompoundObject #type_generics),*].into_iter())
+                    }
+                }
+
+                impl #impl_generics ::proto_vulcan::compound::CompoundWalkStar #type_generics for #variant_name #type_generics #where_clause {
+                    fn compound_walk_star(&self, smap: &::proto_vulcan::state::SMap #type_generics) -> Self {
+                        #variant_name { #( #field_names: self.#field_names.compound_walk_star(smap)),* }
+                    }
+                }
+
+                impl #impl_generics ::core::fmt::Debug for #variant_name #type_generics #where_clause {
+                    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                        let debug_trait_builder = &mut ::core::fmt::Formatter::debug_struct(
+                            f,
+                            concat!(stringify!(#enum_name), "::", stringify!(#variant_name)),
+                        );
+                        #(
+                            let _= ::core::fmt::DebugStruct::field(
+                                debug_trait_builder,
+                                stringify!(#field_names),
+                                &self.#field_names,
+                            );
+                        )*
+                        ::core::fmt::DebugStruct::finish(debug_trait_builder)
+                    }
+                }
+
+                impl #impl_generics ::std::hash::Hash for #variant_name #type_generics #where_clause {
+                    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+                        #( ::std::hash::Hash::hash(&self.#field_names, state); )*
+                    }
+                }
+
+                impl #impl_generics ::std::cmp::PartialEq for #variant_name #type_generics #where_clause {
+                    fn eq(&self, other: &Self) -> bool {
+                        #( ::std::cmp::PartialEq::eq(&self.#field_names, &other.#field_names) &&)* true
+                    }
+                }
+
+                impl #impl_generics Into<#enum_name #type_generics> for #variant_name #type_generics #where_clause {
+                    fn into(self) -> #enum_name #type_generics {
+                        #enum_name {
+                            inner: Into::<LTerm #type_generics>::into(self),
+                        }
+                    }
+                }
+
+                impl #impl_generics Into<::proto_vulcan::lterm::LTerm #type_generics> for #variant_name #type_generics #where_clause {
+                    fn into(self) -> ::proto_vulcan::lterm::LTerm #type_generics {
+                        ::proto_vulcan::lterm::LTerm::from(::std::rc::Rc::new(self) as ::std::rc::Rc<dyn ::proto_vulcan::compound::CompoundObject #type_generics>)
+                    }
+                }
+
+                impl #impl_generics ::proto_vulcan::Downcast #type_generics for #variant_name #type_generics #where_clause {
+                    type SubType = #enum_name #type_generics;
+                    fn into_sub(self) -> Self::SubType {
+                        self.into()
+                    }
+                }
+            )
+        }
+        _ => {
+            let field_indices: Vec<syn::Index> = fields
+                .iter()
+                .enumerate()
+                .map(|(n, _)| syn::Index::from(n))
+                .collect();
+            quote!(
+                #[derive(Eq)]
+                pub struct #variant_name #impl_generics #fields #where_clause;
+
+                impl #impl_generics ::std::clone::Clone for #variant_name #type_generics #where_clause {
+                    fn clone(&self) -> #variant_name #type_generics {
+                        #variant_name(#( ::std::clone::Clone::clone(&self.#field_indices) ),* )
+                    }
+                }
+
+                impl #impl_generics ::proto_vulcan::compound::CompoundObject #type_generics for #variant_name #type_generics #where_clause {
+                    fn type_name(&self) -> &'static str {
+                        concat!(stringify!(#enum_name), "::", stringify!(#variant_name))
+                    }
+
+                    fn children<'a>(&'a self) -> Box<dyn Iterator<Item = &'a dyn ::proto_vulcan::compound::CompoundObject #type_generics> + 'a> {
+                        Box::new(vec![#(&self.#field_indices as &dyn ::proto_vulcan::compound::CompoundObject #type_generics),*].into_iter())
+                    }
+                }
+
+                impl #impl_generics ::proto_vulcan::compound::CompoundWalkStar #type_generics for #variant_name #type_generics #where_clause {
+                    fn compound_walk_star(&self, smap: &::proto_vulcan::state::SMap #type_generics) -> Self {
+                        #variant_name(#(self.#field_indices.compound_walk_star(smap)),*)
+                    }
+                }
+
+                impl #impl_generics ::core::fmt::Debug for #variant_name #type_generics #where_clause {
+                    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                        let debug_trait_builder = &mut ::core::fmt::Formatter::debug_tuple(
+                            f,
+                            concat!(stringify!(#enum_name), "::", stringify!(#variant_name)),
+                        );
+                        #( let _ = ::core::fmt::DebugTuple::field(debug_trait_builder, &self.#field_indices); )*
+                        ::core::fmt::DebugTuple::finish(debug_trait_builder)
+                    }
+                }
+
+                impl #impl_generics ::std::hash::Hash for #variant_name #type_generics #where_clause {
+                    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+                        #( ::std::hash::Hash::hash(&self.#field_indices, state); )*
+                    }
+                }
+
+                impl #impl_generics ::std::cmp::PartialEq for #variant_name #type_generics #where_clause {
+                    fn eq(&self, other: &Self) -> bool {
+                        #( ::std::cmp::PartialEq::eq(&self.#field_indices, &other.#field_indices) &&)* true
+                    }
+                }
+
+                impl #impl_generics Into<#enum_name #type_generics> for #variant_name #type_generics #where_clause {
+                    fn into(self) -> #enum_name #type_generics {
+                        #enum_name {
+                            inner: Into::<LTerm #type_generics>::into(self),
+                        }
+                    }
+                }
+
+                impl #impl_generics Into<::proto_vulcan::lterm::LTerm #type_generics> for #variant_name #type_generics #where_clause {
+                    fn into(self) -> ::proto_vulcan::lterm::LTerm #type_generics {
+                        ::proto_vulcan::lterm::LTerm::from(::std::rc::Rc::new(self) as ::std::rc::Rc<dyn ::proto_vulcan::compound::CompoundObject #type_generics>)
+                    }
+                }
+
+                impl #impl_generics ::proto_vulcan::Downcast #type_generics for #variant_name #type_generics #where_clause {
+                    type SubType = #enum_name #type_generics;
+                    fn into_sub(self) -> Self::SubType {
+                        self.into()
+                    }
+                }
+            )
+        }
+    }
+}
+
+/// A compound enum is a compound term like a compound struct, but instead of a single
+/// compound object type, each of its variants has a compound object type of its own.
+/// The variant types are in the `{Enum}_compound`-module, where the `Enum::Variant`-paths of
+/// the compound patterns and constructors refer to. Terms of different variants never unify.
+fn make_compound_enum(mut itemenum: syn::ItemEnum) -> TokenStream {
+    // Add generics and where necessary
+    match make_compound_modifications_to_itemenum(&mut itemenum) {
+        Ok(()) => (),
+        Err(error) => return error.to_compile_error().into(),
+    }
+
+    let vis = &itemenum.vis;
+    let enum_name = itemenum.ident.clone();
+    let mod_name = quote::format_ident!("{}_compound", enum_name);
+    let generics = &itemenum.generics;
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+    let variants: Vec<proc_macro2::TokenStream> = itemenum
+        .variants
+        .iter()
+        .map(|variant| make_compound_enum_variant(&enum_name, generics, variant))
+        .collect();
+
+    let output = quote!(
+        #[allow(non_snake_case)]
+        #vis mod #mod_name {
+            use super::*;
+
+            #( #variants )*
+        }
+
+        #[derive(Eq)]
+        #vis struct #enum_name #impl_generics {
+            inner: LTerm #type_generics,
+        }
+
+        impl #impl_generics ::std::clone::Clone for #enum_name #type_generics #where_clause {
+            fn clone(&self) -> #enum_name #type_generics {
+                #enum_name {
+                    inner: ::std::clone::Clone::clone(&self.inner),
+                }
+            }
+        }
+
+        impl #impl_generics ::std::fmt::Debug for #enum_name #type_generics #where_clause {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                self.inner.fmt(f)
+            }
+        }
+
+        impl #impl_generics ::std::hash::Hash for #enum_name #type_generics #where_clause {
+            fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+                ::std::hash::Hash::hash(&self.inner, state);
+            }
+        }
+
+        impl #impl_generics ::std::cmp::PartialEq for #enum_name #type_generics #where_clause {
+            fn eq(&self, other: &Self) -> bool {
+                ::std::cmp::PartialEq::eq(&self.inner, &other.inner)
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics ::proto_vulcan::compound::CompoundTerm #type_generics for #enum_name #type_generics #where_clause {
+            fn new_var(name: &'static str) -> #enum_name #type_generics {
+                #enum_name {
+                    inner: LTerm::var(name),
+                }
+            }
+
+            fn new_wildcard() -> #enum_name #type_generics {
+                #enum_name {
+                    inner: LTerm::any(),
+                }
+            }
+
+            fn new_none() -> #enum_name #type_generics {
+                #enum_name {
+                    inner: LTerm::empty_list(),
+                }
+            }
+        }
+
+        impl #impl_generics ::proto_vulcan::compound::CompoundObject #type_generics for #enum_name #type_generics #where_clause {
+            fn type_name(&self) -> &'static str {
+                stringify!(#enum_name)
+            }
+
+            fn children<'a>(&'a self) -> Box<dyn Iterator<Item = &'a dyn ::proto_vulcan::compound::CompoundObject #type_generics> + 'a> {
+                self.inner.children()
+            }
+
+            fn as_term(&self) -> Option<&LTerm #type_generics> {
+                Some(&self.inner)
+            }
+        }
+
+        impl #impl_generics ::proto_vulcan::compound::CompoundWalkStar #type_generics for #enum_name #type_generics #where_clause {
+            fn compound_walk_star(&self, smap: &::proto_vulcan::state::SMap #type_generics) -> Self {
+                #enum_name {
+                    inner: self.inner.compound_walk_star(smap),
+                }
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics Into<::proto_vulcan::lterm::LTerm #type_generics> for #enum_name #type_generics #where_clause {
+            fn into(self) -> LTerm #type_generics {
+                self.inner
+            }
+        }
+
+        impl #impl_generics ::proto_vulcan::Upcast<U, E, ::proto_vulcan::lterm::LTerm #type_generics> for #enum_name #type_generics #where_clause {
+            #[inline]
+            fn to_super<K: ::std::borrow::Borrow<Self>>(k: &K) -> ::proto_vulcan::lterm::LTerm #type_generics {
+                Into::into(::std::clone::Clone::clone(k.borrow()))
+            }
+
+            #[inline]
+            fn into_super(self) -> ::proto_vulcan::lterm::LTerm #type_generics {
+                Into::into(self)
+            }
+        }
+
+        impl #impl_generics ::proto_vulcan::Downcast #type_generics for #enum_name #type_generics #where_clause {
+            type SubType = Self;
+            fn into_sub(self) -> Self::SubType {
+                self.into()
+            }
+        }
+    );
+    output.into()
+}
+
 #[proc_macro_attribute]
 pub fn compound(_metadata: TokenStream, input: TokenStream) -> TokenStream {
     let item = parse_macro_input!(input as syn::Item);
 
     match item {
-        //syn::Item::Enum(item_enum) => return make_compound_enum(item_enum),
-        syn::Item::Struct(item_struct) => return make_compound_struct(item_struct),
-        _ => {
-            return syn::Error::new(item.span(), "Compound attribute requires struct or enum.")
-                .to_compile_error()
-                .into();
+        syn::Item::Enum(item_enum) => make_compound_enum(item_enum),
+        syn::Item::Struct(item_struct) => make_compound_struct(item_struct),
+        _ => syn::Error::new(item.span(), "Compound attribute requires struct or enum.")
+            .to_compile_error()
+            .into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operator_empty_body() {
+        let err = syn::parse_str::<Operator>("conde {}").err().unwrap();
+        assert!(err
+            .to_string()
+            .contains("requires a body of at least one clause"));
+    }
+
+    #[test]
+    fn test_operator_tuple_clause() {
+        let err = syn::parse_str::<Operator>("conde { (x == 1, y == 2), [x == 2] }")
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("clause lists `[...]`"));
+    }
+
+    #[test]
+    fn test_operator_literal_clause() {
+        let err = syn::parse_str::<Operator>("conde { 1, [x == 2] }")
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("clause lists `[...]`"));
+    }
+
+    #[test]
+    fn test_operator_valid_body() {
+        assert!(syn::parse_str::<Operator>("conde { [x == 1, y == 2], x == 2, true }").is_ok());
+    }
+
+    #[test]
+    fn test_tree_term_rest() {
+        let term = syn::parse_str::<TreeTerm>("[a, b, ..rest]").unwrap();
+        assert!(matches!(term, TreeTerm::ImproperList { ref items } if items.len() == 3));
+
+        let term = syn::parse_str::<TreeTerm>("[a, ..]").unwrap();
+        match term {
+            TreeTerm::ImproperList { items } => assert!(matches!(items[1].0, TreeTerm::Any(_))),
+            _ => panic!("Expected improper list"),
         }
+
+        let err = syn::parse_str::<TreeTerm>("[..rest]").err().unwrap();
+        assert!(err
+            .to_string()
+            .contains("requires at least one leading element"));
+
+        assert!(syn::parse_str::<TreeTerm>("[a, ..rest, b]").is_err());
+    }
+
+    #[test]
+    fn test_pattern_alternatives() {
+        assert!(syn::parse_str::<PatternArm>("[x, 1] | [1, x] => x == 2").is_ok());
+
+        let err = syn::parse_str::<PatternArm>("[x, _] | [_, y] => x == 2")
+            .err()
+            .unwrap();
+        assert!(err
+            .to_string()
+            .contains("`x`, `y` not bound in all alternatives"));
     }
 }
@@ -2,7 +2,7 @@
 //!
 //! In proto-vulcan type system, [`LTerm`] is the supertype of all types, and all its
 //! subtypes types can be cast back to it. Compound types are Rust
-//! structs that are built from `LTerm`s and other compound types.
+//! structs and enums that are built from `LTerm`s and other compound types.
 //! Proto-vulcan compound types are comparable to prolog compound types.
 //!
 //! ```text
@@ -34,11 +34,50 @@
 //!  * Can be recursive
 //!  * Can be wildcard variable `_` or `[]` instead of structural content.
 //!
+//! # Compound enums
+//! An enum with the `#[compound]` attribute is a compound term whose variants are constructed
+//! and matched with `Enum::Variant`-paths, such as `Expr::Num(n)` or `Expr::Neg { operand }`.
+//! Each variant is a compound object type of its own in the `Enum_compound` module, and terms
+//! of different variants do not unify. Variants without fields are not supported; `[]` can be
+//! used as an empty term instead.
+//!
+//! ```rust
+//! extern crate proto_vulcan;
+//! use proto_vulcan::prelude::*;
+//! use proto_vulcan::relation::plusfd;
+//!
+//! #[compound]
+//! enum Expr {
+//!     Num(LTerm),
+//!     Add(Expr, Expr),
+//! }
+//!
+//! fn evalo<U: User, E: Engine<U>>(expr: Expr<U, E>, value: LTerm<U, E>) -> Goal<U, E> {
+//!     proto_vulcan_closure!(match expr {
+//!         Expr::Num(n) => value == n,
+//!         Expr::Add(a, b) => |x, y| { evalo(a, x), evalo(b, y), plusfd(x, y, value) },
+//!     })
+//! }
+//!
+//! fn main() {
+//!     let query = proto_vulcan_query!(|q| {
+//!         evalo(Expr::Add(Expr::Num(1), Expr::Add(Expr::Num(2), Expr::Num(3))), q)
+//!     });
+//!     assert_eq!(query.run().next().unwrap().q, 6);
+//! }
+//! ```
+//!
 //! # `use`-clauses
 //! When `use`ing compound object or term `Bar`, the corresponding `Bar_compound`
 //! module must also be imported for the compound type to work in proto-vulcan
 //! expressions: `use path::to::{Bar, Bar_compound};`.
 //!
+//! # Compound lists
+//! A compound term that represents a cons-list, such as `struct Cell(LTerm, Cell)` terminated
+//! with `[]`, can implement [`CompoundList`] to be related to an untyped list with
+//! [`compound_listo`](crate::relation::compound_listo), and thereby used with the standard
+//! list relations.
+//!
 //! # Type conversions
 //! Type conversions to supertypes are done implicitly via inserted `Into::into` calls;
 //! for conversions to subtypes, such as compound types, unification must be used.
@@ -50,6 +89,7 @@ use crate::user::User;
 use crate::{Downcast, Upcast};
 use std::any::Any;
 use std::borrow::Borrow;
+use std::fmt;
 use std::hash::{Hash, Hasher};
 use std::rc::Rc;
 
@@ -66,6 +106,20 @@ where
     fn new_none() -> Self;
 }
 
+/// A compound term that represents a cons-list of `LTerm` elements.
+///
+/// The empty list is represented with `[]`, and the non-empty lists with list cells that are
+/// constructed with [`cons`](CompoundList::cons).
+pub trait CompoundList<U, E>:
+    CompoundTerm<U, E> + Upcast<U, E, LTerm<U, E>> + Into<LTerm<U, E>> + Clone
+where
+    U: User,
+    E: Engine<U>,
+{
+    /// Constructs a list cell from `head` and `tail`.
+    fn cons(head: LTerm<U, E>, tail: Self) -> Self;
+}
+
 pub trait CompoundObject<U, E>:
     CompoundHash<U, E> + CompoundEq<U, E> + CompoundAs<U, E> + WalkStar<U, E> + std::fmt::Debug
 where
@@ -88,6 +142,28 @@ where
             None => false,
         }
     }
+
+    /// Formats the compound for display as `TypeName(child1, child2, ...)`, or just
+    /// `TypeName` if it has no children. Children that are terms are formatted with the
+    /// `Display` implementation of `LTerm`.
+    fn compound_display(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.type_name())?;
+        let mut children = self.children().peekable();
+        if children.peek().is_none() {
+            return Ok(());
+        }
+        write!(f, "(")?;
+        for (count, child) in children.enumerate() {
+            if count != 0 {
+                write!(f, ", ")?;
+            }
+            match child.as_term() {
+                Some(term) => write!(f, "{}", term)?,
+                None => child.compound_display(f)?,
+            }
+        }
+        write!(f, ")")
+    }
 }
 
 pub trait WalkStar<U, E>
@@ -418,3 +494,93 @@ where
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::relation::append;
+
+    #[compound]
+    enum Expr {
+        Num(LTerm),
+        Add(Expr, Expr),
+        Neg { operand: Expr },
+    }
+
+    // A relation between an expression and the list of its numbers
+    fn numberso<U: User, E: Engine<U>>(expr: Expr<U, E>, list: LTerm<U, E>) -> Goal<U, E> {
+        proto_vulcan_closure!(match expr {
+            Expr::Num(n) => list == [n],
+            Expr::Add(a, b) => |l, r| {
+                numberso(a, l),
+                numberso(b, r),
+                append(l, r, list),
+            },
+            Expr::Neg { operand } => numberso(operand, list),
+        })
+    }
+
+    #[test]
+    fn test_compound_enum_1() {
+        let query = proto_vulcan_query!(|q| {
+            numberso(
+                Expr::Add(
+                    Expr::Num(1),
+                    Expr::Neg {
+                        operand: Expr::Num(2),
+                    },
+                ),
+                q,
+            )
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([1, 2]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_compound_enum_2() {
+        // Different variants do not unify
+        let query = proto_vulcan_query!(|q: Expr| {
+            numberso(q, [1]),
+            numberso(Expr::Num(1), [1]),
+        });
+        let mut iter = query.run();
+        let expected: LTerm = Expr_compound::Num(LTerm::from(1)).into();
+        assert_eq!(iter.next().unwrap().q, expected);
+
+        let query = proto_vulcan_query!(|q: Expr| {
+            q == Expr::Num(1),
+            q == Expr::Neg { operand: Expr::Num(1) },
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[compound]
+    struct Point(LTerm, LTerm);
+
+    #[test]
+    fn test_compound_display() {
+        let query = proto_vulcan_query!(|q| {
+            q == Expr::Add(
+                Expr::Num(1),
+                Expr::Neg {
+                    operand: Expr::Num(2),
+                },
+            )
+        });
+        let u = query.run().next().unwrap().q.clone();
+        assert_eq!(
+            u.to_string(),
+            "Expr::Add(Expr::Num(1), Expr::Neg(Expr::Num(2)))"
+        );
+
+        let x = LTerm::var("x");
+        let u: LTerm = Point_compound::_InnerPoint(x, lterm!(["a", 'b'])).into();
+        assert_eq!(u.to_string(), "Point(x, [\"a\", 'b'])");
+
+        let query = proto_vulcan_query!(|q| { q == Point(1, _) });
+        let s = query.run().next().unwrap().q.to_string();
+        assert!(s.starts_with("Point(1, _."));
+    }
+}
@@ -7,13 +7,19 @@ use crate::user::User;
 mod ui;
 use ui::UI;
 
+/// Maximum number of streams kept in the history of the debugger.
+const HISTORY_LIMIT: usize = 100;
+
 pub struct Model<U, E>
 where
     U: User,
     E: Engine<U>,
 {
     has_quit: bool,
-    stream: Stream<U, E>,
+    // Previously observed streams, the latest last. The history is never empty.
+    history: Vec<Stream<U, E>>,
+    // Index of the stream in the history that is being viewed.
+    cursor: usize,
 }
 
 impl<U, E> Model<U, E>
@@ -24,9 +30,52 @@ where
     pub fn new() -> Model<U, E> {
         Model {
             has_quit: false,
-            stream: Stream::Empty,
+            history: vec![Stream::Empty],
+            cursor: 0,
         }
     }
+
+    /// Returns the stream that is being viewed.
+    pub fn stream(&self) -> &Stream<U, E> {
+        &self.history[self.cursor]
+    }
+
+    /// Adds a newly observed stream to the history, and moves the cursor to it. The oldest
+    /// stream is dropped when the history is full.
+    pub fn push(&mut self, stream: Stream<U, E>) {
+        self.history.push(stream);
+        if self.history.len() > HISTORY_LIMIT {
+            self.history.remove(0);
+        }
+        self.cursor = self.history.len() - 1;
+    }
+
+    /// Moves the cursor to the previous stream in the history. Returns false if the cursor
+    /// is already at the oldest stream.
+    pub fn step_back(&mut self) -> bool {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Moves the cursor to the next stream in the history. Returns false if the cursor is
+    /// already at the latest stream.
+    pub fn step_forward(&mut self) -> bool {
+        if self.cursor + 1 < self.history.len() {
+            self.cursor += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// True if the cursor is at the latest observed stream.
+    pub fn is_latest(&self) -> bool {
+        self.cursor + 1 == self.history.len()
+    }
 }
 
 pub struct Debugger<U, E>
@@ -57,7 +106,7 @@ where
         }
 
         // Update debugger data model with new stream
-        self.model.stream = stream.clone();
+        self.model.push(stream.clone());
 
         // Refresh view
         self.ui.show();
@@ -73,3 +122,51 @@ where
 
     pub fn new_solution(&mut self, _stream: &Stream<U, E>, _state: &Box<State<U, E>>) {}
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Model;
+    use crate::prelude::*;
+    use crate::state::State;
+    use crate::stream::{LazyStream, Stream};
+
+    type TestModel = Model<DefaultUser, DefaultEngine<DefaultUser>>;
+
+    #[test]
+    fn test_model_history_1() {
+        let mut model = TestModel::new();
+        let unit = || Stream::unit(Box::new(State::new(DefaultUser::new())));
+        model.push(unit());
+        model.push(Stream::lazy(LazyStream::delay(unit())));
+        model.push(Stream::empty());
+        assert!(model.is_latest());
+        assert!(model.stream().is_empty());
+
+        // Stepping back restores the previously observed streams
+        assert!(model.step_back());
+        assert!(!model.stream().is_mature());
+        assert!(model.step_back());
+        assert!(model.stream().head().is_some());
+        assert!(!model.is_latest());
+
+        assert!(model.step_forward());
+        assert!(!model.stream().is_mature());
+        assert!(model.step_forward());
+        assert!(!model.step_forward());
+        assert!(model.stream().is_empty());
+    }
+
+    #[test]
+    fn test_model_history_2() {
+        // The history is bounded
+        let mut model = TestModel::new();
+        for _ in 0..2 * super::HISTORY_LIMIT {
+            model.push(Stream::empty());
+        }
+        let mut steps = 0;
+        while model.step_back() {
+            steps += 1;
+        }
+        assert_eq!(steps, super::HISTORY_LIMIT - 1);
+    }
+}
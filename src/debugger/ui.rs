@@ -145,7 +145,7 @@ where
         let block = Block::default().title("Stream").borders(Borders::ALL);
         let block2 = Block::default().title("Substitution").borders(Borders::ALL);
         let mut stream_items: Vec<ListItem> = vec![];
-        let mut stream_walker = model.stream.walk();
+        let mut stream_walker = model.stream().walk();
         loop {
             match stream_walker.next() {
                 Some((depth, step)) => {
@@ -192,8 +192,9 @@ where
         }
 
         let mut smap_items = vec![];
-        if !model.stream.is_empty() && model.stream.is_mature() {
-            let head = model.stream.head().unwrap();
+        let stream = model.stream();
+        if !stream.is_empty() && stream.is_mature() {
+            let head = stream.head().unwrap();
             let smap = head.smap_ref();
             for (key, value) in smap.iter() {
                 assert!(key.is_var());
@@ -237,6 +238,14 @@ where
                     KeyCode::Char('s') => {
                         break;
                     }
+                    KeyCode::Left | KeyCode::Char('b') => {
+                        // View the previously observed stream
+                        model.step_back();
+                    }
+                    KeyCode::Right | KeyCode::Char('f') => {
+                        // View the next observed stream
+                        model.step_forward();
+                    }
                     _ => (),
                 },
                 Event::Tick => {
@@ -0,0 +1,332 @@
+use crate::engine::continuation::{Continuation, Continuations};
+use crate::engine::Engine;
+use crate::solver::Solver;
+use crate::state::State;
+use crate::stream::{step_lazy, Lazy, LazyStream, Stream, StreamIterator};
+use crate::user::User;
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+
+/// Breadth-first search engine
+///
+/// The engine keeps the pending streams in a FIFO-queue. When a paused goal is expanded, the
+/// resulting stream is put to the back of the queue, and the streams that are already in the
+/// queue are expanded before it. The search therefore proceeds one level of derivation depth
+/// at a time, where the depth of a goal is the number of paused goals that were expanded on the
+/// path to it, and the answers are produced in non-decreasing order of their depth. For example,
+/// when enumerating the sentences of a grammar, the shortest derivations are found first.
+///
+/// The `MPlus`-nodes, including the ones of `dfs`-goals, are searched left to right within a
+/// level. Goals that run sub-searches of their own, such as `conda` and `condu`, run their
+/// sub-searches with the interleaving search. Unlike with the default interleaving engine, all
+/// pending streams of the next level are kept in memory.
+///
+/// The engine is selected by the type of the query:
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::engine::BreadthFirstEngine;
+/// use proto_vulcan::lresult::LResult;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::query::Query;
+/// use proto_vulcan::relation::append;
+///
+/// type Engine = BreadthFirstEngine<DefaultUser>;
+/// type Results = Vec<LResult<DefaultUser, Engine>>;
+///
+/// fn main() {
+///     // Lists that end with 1, shortest first
+///     let x = LTerm::var("x");
+///     let goal = proto_vulcan!(|y| { append(y, [1], x) });
+///     let query = Query::<Results, DefaultUser, Engine>::from_goal(goal, vec![x]);
+///     let lengths: Vec<usize> = query
+///         .run()
+///         .answers()
+///         .take(4)
+///         .map(|a| a.get("x").unwrap().iter().count())
+///         .collect();
+///     assert_eq!(lengths, vec![1, 2, 3, 4]);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct BreadthFirstEngine<U: User> {
+    // True while the queue is being searched. The lazy streams that the goals step by
+    // themselves during the search are stepped with the interleaving search.
+    searching: Cell<bool>,
+    _phantom: PhantomData<U>,
+}
+
+impl<U> Engine<U> for BreadthFirstEngine<U>
+where
+    U: User,
+{
+    fn new() -> Self {
+        BreadthFirstEngine {
+            searching: Cell::new(false),
+            _phantom: PhantomData,
+        }
+    }
+
+    fn step(&self, solver: &Solver<U, Self>, lazy: Lazy<U, Self>) -> Stream<U, Self> {
+        if self.searching.get() {
+            return step_lazy(solver, lazy);
+        }
+
+        match lazy {
+            Lazy::Delay(stream) => stream,
+            Lazy::Iterator(mut iter) => match iter.next(solver) {
+                Some(stream) => Stream::mplus_dfs(stream, LazyStream::iterator(iter)),
+                None => Stream::empty(),
+            },
+            lazy => Stream::iterator(Box::new(BreadthFirstSearch::new(lazy))),
+        }
+    }
+}
+
+#[derive(Derivative)]
+#[derivative(Clone(bound = "U: User"))]
+struct Task<U: User> {
+    stream: Stream<U, BreadthFirstEngine<U>>,
+    continuations: Continuations<U, BreadthFirstEngine<U>>,
+}
+
+#[derive(Derivative)]
+#[derivative(Clone(bound = "U: User"))]
+struct BreadthFirstSearch<U: User> {
+    // The streams of the current level are at the front of the queue, and the streams of the
+    // next level at the back.
+    queue: VecDeque<Task<U>>,
+}
+
+impl<U> BreadthFirstSearch<U>
+where
+    U: User,
+{
+    fn new(root: Lazy<U, BreadthFirstEngine<U>>) -> BreadthFirstSearch<U> {
+        let mut queue = VecDeque::new();
+        queue.push_back(Task {
+            stream: Stream::Lazy(LazyStream(Box::new(root))),
+            continuations: Continuations::new(),
+        });
+        BreadthFirstSearch { queue }
+    }
+
+    // Queues a stream to be expanded on the current level.
+    fn push_front(
+        &mut self,
+        stream: Stream<U, BreadthFirstEngine<U>>,
+        continuations: Continuations<U, BreadthFirstEngine<U>>,
+    ) {
+        self.queue.push_front(Task {
+            stream,
+            continuations,
+        });
+    }
+
+    // Starts a paused goal, and queues the resulting stream to be expanded on the next level.
+    fn pause(
+        &mut self,
+        solver: &Solver<U, BreadthFirstEngine<U>>,
+        state: State<U, BreadthFirstEngine<U>>,
+        goal: Continuation<U, BreadthFirstEngine<U>>,
+        continuations: Continuations<U, BreadthFirstEngine<U>>,
+    ) {
+        self.queue.push_back(Task {
+            stream: goal.start(solver, state),
+            continuations,
+        });
+    }
+
+    // Continues a state produced by a stream with the innermost goal it is bound to. A state
+    // that is not bound to any goal is an answer.
+    fn resume(
+        &mut self,
+        solver: &Solver<U, BreadthFirstEngine<U>>,
+        state: Box<State<U, BreadthFirstEngine<U>>>,
+        continuations: Continuations<U, BreadthFirstEngine<U>>,
+    ) -> Option<Box<State<U, BreadthFirstEngine<U>>>> {
+        match continuations.pop() {
+            Some((goal, rest)) => {
+                self.pause(solver, *state, goal, rest);
+                None
+            }
+            None => Some(state),
+        }
+    }
+
+    fn expand(
+        &mut self,
+        solver: &Solver<U, BreadthFirstEngine<U>>,
+        task: Task<U>,
+    ) -> Option<Box<State<U, BreadthFirstEngine<U>>>> {
+        let Task {
+            stream,
+            continuations,
+        } = task;
+        match stream {
+            Stream::Empty => None,
+            Stream::Unit(state) => self.resume(solver, state, continuations),
+            Stream::Cons(state, lazy) => {
+                self.push_front(Stream::Lazy(lazy), continuations.clone());
+                self.resume(solver, state, continuations)
+            }
+            Stream::Lazy(LazyStream(lazy)) => {
+                match *lazy {
+                    Lazy::MPlus(left, right) | Lazy::MPlusDFS(left, right) => {
+                        self.push_front(Stream::Lazy(right), continuations.clone());
+                        self.push_front(Stream::Lazy(left), continuations);
+                    }
+                    Lazy::Bind(lazy, goal) => {
+                        let continuations = continuations.push(Continuation::Goal(goal));
+                        self.push_front(Stream::Lazy(lazy), continuations);
+                    }
+                    Lazy::BindDFS(lazy, goal) => {
+                        let continuations = continuations.push(Continuation::DFSGoal(goal));
+                        self.push_front(Stream::Lazy(lazy), continuations);
+                    }
+                    Lazy::Pause(state, goal) => {
+                        let goal = Continuation::Goal(goal);
+                        self.pause(solver, *state, goal, continuations);
+                    }
+                    Lazy::PauseDFS(state, goal) => {
+                        let goal = Continuation::DFSGoal(goal);
+                        self.pause(solver, *state, goal, continuations);
+                    }
+                    Lazy::Delay(stream) => self.push_front(stream, continuations),
+                    Lazy::Iterator(mut iter) => {
+                        if let Some(stream) = iter.next(solver) {
+                            let rest = Stream::Lazy(LazyStream::iterator(iter));
+                            self.push_front(rest, continuations.clone());
+                            self.push_front(stream, continuations);
+                        }
+                    }
+                }
+                None
+            }
+        }
+    }
+
+    fn search(
+        &mut self,
+        solver: &Solver<U, BreadthFirstEngine<U>>,
+    ) -> Option<Box<State<U, BreadthFirstEngine<U>>>> {
+        while let Some(task) = self.queue.pop_front() {
+            if let Some(state) = self.expand(solver, task) {
+                return Some(state);
+            }
+        }
+        None
+    }
+}
+
+impl<U> StreamIterator<U, BreadthFirstEngine<U>> for BreadthFirstSearch<U>
+where
+    U: User,
+{
+    fn clone_box(&self) -> Box<dyn StreamIterator<U, BreadthFirstEngine<U>>> {
+        Box::new(self.clone())
+    }
+
+    fn next(
+        &mut self,
+        solver: &Solver<U, BreadthFirstEngine<U>>,
+    ) -> Option<Stream<U, BreadthFirstEngine<U>>> {
+        let searching = solver.engine().searching.replace(true);
+        let answer = self.search(solver);
+        solver.engine().searching.set(searching);
+        answer.map(Stream::unit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BreadthFirstEngine;
+    use crate::goal::InferredGoal;
+    use crate::lresult::LResult;
+    use crate::operator::{anyo, cond, conde, dfs};
+    use crate::prelude::*;
+    use crate::query::Query;
+    use crate::relation::member;
+
+    type Engine = BreadthFirstEngine<DefaultUser>;
+    type Results = Vec<LResult<DefaultUser, Engine>>;
+
+    fn run(
+        goal: Goal<DefaultUser, Engine>,
+        x: LTerm<DefaultUser, Engine>,
+        n: usize,
+    ) -> Vec<LTerm<DefaultUser, Engine>> {
+        let query = Query::<Results, DefaultUser, Engine>::from_goal(goal, vec![x]);
+        query
+            .run()
+            .answers()
+            .take(n)
+            .map(|a| a.get("x").unwrap().clone())
+            .collect()
+    }
+
+    // A left-recursive relation: the recursion is tried before the base case.
+    fn nested<U, E, G>(x: LTerm<U, E>) -> InferredGoal<U, E, G>
+    where
+        U: User,
+        E: crate::engine::Engine<U>,
+        G: AnyGoal<U, E>,
+    {
+        proto_vulcan_closure!(|y| {
+            cond {
+                [nested(y), x == [y]],
+                x == 0,
+            }
+        })
+    }
+
+    #[test]
+    fn test_breadth_first_1() {
+        // The loop and the member-relation produce one answer per level
+        let x = LTerm::var("x");
+        let goal = proto_vulcan!(conde {
+            anyo {
+                x == 1,
+            },
+            member(x, [2, 3, 4]),
+        });
+        let answers = run(goal, x, 6);
+        assert_eq!(
+            answers,
+            vec![
+                lterm!(1),
+                lterm!(2),
+                lterm!(1),
+                lterm!(3),
+                lterm!(1),
+                lterm!(4)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_breadth_first_2() {
+        // A finite search ends
+        let x = LTerm::var("x");
+        let answers = run(proto_vulcan!(member(x, [1, 2, 3])), x, 10);
+        assert_eq!(answers, vec![lterm!(1), lterm!(2), lterm!(3)]);
+
+        let x = LTerm::var("x");
+        let goal = proto_vulcan!([member(x, [1, 2]), member(x, [3, 4])]);
+        assert!(run(goal, x, 10).is_empty());
+    }
+
+    #[test]
+    fn test_breadth_first_3() {
+        // Shallower answers first, also within depth-first goals
+        let x = LTerm::var("x");
+        let answers = run(proto_vulcan!(nested(x)), x, 3);
+        assert_eq!(answers, vec![lterm!(0), lterm!([0]), lterm!([[0]])]);
+
+        let x = LTerm::var("x");
+        let answers = run(proto_vulcan!(dfs { nested(x) }), x, 3);
+        assert_eq!(answers, vec![lterm!(0), lterm!([0]), lterm!([[0]])]);
+    }
+}
@@ -0,0 +1,56 @@
+use crate::engine::Engine;
+use crate::goal::{DFSGoal, Goal};
+use crate::solver::Solver;
+use crate::state::State;
+use crate::stream::Stream;
+use crate::user::User;
+use std::rc::Rc;
+
+/// A goal that the states produced by a stream are bound to.
+#[derive(Derivative)]
+#[derivative(Clone(bound = "U: User"))]
+pub(crate) enum Continuation<U: User, E: Engine<U>> {
+    Goal(Goal<U, E>),
+    DFSGoal(DFSGoal<U, E>),
+}
+
+impl<U, E> Continuation<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub(crate) fn start(&self, solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        match self {
+            Continuation::Goal(goal) => solver.start(goal, state),
+            Continuation::DFSGoal(goal) => solver.start_dfs(goal, state),
+        }
+    }
+}
+
+type ContinuationNode<U, E> = Rc<(Continuation<U, E>, Continuations<U, E>)>;
+
+/// The goals that the states produced by a stream are bound to, innermost first.
+///
+/// The engines that keep their own queue of pending streams flatten the `Bind`-nodes of the
+/// stream into continuations, such that each pending stream can be expanded independently.
+#[derive(Derivative)]
+#[derivative(Clone(bound = "U: User"))]
+pub(crate) struct Continuations<U: User, E: Engine<U>>(Option<ContinuationNode<U, E>>);
+
+impl<U, E> Continuations<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub(crate) fn new() -> Continuations<U, E> {
+        Continuations(None)
+    }
+
+    pub(crate) fn push(self, continuation: Continuation<U, E>) -> Continuations<U, E> {
+        Continuations(Some(Rc::new((continuation, self))))
+    }
+
+    pub(crate) fn pop(&self) -> Option<(Continuation<U, E>, Continuations<U, E>)> {
+        self.0.as_ref().map(|node| node.as_ref().clone())
+    }
+}
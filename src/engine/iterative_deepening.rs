@@ -0,0 +1,374 @@
+use crate::engine::continuation::{Continuation, Continuations};
+use crate::engine::Engine;
+use crate::solver::Solver;
+use crate::state::State;
+use crate::stream::{step_lazy, Lazy, LazyStream, Stream, StreamIterator};
+use crate::user::User;
+use std::cell::Cell;
+use std::marker::PhantomData;
+
+/// Iterative deepening depth-first search engine
+///
+/// The engine searches the stream depth-first in passes. Each pass starts again from the
+/// beginning of the search, and expands paused goals only up to the depth limit of the pass,
+/// where the depth of a goal is the number of paused goals that were expanded on the path to
+/// it. The limit is incremented by one after each pass, and the search ends when a pass did not
+/// reach the limit anywhere. Answers found in the earlier passes are not repeated; a pass
+/// produces only the answers that are exactly at its depth limit, therefore the answers are
+/// produced in the order of their depth.
+///
+/// Plain depth-first search, such as goals within `dfs`-operator or queries created with
+/// `proto_vulcan_query_dfs!`, does not terminate on left-recursive relations: a relation that
+/// calls itself before producing anything keeps descending into its first branch, and the
+/// branches after it are never tried. The depth limit cuts such descent at the end of each pass,
+/// and the search continues from the remaining branches of the pass. Every answer is found in
+/// the pass whose limit equals its depth, regardless of the infinite branches before it.
+///
+/// The price of the completeness is that the shallow part of the search is repeated in every
+/// pass; unlike the default interleaving engine, the memory used by a pass is bounded by the
+/// depth limit. Goals that run sub-searches of their own, such as `conda` and `condu`, run
+/// their sub-searches without the depth limit.
+///
+/// The engine is selected by the type of the query:
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::engine::IterativeDeepeningEngine;
+/// use proto_vulcan::lresult::LResult;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::query::Query;
+/// use proto_vulcan::relation::member;
+///
+/// type Engine = IterativeDeepeningEngine<DefaultUser>;
+/// type Results = Vec<LResult<DefaultUser, Engine>>;
+///
+/// fn main() {
+///     let x = LTerm::var("x");
+///     let goal = proto_vulcan!(member(x, [1, 2, 3]));
+///     let query = Query::<Results, DefaultUser, Engine>::from_goal(goal, vec![x]);
+///     let answers: Vec<_> = query.run().answers().map(|a| a.get("x").cloned()).collect();
+///     assert_eq!(
+///         answers,
+///         vec![Some(lterm!(1)), Some(lterm!(2)), Some(lterm!(3))]
+///     );
+/// }
+/// ```
+#[derive(Debug)]
+pub struct IterativeDeepeningEngine<U: User> {
+    // True while a pass is being searched. The lazy streams that the goals step by themselves
+    // during a pass are stepped with the interleaving search.
+    in_pass: Cell<bool>,
+    _phantom: PhantomData<U>,
+}
+
+impl<U> Engine<U> for IterativeDeepeningEngine<U>
+where
+    U: User,
+{
+    fn new() -> Self {
+        IterativeDeepeningEngine {
+            in_pass: Cell::new(false),
+            _phantom: PhantomData,
+        }
+    }
+
+    fn step(&self, solver: &Solver<U, Self>, lazy: Lazy<U, Self>) -> Stream<U, Self> {
+        if self.in_pass.get() {
+            return step_lazy(solver, lazy);
+        }
+
+        match lazy {
+            Lazy::Delay(stream) => stream,
+            Lazy::Iterator(mut iter) => match iter.next(solver) {
+                Some(stream) => Stream::mplus_dfs(stream, LazyStream::iterator(iter)),
+                None => Stream::empty(),
+            },
+            lazy => Stream::iterator(Box::new(DeepeningSearch::new(lazy))),
+        }
+    }
+}
+
+#[derive(Derivative)]
+#[derivative(Clone(bound = "U: User"))]
+struct Task<U: User> {
+    stream: Stream<U, IterativeDeepeningEngine<U>>,
+    depth: usize,
+    continuations: Continuations<U, IterativeDeepeningEngine<U>>,
+}
+
+#[derive(Derivative)]
+#[derivative(Clone(bound = "U: User"))]
+struct DeepeningSearch<U: User> {
+    root: Lazy<U, IterativeDeepeningEngine<U>>,
+    limit: usize,
+    cutoff: bool,
+    stack: Vec<Task<U>>,
+}
+
+impl<U> DeepeningSearch<U>
+where
+    U: User,
+{
+    fn new(root: Lazy<U, IterativeDeepeningEngine<U>>) -> DeepeningSearch<U> {
+        let mut search = DeepeningSearch {
+            root,
+            limit: 0,
+            cutoff: false,
+            stack: vec![],
+        };
+        search.start_pass();
+        search
+    }
+
+    fn start_pass(&mut self) {
+        self.cutoff = false;
+        let root = Stream::Lazy(LazyStream(Box::new(self.root.clone())));
+        self.push(root, 0, Continuations::new());
+    }
+
+    fn push(
+        &mut self,
+        stream: Stream<U, IterativeDeepeningEngine<U>>,
+        depth: usize,
+        continuations: Continuations<U, IterativeDeepeningEngine<U>>,
+    ) {
+        self.stack.push(Task {
+            stream,
+            depth,
+            continuations,
+        });
+    }
+
+    fn search(
+        &mut self,
+        solver: &Solver<U, IterativeDeepeningEngine<U>>,
+    ) -> Option<Box<State<U, IterativeDeepeningEngine<U>>>> {
+        loop {
+            let task = match self.stack.pop() {
+                Some(task) => task,
+                None if self.cutoff => {
+                    // Start the next pass from the beginning with a deeper limit.
+                    self.limit += 1;
+                    self.start_pass();
+                    continue;
+                }
+                None => return None,
+            };
+
+            if let Some(state) = self.expand(solver, task) {
+                return Some(state);
+            }
+        }
+    }
+
+    fn expand(
+        &mut self,
+        solver: &Solver<U, IterativeDeepeningEngine<U>>,
+        task: Task<U>,
+    ) -> Option<Box<State<U, IterativeDeepeningEngine<U>>>> {
+        let Task {
+            stream,
+            depth,
+            continuations,
+        } = task;
+        match stream {
+            Stream::Empty => None,
+            Stream::Unit(state) => self.resume(solver, state, depth, continuations),
+            Stream::Cons(state, lazy) => {
+                self.push(Stream::Lazy(lazy), depth, continuations.clone());
+                self.resume(solver, state, depth, continuations)
+            }
+            Stream::Lazy(LazyStream(lazy)) => {
+                match *lazy {
+                    Lazy::MPlus(left, right) | Lazy::MPlusDFS(left, right) => {
+                        self.push(Stream::Lazy(right), depth, continuations.clone());
+                        self.push(Stream::Lazy(left), depth, continuations);
+                    }
+                    Lazy::Bind(lazy, goal) => {
+                        let continuations = continuations.push(Continuation::Goal(goal));
+                        self.push(Stream::Lazy(lazy), depth, continuations);
+                    }
+                    Lazy::BindDFS(lazy, goal) => {
+                        let continuations = continuations.push(Continuation::DFSGoal(goal));
+                        self.push(Stream::Lazy(lazy), depth, continuations);
+                    }
+                    Lazy::Pause(state, goal) => {
+                        let goal = Continuation::Goal(goal);
+                        self.pause(solver, *state, goal, depth, continuations);
+                    }
+                    Lazy::PauseDFS(state, goal) => {
+                        let goal = Continuation::DFSGoal(goal);
+                        self.pause(solver, *state, goal, depth, continuations);
+                    }
+                    Lazy::Delay(stream) => self.push(stream, depth, continuations),
+                    Lazy::Iterator(mut iter) => {
+                        if let Some(stream) = iter.next(solver) {
+                            let rest = Stream::Lazy(LazyStream::iterator(iter));
+                            self.push(rest, depth, continuations.clone());
+                            self.push(stream, depth, continuations);
+                        }
+                    }
+                }
+                None
+            }
+        }
+    }
+
+    // Continues a state produced by a stream with the innermost goal it is bound to. A state
+    // that is not bound to any goal is an answer.
+    fn resume(
+        &mut self,
+        solver: &Solver<U, IterativeDeepeningEngine<U>>,
+        state: Box<State<U, IterativeDeepeningEngine<U>>>,
+        depth: usize,
+        continuations: Continuations<U, IterativeDeepeningEngine<U>>,
+    ) -> Option<Box<State<U, IterativeDeepeningEngine<U>>>> {
+        match continuations.pop() {
+            Some((goal, rest)) => {
+                self.pause(solver, *state, goal, depth, rest);
+                None
+            }
+            // Answers at smaller depths were produced by the earlier passes.
+            None if depth == self.limit => Some(state),
+            None => None,
+        }
+    }
+
+    fn pause(
+        &mut self,
+        solver: &Solver<U, IterativeDeepeningEngine<U>>,
+        state: State<U, IterativeDeepeningEngine<U>>,
+        goal: Continuation<U, IterativeDeepeningEngine<U>>,
+        depth: usize,
+        continuations: Continuations<U, IterativeDeepeningEngine<U>>,
+    ) {
+        if depth == self.limit {
+            self.cutoff = true;
+        } else {
+            let stream = goal.start(solver, state);
+            self.push(stream, depth + 1, continuations);
+        }
+    }
+}
+
+impl<U> StreamIterator<U, IterativeDeepeningEngine<U>> for DeepeningSearch<U>
+where
+    U: User,
+{
+    fn clone_box(&self) -> Box<dyn StreamIterator<U, IterativeDeepeningEngine<U>>> {
+        Box::new(self.clone())
+    }
+
+    fn next(
+        &mut self,
+        solver: &Solver<U, IterativeDeepeningEngine<U>>,
+    ) -> Option<Stream<U, IterativeDeepeningEngine<U>>> {
+        let in_pass = solver.engine().in_pass.replace(true);
+        let answer = self.search(solver);
+        solver.engine().in_pass.set(in_pass);
+        answer.map(Stream::unit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IterativeDeepeningEngine;
+    use crate::goal::InferredGoal;
+    use crate::lresult::LResult;
+    use crate::operator::{cond, condu, dfs};
+    use crate::prelude::*;
+    use crate::query::Query;
+    use crate::relation::{append, member};
+
+    type Engine = IterativeDeepeningEngine<DefaultUser>;
+    type Results = Vec<LResult<DefaultUser, Engine>>;
+
+    // A left-recursive relation: the recursion is tried before the base case.
+    fn nested<U, E, G>(x: LTerm<U, E>) -> InferredGoal<U, E, G>
+    where
+        U: User,
+        E: crate::engine::Engine<U>,
+        G: AnyGoal<U, E>,
+    {
+        proto_vulcan_closure!(|y| {
+            cond {
+                [nested(y), x == [y]],
+                x == 0,
+            }
+        })
+    }
+
+    fn run(
+        goal: Goal<DefaultUser, Engine>,
+        x: LTerm<DefaultUser, Engine>,
+    ) -> Vec<LTerm<DefaultUser, Engine>> {
+        let query = Query::<Results, DefaultUser, Engine>::from_goal(goal, vec![x]);
+        query
+            .run()
+            .answers()
+            .map(|a| a.get("x").unwrap().clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_iterative_deepening_1() {
+        // Left recursion produces answers in the order of depth
+        let x = LTerm::var("x");
+        let query =
+            Query::<Results, DefaultUser, Engine>::from_goal(proto_vulcan!(nested(x)), vec![x]);
+        let answers: Vec<_> = query
+            .run()
+            .answers()
+            .take(3)
+            .map(|a| a.get("x").unwrap().clone())
+            .collect();
+        assert_eq!(answers, vec![lterm!(0), lterm!([0]), lterm!([[0]])]);
+    }
+
+    #[test]
+    fn test_iterative_deepening_2() {
+        // Left recursion in depth-first goals
+        let x = LTerm::var("x");
+        let query = Query::<Results, DefaultUser, Engine>::from_goal(
+            proto_vulcan!(dfs { nested(x) }),
+            vec![x],
+        );
+        let answers: Vec<_> = query
+            .run()
+            .answers()
+            .take(3)
+            .map(|a| a.get("x").unwrap().clone())
+            .collect();
+        assert_eq!(answers, vec![lterm!(0), lterm!([0]), lterm!([[0]])]);
+    }
+
+    #[test]
+    fn test_iterative_deepening_3() {
+        // A finite search ends, and each answer is produced once
+        let x = LTerm::var("x");
+        let mut answers = run(proto_vulcan!(|y| { append(x, y, [1, 2, 3]) }), x);
+        answers.sort_by_key(|x| x.iter().count());
+        assert_eq!(
+            answers,
+            vec![lterm!([]), lterm!([1]), lterm!([1, 2]), lterm!([1, 2, 3])]
+        );
+
+        let x = LTerm::var("x");
+        assert!(run(proto_vulcan!([member(x, [1, 2]), member(x, [3, 4])]), x).is_empty());
+    }
+
+    #[test]
+    fn test_iterative_deepening_4() {
+        // Goals that search by themselves
+        let x = LTerm::var("x");
+        let answers = run(proto_vulcan!(condu { member(x, [1, 2, 3]) }), x);
+        assert_eq!(answers, vec![lterm!(1)]);
+
+        let x = LTerm::var("x");
+        let y = x.clone();
+        let goal = proto_vulcan!(conde for v in vec![1, 2, 3] { y == v });
+        let answers = run(goal, x);
+        assert_eq!(answers, vec![lterm!(1), lterm!(2), lterm!(3)]);
+    }
+}
@@ -2,6 +2,17 @@ use crate::solver::Solver;
 use crate::stream::{Lazy, Stream, StreamEngine};
 use crate::user::User;
 
+mod breadth_first;
+mod continuation;
+mod iterative_deepening;
+#[cfg(feature = "parallel")]
+mod parallel;
+
+pub use breadth_first::BreadthFirstEngine;
+pub use iterative_deepening::IterativeDeepeningEngine;
+#[cfg(feature = "parallel")]
+pub use parallel::ParallelEngine;
+
 pub type DefaultEngine<U> = StreamEngine<U>;
 
 pub trait Engine<U>: Sized + 'static
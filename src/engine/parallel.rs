@@ -0,0 +1,263 @@
+use crate::engine::Engine;
+use crate::query::{Answer, Query, QueryResult};
+use crate::solver::Solver;
+use crate::stream::{step_lazy, Lazy, LazyStream, Stream};
+use crate::user::{DefaultUser, User};
+use std::cell::Cell;
+use std::marker::PhantomData;
+use std::thread;
+
+thread_local! {
+    // The index of the worker running on this thread, and the number of workers.
+    static WORKER: Cell<(usize, usize)> = const { Cell::new((0, 1)) };
+}
+
+/// Parallel search engine
+///
+/// The states and goals of a search share their data with reference counting that is local to
+/// a thread, therefore a search cannot be moved between threads. Instead, the engine splits
+/// the search by recomputation: each worker thread builds and runs the whole query, and when
+/// the search reaches an `MPlus`-node, the workers that have so far made the same choices are
+/// split into two groups, one of which continues with the left branch and the other with the
+/// right branch. A worker that is alone in its group searches the rest of its branch like the
+/// default interleaving engine. The search is deterministic, therefore the workers of a group
+/// reach the same `MPlus`-nodes. A solution that is found before its group is split to single
+/// workers is produced by the first worker of the group, such that every solution is produced
+/// by exactly one worker.
+///
+/// The query is run in parallel with [`ParallelEngine::run`]. When a query is run with
+/// `query.run()` on a single thread, the engine searches like the default engine.
+///
+/// Branches are split only in the search of the query itself, not within the sub-searches of
+/// goals such as `conda`, `condu` and `onceo`, which commit to the first solution of their
+/// sub-search.
+#[derive(Debug)]
+pub struct ParallelEngine<U: User> {
+    worker: usize,
+    // The group of workers that have made the same choices as this worker.
+    group: Cell<(usize, usize)>,
+    // True while a goal is being solved; the lazy streams that are stepped by the goal are not
+    // split.
+    solving: Cell<bool>,
+    // Number of nested `step`-calls in progress.
+    depth: Cell<usize>,
+    _phantom: PhantomData<U>,
+}
+
+impl ParallelEngine<DefaultUser> {
+    /// Runs the query created by `make_query` with `workers` threads, and returns the
+    /// answers of all workers converted with `f`.
+    ///
+    /// The query must have a finite number of solutions. The answers contain the same
+    /// solutions as the answers of `query.run()`, but their order is not specified.
+    ///
+    /// # Example
+    /// ```rust
+    /// extern crate proto_vulcan;
+    /// use proto_vulcan::engine::ParallelEngine;
+    /// use proto_vulcan::lresult::LResult;
+    /// use proto_vulcan::prelude::*;
+    /// use proto_vulcan::query::Query;
+    /// use proto_vulcan::relation::member;
+    ///
+    /// type Engine = ParallelEngine<DefaultUser>;
+    /// type Results = Vec<LResult<DefaultUser, Engine>>;
+    ///
+    /// fn main() {
+    ///     let make_query = || {
+    ///         let x = LTerm::var("x");
+    ///         let goal = proto_vulcan!(member(x, [1, 2, 3, 4]));
+    ///         Query::<Results, DefaultUser, Engine>::from_goal(goal, vec![x])
+    ///     };
+    ///     let mut xs = Engine::run(4, make_query, |answer| {
+    ///         answer.get("x").unwrap().get_number().unwrap()
+    ///     });
+    ///     xs.sort();
+    ///     assert_eq!(xs, vec![1, 2, 3, 4]);
+    /// }
+    /// ```
+    pub fn run<R, Q, F, T>(workers: usize, make_query: Q, f: F) -> Vec<T>
+    where
+        R: QueryResult<DefaultUser, ParallelEngine<DefaultUser>>,
+        Q: Fn() -> Query<R, DefaultUser, ParallelEngine<DefaultUser>> + Send + Sync,
+        F: Fn(Answer<DefaultUser, ParallelEngine<DefaultUser>>) -> T + Send + Sync,
+        T: Send,
+    {
+        assert!(workers > 0, "at least one worker is required");
+        let make_query = &make_query;
+        let f = &f;
+        thread::scope(|scope| {
+            let handles: Vec<_> = (0..workers)
+                .map(|worker| {
+                    scope.spawn(move || {
+                        WORKER.with(|w| w.set((worker, workers)));
+                        let query = make_query();
+                        let answers: Vec<T> = query.run().answers().map(f).collect();
+                        WORKER.with(|w| w.set((0, 1)));
+                        answers
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap())
+                .collect()
+        })
+    }
+}
+
+impl<U> Engine<U> for ParallelEngine<U>
+where
+    U: User,
+{
+    fn new() -> Self {
+        let (worker, workers) = WORKER.with(|w| w.get());
+        ParallelEngine {
+            worker,
+            group: Cell::new((0, workers)),
+            solving: Cell::new(false),
+            depth: Cell::new(0),
+            _phantom: PhantomData,
+        }
+    }
+
+    fn step(&self, solver: &Solver<U, Self>, lazy: Lazy<U, Self>) -> Stream<U, Self> {
+        let is_top = self.depth.get() == 0 && !self.solving.get();
+        self.depth.set(self.depth.get() + 1);
+        let stream = self.step_lazy(solver, lazy);
+        self.depth.set(self.depth.get() - 1);
+
+        let (lo, hi) = self.group.get();
+        if is_top && hi - lo > 1 && self.worker != lo {
+            // The solutions that the whole group has found are produced by the first worker
+            // of the group only.
+            match stream {
+                Stream::Unit(_) => Stream::empty(),
+                Stream::Cons(_, lazy) => Stream::Lazy(lazy),
+                stream => stream,
+            }
+        } else {
+            stream
+        }
+    }
+}
+
+impl<U> ParallelEngine<U>
+where
+    U: User,
+{
+    fn step_lazy(&self, solver: &Solver<U, Self>, lazy: Lazy<U, Self>) -> Stream<U, Self> {
+        match lazy {
+            Lazy::MPlus(left, right) | Lazy::MPlusDFS(left, right) if self.is_split() => {
+                // The left branch goes to the lower half of the group, and the right branch
+                // to the upper half.
+                let (lo, hi) = self.group.get();
+                let mid = lo + (hi - lo) / 2;
+                if self.worker < mid {
+                    self.group.set((lo, mid));
+                    self.step_lazy(solver, *left.0)
+                } else {
+                    self.group.set((mid, hi));
+                    self.step_lazy(solver, *right.0)
+                }
+            }
+            Lazy::Pause(state, goal) => {
+                let solving = self.solving.replace(true);
+                let stream = solver.start(&goal, *state);
+                self.solving.set(solving);
+                stream
+            }
+            Lazy::PauseDFS(state, goal) => {
+                let solving = self.solving.replace(true);
+                let stream = solver.start_dfs(&goal, *state);
+                self.solving.set(solving);
+                stream
+            }
+            Lazy::Iterator(mut iter) => {
+                let solving = self.solving.replace(true);
+                let stream = iter.next(solver);
+                self.solving.set(solving);
+                match stream {
+                    Some(stream) => Stream::mplus_dfs(stream, LazyStream::iterator(iter)),
+                    None => Stream::empty(),
+                }
+            }
+            lazy => step_lazy(solver, lazy),
+        }
+    }
+
+    fn is_split(&self) -> bool {
+        let (lo, hi) = self.group.get();
+        !self.solving.get() && hi - lo > 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ParallelEngine, WORKER};
+    use crate::lresult::LResult;
+    use crate::prelude::*;
+    use crate::query::{Answer, Query};
+    use crate::relation::{append, member};
+
+    type Engine = ParallelEngine<DefaultUser>;
+    type Results = Vec<LResult<DefaultUser, Engine>>;
+
+    fn append_query() -> Query<Results, DefaultUser, Engine> {
+        let x = LTerm::var("x");
+        let y = LTerm::var("y");
+        let goal = proto_vulcan!(append(x, y, [1, 2, 3, 4, 5, 6, 7, 8]));
+        Query::from_goal(goal, vec![x, y])
+    }
+
+    fn member_query() -> Query<Results, DefaultUser, Engine> {
+        let x = LTerm::var("x");
+        let y = LTerm::var("y");
+        let goal = proto_vulcan!([member(x, [1, 2, 3, 4, 5]), member(y, [x, 6, 7])]);
+        Query::from_goal(goal, vec![x, y])
+    }
+
+    fn show(answer: Answer<DefaultUser, Engine>) -> String {
+        format!("{} {}", answer.get("x").unwrap(), answer.get("y").unwrap())
+    }
+
+    fn sorted_answers<Q>(workers: usize, make_query: Q) -> Vec<String>
+    where
+        Q: Fn() -> Query<Results, DefaultUser, Engine> + Send + Sync,
+    {
+        let mut answers = Engine::run(workers, make_query, show);
+        answers.sort();
+        answers
+    }
+
+    #[test]
+    fn test_parallel_1() {
+        // Single-threaded run searches like the default engine
+        let mut sequential: Vec<String> = append_query().run().answers().map(show).collect();
+        assert_eq!(sequential.len(), 9);
+        sequential.sort();
+        for workers in 1..=5 {
+            assert_eq!(sorted_answers(workers, append_query), sequential);
+        }
+    }
+
+    #[test]
+    fn test_parallel_2() {
+        let mut sequential: Vec<String> = member_query().run().answers().map(show).collect();
+        assert_eq!(sequential.len(), 15);
+        sequential.sort();
+        for workers in 1..=8 {
+            assert_eq!(sorted_answers(workers, member_query), sequential);
+        }
+    }
+
+    #[test]
+    fn test_parallel_3() {
+        // The solutions are divided between the workers
+        let mut workers = Engine::run(4, append_query, |_| WORKER.with(|w| w.get().0));
+        assert_eq!(workers.len(), 9);
+        workers.sort_unstable();
+        workers.dedup();
+        assert!(workers.len() > 1);
+    }
+}
@@ -130,6 +130,25 @@ where
     }
 }
 
+#[cfg(feature = "core")]
+impl<U, E> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    /// Returns a conjunction of the goal and `other`. Same as `[self, other]` in the
+    /// proto-vulcan syntax.
+    pub fn and(self, other: Goal<U, E>) -> Goal<U, E> {
+        crate::operator::conj::Conj::new(self, other)
+    }
+
+    /// Returns a disjunction of the goal and `other`. Same as `conde { self, other }` in the
+    /// proto-vulcan syntax.
+    pub fn or(self, other: Goal<U, E>) -> Goal<U, E> {
+        crate::operator::disj::Disj::new(self, other)
+    }
+}
+
 /// Depth-first searched goal
 #[derive(Derivative)]
 #[derivative(Debug(bound = "U: User"), Clone(bound = "U: User"))]
@@ -196,6 +215,23 @@ where
     }
 }
 
+#[cfg(feature = "core")]
+impl<U, E> DFSGoal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    /// Returns a conjunction of the goal and `other`.
+    pub fn and(self, other: DFSGoal<U, E>) -> DFSGoal<U, E> {
+        crate::operator::conj::DFSConj::new(self, other)
+    }
+
+    /// Returns a depth-first disjunction of the goal and `other`.
+    pub fn or(self, other: DFSGoal<U, E>) -> DFSGoal<U, E> {
+        crate::operator::disj::DFSDisj::new(self, other)
+    }
+}
+
 impl<U, E> Into<Goal<U, E>> for DFSGoal<U, E>
 where
     U: User,
@@ -309,7 +345,10 @@ where
 mod test {
     use super::AnyGoal;
     use crate::engine::{DefaultEngine, Engine};
+    use crate::lresult::LResult;
     use crate::prelude::*;
+    use crate::query::{Query, QueryResult};
+    use crate::relation::member;
     use crate::solver::Solve;
     use crate::state::State;
     use crate::stream::Stream;
@@ -345,4 +384,63 @@ mod test {
         assert!(!g.is_succeed());
         assert!(!g.is_fail());
     }
+
+    type TestG = Goal<DefaultUser, DefaultEngine<DefaultUser>>;
+
+    struct R(LResult<DefaultUser, DefaultEngine<DefaultUser>>);
+
+    impl QueryResult for R {
+        fn from_vec(mut v: Vec<LResult<DefaultUser, DefaultEngine<DefaultUser>>>) -> R {
+            R(v.remove(0))
+        }
+    }
+
+    #[test]
+    fn test_goal_and_1() {
+        let q = LTerm::var("q");
+        let a: TestG = proto_vulcan!(member(q, [1, 2, 3, 4]));
+        let b: TestG = proto_vulcan!(q != 2);
+        let c: TestG = proto_vulcan!(q != 4);
+        let query = Query::<R>::new(vec![q], a.and(b).and(c));
+        let composed: Vec<LTerm> = query.run().map(|r| r.0 .0).collect();
+
+        let query = proto_vulcan_query!(|q| {
+            member(q, [1, 2, 3, 4]),
+            q != 2,
+            q != 4,
+        });
+        let expected: Vec<LTerm> = query.run().map(|r| r.q.0).collect();
+        assert_eq!(composed, expected);
+        assert_eq!(composed, vec![lterm!(1), lterm!(3)]);
+    }
+
+    #[test]
+    fn test_goal_or_1() {
+        let q = LTerm::var("q");
+        let a: TestG = proto_vulcan!(member(q, [1, 2, 3]));
+        let b: TestG = proto_vulcan!(member(q, [4, 5, 6]));
+        let query = Query::<R>::new(vec![q], a.or(b));
+        let composed: Vec<LTerm> = query.run().map(|r| r.0 .0).collect();
+
+        let query = proto_vulcan_query!(|q| {
+            conde {
+                member(q, [1, 2, 3]),
+                member(q, [4, 5, 6]),
+            }
+        });
+        let expected: Vec<LTerm> = query.run().map(|r| r.q.0).collect();
+        assert_eq!(composed, expected);
+    }
+
+    #[test]
+    fn test_goal_and_or_1() {
+        let q = LTerm::var("q");
+        let a: TestG = proto_vulcan!(q == 1);
+        let b: TestG = proto_vulcan!(q == 2);
+        let c: TestG = proto_vulcan!(q != 1);
+        let query = Query::<R>::new(vec![q], a.or(b).and(c));
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().0, 2);
+        assert!(iter.next().is_none());
+    }
 }
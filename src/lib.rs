@@ -5,7 +5,7 @@ extern crate self as proto_vulcan;
 extern crate proto_vulcan_macros;
 
 pub use proto_vulcan_macros::{
-    compound, lterm, proto_vulcan, proto_vulcan_closure, proto_vulcan_query,
+    compound, lterm, proto_vulcan, proto_vulcan_closure, proto_vulcan_query, proto_vulcan_query_dfs,
 };
 
 #[macro_use]
@@ -67,6 +67,7 @@ pub mod prelude {
 
     pub use proto_vulcan_macros::{
         compound, lterm, proto_vulcan, proto_vulcan_closure, proto_vulcan_query,
+        proto_vulcan_query_dfs,
     };
 
     pub use crate::compound::CompoundTerm;
@@ -81,3 +82,47 @@ pub mod prelude {
     // conde is the only non-built-in operator exported by default.
     pub use crate::operator::conde::conde;
 }
+
+/// Finite-domain relations in one module.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::clpfd_prelude::*;
+/// use proto_vulcan::prelude::*;
+/// fn main() {
+///     let query = proto_vulcan_query!(|x, y| {
+///         infdrange([x, y], &(0..=5)),
+///         plusfd(x, y, 5),
+///         ltfd(x, y),
+///         diseqfd(x, 0),
+///     });
+///     let mut iter = query.run();
+///     let result = iter.next().unwrap();
+///     assert_eq!(result.x, 1);
+///     assert_eq!(result.y, 4);
+///     let result = iter.next().unwrap();
+///     assert_eq!(result.x, 2);
+///     assert_eq!(result.y, 3);
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+#[cfg(feature = "clpfd")]
+pub mod clpfd_prelude {
+    pub use crate::operator::label_ff;
+    pub use crate::relation::clpfd::absfd::absfd;
+    pub use crate::relation::clpfd::diseqfd::diseqfd;
+    pub use crate::relation::clpfd::disjointo::disjointo;
+    pub use crate::relation::clpfd::distinctfd::distinctfd;
+    pub use crate::relation::clpfd::divfd::divfd;
+    pub use crate::relation::clpfd::infd::{infd, infdrange, infdrange_exclusive};
+    pub use crate::relation::clpfd::ltefd::ltefd;
+    pub use crate::relation::clpfd::ltfd::ltfd;
+    pub use crate::relation::clpfd::minusfd::minusfd;
+    pub use crate::relation::clpfd::modfd::modfd;
+    pub use crate::relation::clpfd::plusfd::plusfd;
+    pub use crate::relation::clpfd::reifyeq::reifyeq;
+    pub use crate::relation::clpfd::scaleo::scaleo;
+    pub use crate::relation::clpfd::sumfd::sumfd;
+    pub use crate::relation::clpfd::timesfd::timesfd;
+}
@@ -4,19 +4,36 @@ use crate::engine::Engine;
 use crate::relation::diseq::DisequalityConstraint;
 use crate::state::constraint::store::ConstraintStore;
 use crate::state::constraint::Constraint;
+use crate::state::FiniteDomain;
 use crate::user::User;
 use std::fmt;
 use std::ops::Deref;
 use std::rc::Rc;
 
+/// A single variable of a query answer.
+///
+/// Consists of the reified term and the residual constraints. The finite domain of the
+/// variable is available with [`fd_domain`](LResult::fd_domain).
 #[derive(Clone, Debug)]
-pub struct LResult<U: User, E: Engine<U>>(pub LTerm<U, E>, pub Rc<ConstraintStore<U, E>>);
+pub struct LResult<U: User, E: Engine<U>>(
+    pub LTerm<U, E>,
+    pub Rc<ConstraintStore<U, E>>,
+    Option<Rc<FiniteDomain>>,
+);
 
 impl<U, E> LResult<U, E>
 where
     U: User,
     E: Engine<U>,
 {
+    pub(crate) fn new(
+        term: LTerm<U, E>,
+        cstore: Rc<ConstraintStore<U, E>>,
+        domain: Option<Rc<FiniteDomain>>,
+    ) -> LResult<U, E> {
+        LResult(term, cstore, domain)
+    }
+
     /// Check if the wrapped LTerm is an Any-variable with constraints such that it cannot be
     /// the `exception`.
     pub fn is_any_except<T>(&self, exception: &T) -> bool
@@ -44,6 +61,15 @@ where
         self.constraints().any(|_| true)
     }
 
+    /// Returns the values of the finite domain of the query variable, if the variable was
+    /// constrained to a finite domain when the goal of the query succeeded.
+    ///
+    /// Queries built with `proto_vulcan_query!` enumerate the domains into answers, and then
+    /// the wrapped LTerm is one of the returned values.
+    pub fn fd_domain(&self) -> Option<Vec<isize>> {
+        self.2.as_ref().map(|domain| domain.iter().collect())
+    }
+
     /// Returns iterator to constraints that refer to the wrapped LTerm.
     pub fn constraints<'a>(&'a self) -> impl Iterator<Item = &'a Rc<dyn Constraint<U, E>>> {
         let anyvars = self.0.anyvars();
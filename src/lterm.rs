@@ -12,6 +12,12 @@ use std::vec::Vec;
 
 pub use crate::lvalue::LValue;
 
+mod parse;
+pub use parse::{ParseError, MAX_PARSE_DEPTH};
+
+mod symbol;
+pub use symbol::Symbol;
+
 static UNIQUE_ID_COUNTER: AtomicUsize = AtomicUsize::new(0);
 
 #[derive(Copy, Clone, Hash, PartialEq, Eq, Debug)]
@@ -42,7 +48,7 @@ where
     Val(LValue),
 
     /// Variable (uid, name)
-    Var(VarID, &'static str),
+    Var(VarID, Symbol),
 
     // User defined item
     User(<U as User>::UserTerm),
@@ -81,8 +87,13 @@ where
         Rc::ptr_eq(&this.inner, &other.inner)
     }
 
-    pub fn var(name: &'static str) -> LTerm<U, E> {
-        if name == "_" {
+    /// Constructs a new variable with the given name.
+    ///
+    /// The name can be any string, such as a `&'static str` or a `String` that is created at
+    /// runtime; it is interned into a `Symbol`.
+    pub fn var<S: Into<Symbol>>(name: S) -> LTerm<U, E> {
+        let name = name.into();
+        if name.is_any() {
             panic!("Error: Invalid variable name. Name \"_\" is reserved for any-variables.")
         }
 
@@ -93,7 +104,7 @@ where
 
     pub fn any() -> LTerm<U, E> {
         LTerm {
-            inner: Rc::new(LTermInner::Var(VarID::new(), "_")),
+            inner: Rc::new(LTermInner::Var(VarID::new(), Symbol::ANY)),
         }
     }
 
@@ -145,7 +156,7 @@ where
     }
 
     pub fn into_inner(self) -> Rc<LTermInner<U, E>> {
-        self.inner
+        Rc::clone(&self.inner)
     }
 
     /// Construct a list cell
@@ -232,7 +243,7 @@ where
 
     pub fn get_name(&self) -> Option<&str> {
         match self.as_ref() {
-            LTermInner::Var(_, name) => Some(name),
+            LTermInner::Var(_, name) => Some(name.as_str()),
             _ => None,
         }
     }
@@ -251,6 +262,30 @@ where
         }
     }
 
+    pub fn is_float(&self) -> bool {
+        matches!(self.as_ref(), LTermInner::Val(LValue::Float(_)))
+    }
+
+    pub fn get_float(&self) -> Option<f64> {
+        match self.as_ref() {
+            LTermInner::Val(LValue::Float(u)) => Some(*u),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "bigint")]
+    pub fn is_bigint(&self) -> bool {
+        matches!(self.as_ref(), LTermInner::Val(LValue::BigInt(_)))
+    }
+
+    #[cfg(feature = "bigint")]
+    pub fn get_bigint(&self) -> Option<&num_bigint::BigInt> {
+        match self.as_ref() {
+            LTermInner::Val(LValue::BigInt(u)) => Some(u),
+            _ => None,
+        }
+    }
+
     pub fn is_var(&self) -> bool {
         match self.as_ref() {
             LTermInner::<U, E>::Var(_, _) => true,
@@ -260,7 +295,7 @@ where
 
     pub fn is_any(&self) -> bool {
         match self.as_ref() {
-            LTermInner::Var(_, "_") => true,
+            LTermInner::Var(_, name) => name.is_any(),
             _ => false,
         }
     }
@@ -319,6 +354,31 @@ where
         }
     }
 
+    /// Returns true if the term is a list that ends with an empty list.
+    ///
+    /// Only the structure of the term itself is considered; a list with a variable tail is not
+    /// proper, even if the variable is bound to a list in some substitution.
+    pub fn is_proper_list(&self) -> bool {
+        self.len().is_some()
+    }
+
+    /// Returns the number of elements of a proper list, or `None` if the term is not a
+    /// proper list. The spine of the list is iterated, and elements are not walked.
+    pub fn len(&self) -> Option<usize> {
+        let mut n = 0;
+        let mut tail = self;
+        loop {
+            match tail.as_ref() {
+                LTermInner::Empty => return Some(n),
+                LTermInner::Cons(_, rest) => {
+                    n += 1;
+                    tail = rest;
+                }
+                _ => return None,
+            }
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
         match self.as_ref() {
             LTermInner::Empty => true,
@@ -333,6 +393,25 @@ where
         }
     }
 
+    /// Returns true if the term does not contain any variables or projections. Only the term
+    /// itself is considered, therefore the term should be walked with `walk_star` first.
+    pub fn is_ground(&self) -> bool {
+        let mut rest = self;
+        loop {
+            match rest.as_ref() {
+                LTermInner::Var(_, _) | LTermInner::Projection(_) => return false,
+                LTermInner::Cons(head, tail) => {
+                    if !head.is_ground() {
+                        return false;
+                    }
+                    rest = tail;
+                }
+                LTermInner::Compound(object) => return is_ground_compound(object.as_ref()),
+                _ => return true,
+            }
+        }
+    }
+
     pub fn head(&self) -> Option<&LTerm<U, E>> {
         match self.as_ref() {
             LTermInner::Cons(head, _) => Some(head),
@@ -391,6 +470,17 @@ where
     }
 }
 
+fn is_ground_compound<U, E>(object: &dyn CompoundObject<U, E>) -> bool
+where
+    U: User,
+    E: Engine<U>,
+{
+    object.children().all(|child| match child.as_term() {
+        Some(term) => term.is_ground(),
+        None => is_ground_compound(child),
+    })
+}
+
 impl<U, E> From<Rc<dyn CompoundObject<U, E>>> for LTerm<U, E>
 where
     U: User,
@@ -423,6 +513,16 @@ where
     }
 }
 
+impl<U, E> From<LValue> for LTerm<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn from(u: LValue) -> LTerm<U, E> {
+        LTerm::from(LTermInner::Val(u))
+    }
+}
+
 impl<U, E> From<isize> for LTerm<U, E>
 where
     U: User,
@@ -433,6 +533,27 @@ where
     }
 }
 
+impl<U, E> From<f64> for LTerm<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn from(u: f64) -> LTerm<U, E> {
+        LTerm::from(LTermInner::Val(LValue::Float(u)))
+    }
+}
+
+#[cfg(feature = "bigint")]
+impl<U, E> From<num_bigint::BigInt> for LTerm<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn from(u: num_bigint::BigInt) -> LTerm<U, E> {
+        LTerm::from(LTermInner::Val(LValue::BigInt(u)))
+    }
+}
+
 impl<U, E> From<bool> for LTerm<U, E>
 where
     U: User,
@@ -473,6 +594,18 @@ where
     }
 }
 
+/// Converts a vector of values into a proper list, e.g. `LTerm::from(vec![1, 2, 3])`.
+impl<U, E, T> From<Vec<T>> for LTerm<U, E>
+where
+    U: User,
+    E: Engine<U>,
+    T: Into<LTerm<U, E>>,
+{
+    fn from(u: Vec<T>) -> LTerm<U, E> {
+        u.into_iter().collect()
+    }
+}
+
 impl<U, E> AsRef<LTermInner<U, E>> for LTerm<U, E>
 where
     U: User,
@@ -565,7 +698,7 @@ where
                     write!(f, "]")
                 }
             }
-            LTermInner::Compound(compound_term) => write!(f, "{:?}", compound_term),
+            LTermInner::Compound(compound_term) => compound_term.compound_display(f),
         }
     }
 }
@@ -693,6 +826,32 @@ where
     }
 }
 
+impl<U, E> PartialEq<f64> for LTerm<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn eq(&self, other: &f64) -> bool {
+        match self.as_ref() {
+            LTermInner::Val(LValue::Float(x)) => x == other,
+            _ => false,
+        }
+    }
+}
+
+impl<U, E> PartialEq<LTerm<U, E>> for f64
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn eq(&self, other: &LTerm<U, E>) -> bool {
+        match other.as_ref() {
+            LTermInner::Val(LValue::Float(x)) => x == self,
+            _ => false,
+        }
+    }
+}
+
 impl<U, E> PartialEq<char> for LTerm<U, E>
 where
     U: User,
@@ -814,18 +973,43 @@ where
     }
 }
 
-impl<U, E> FromIterator<LTerm<U, E>> for LTerm<U, E>
+/// Drops the cells of a list one at a time.
+///
+/// Dropping the cells recursively would overflow the stack for long lists. Instead, the tail
+/// of each list cell that is not shared is unlinked from the cell before the cell is dropped,
+/// and the tail is dropped next. The tail is replaced with the head of the cell, which does
+/// not need an allocation.
+impl<U, E> Drop for LTerm<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn drop(&mut self) {
+        let mut tail = match Rc::get_mut(&mut self.inner) {
+            Some(LTermInner::Cons(head, tail)) => std::mem::replace(tail, head.clone()),
+            _ => return,
+        };
+        while let Some(LTermInner::Cons(head, next)) = Rc::get_mut(&mut tail.inner) {
+            let next = std::mem::replace(next, head.clone());
+            tail = next;
+        }
+    }
+}
+
+/// Collects a proper list from an iterator of anything that converts into terms.
+impl<U, E, T> FromIterator<T> for LTerm<U, E>
 where
     U: User,
     E: Engine<U>,
+    T: Into<LTerm<U, E>>,
 {
-    fn from_iter<T: IntoIterator<Item = LTerm<U, E>>>(iter: T) -> Self {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
         let mut list_head = LTerm::empty_list();
         let mut list_tail = &mut list_head;
         for elem in iter {
             let _ = std::mem::replace(
                 list_tail.as_mut(),
-                LTermInner::Cons(elem, LTerm::empty_list()),
+                LTermInner::Cons(elem.into(), LTerm::empty_list()),
             );
             list_tail = list_tail.tail_mut().unwrap();
         }
@@ -1029,6 +1213,225 @@ where
     }
 }
 
+/// Serialization of terms
+///
+/// Values are serialized to their natural representation: booleans, numbers and strings as
+/// themselves, and characters as strings of one character. Proper lists are serialized as
+/// sequences, and improper lists as maps `{"items": [...], "tail": tail}`. Variables are
+/// serialized as maps `{"var": name, "id": id}`, and compound terms as maps
+/// `{"compound": type_name, "children": [...]}`. Projection variables, user terms and custom
+/// values cannot be serialized.
+///
+/// Deserialization is the inverse, except that characters are deserialized as strings, and
+/// compound terms cannot be deserialized. Deserialized variables keep their identifiers, such
+/// that a variable that is deserialized in the same process is the same variable as the
+/// serialized one, and variables that are created after the deserialization get identifiers
+/// of their own. The names of the deserialized variables are interned.
+#[cfg(feature = "serde")]
+mod serialization {
+    use super::{LTerm, LTermInner, Symbol, VarID, UNIQUE_ID_COUNTER};
+    use crate::compound::CompoundObject;
+    use crate::engine::Engine;
+    use crate::lvalue::LValue;
+    use crate::user::User;
+    use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+    use serde::ser::{self, Serialize, SerializeMap, Serializer};
+    use std::fmt;
+    use std::marker::PhantomData;
+    use std::sync::atomic::Ordering;
+
+    impl<U, E> Serialize for LTerm<U, E>
+    where
+        U: User,
+        E: Engine<U>,
+    {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            match self.as_ref() {
+                LTermInner::Val(LValue::Bool(b)) => serializer.serialize_bool(*b),
+                LTermInner::Val(LValue::Number(n)) => serializer.serialize_i64(*n as i64),
+                LTermInner::Val(LValue::Float(x)) => serializer.serialize_f64(*x),
+                #[cfg(feature = "bigint")]
+                LTermInner::Val(LValue::BigInt(n)) => {
+                    // Big integers do not fit in the numbers of most formats; they are written
+                    // in decimal.
+                    let mut map = serializer.serialize_map(Some(1))?;
+                    map.serialize_entry("bigint", &n.to_string())?;
+                    map.end()
+                }
+                LTermInner::Val(LValue::Char(c)) => serializer.serialize_char(*c),
+                LTermInner::Val(LValue::String(s)) => serializer.serialize_str(s),
+                LTermInner::Val(LValue::Custom(_)) => {
+                    Err(ser::Error::custom("custom values cannot be serialized"))
+                }
+                LTermInner::Var(id, name) => {
+                    let mut map = serializer.serialize_map(Some(2))?;
+                    map.serialize_entry("var", name.as_str())?;
+                    map.serialize_entry("id", &id.0)?;
+                    map.end()
+                }
+                LTermInner::User(_) => Err(ser::Error::custom("user terms cannot be serialized")),
+                LTermInner::Empty | LTermInner::Cons(_, _) => {
+                    // The spine of the list is iterated instead of recursing into the tails.
+                    let mut items = vec![];
+                    let mut tail = self;
+                    while let LTermInner::Cons(head, rest) = tail.as_ref() {
+                        items.push(head);
+                        tail = rest;
+                    }
+                    if tail.is_empty() {
+                        serializer.collect_seq(items)
+                    } else {
+                        let mut map = serializer.serialize_map(Some(2))?;
+                        map.serialize_entry("items", &items)?;
+                        map.serialize_entry("tail", tail)?;
+                        map.end()
+                    }
+                }
+                LTermInner::Projection(_) => Err(ser::Error::custom(
+                    "projection variables cannot be serialized",
+                )),
+                LTermInner::Compound(object) => {
+                    SerializeCompound(object.as_ref()).serialize(serializer)
+                }
+            }
+        }
+    }
+
+    struct SerializeCompound<'a, U: User, E: Engine<U>>(&'a dyn CompoundObject<U, E>);
+
+    impl<'a, U, E> Serialize for SerializeCompound<'a, U, E>
+    where
+        U: User,
+        E: Engine<U>,
+    {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            match self.0.as_term() {
+                Some(term) => term.serialize(serializer),
+                None => {
+                    let children: Vec<SerializeCompound<U, E>> =
+                        self.0.children().map(SerializeCompound).collect();
+                    let mut map = serializer.serialize_map(Some(2))?;
+                    map.serialize_entry("compound", self.0.type_name())?;
+                    map.serialize_entry("children", &children)?;
+                    map.end()
+                }
+            }
+        }
+    }
+
+    impl<'de, U, E> Deserialize<'de> for LTerm<U, E>
+    where
+        U: User,
+        E: Engine<U>,
+    {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_any(LTermVisitor(PhantomData))
+        }
+    }
+
+    struct LTermVisitor<U: User, E: Engine<U>>(PhantomData<LTerm<U, E>>);
+
+    impl<'de, U, E> Visitor<'de> for LTermVisitor<U, E>
+    where
+        U: User,
+        E: Engine<U>,
+    {
+        type Value = LTerm<U, E>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "a logic term")
+        }
+
+        fn visit_bool<Er: de::Error>(self, v: bool) -> Result<Self::Value, Er> {
+            Ok(LTerm::from(v))
+        }
+
+        fn visit_i64<Er: de::Error>(self, v: i64) -> Result<Self::Value, Er> {
+            if v < isize::MIN as i64 || v > isize::MAX as i64 {
+                return Err(de::Error::custom("number out of range"));
+            }
+            Ok(LTerm::from(v as isize))
+        }
+
+        fn visit_u64<Er: de::Error>(self, v: u64) -> Result<Self::Value, Er> {
+            if v > isize::MAX as u64 {
+                return Err(de::Error::custom("number out of range"));
+            }
+            Ok(LTerm::from(v as isize))
+        }
+
+        fn visit_f64<Er: de::Error>(self, v: f64) -> Result<Self::Value, Er> {
+            Ok(LTerm::from(v))
+        }
+
+        fn visit_char<Er: de::Error>(self, v: char) -> Result<Self::Value, Er> {
+            Ok(LTerm::from(v))
+        }
+
+        fn visit_str<Er: de::Error>(self, v: &str) -> Result<Self::Value, Er> {
+            Ok(LTerm::from(v))
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut items = vec![];
+            while let Some(item) = seq.next_element()? {
+                items.push(item);
+            }
+            Ok(LTerm::from_vec(items))
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut name: Option<String> = None;
+            let mut id: Option<usize> = None;
+            let mut items: Option<Vec<LTerm<U, E>>> = None;
+            let mut tail: Option<LTerm<U, E>> = None;
+            while let Some(key) = map.next_key::<String>()? {
+                match key.as_str() {
+                    #[cfg(feature = "bigint")]
+                    "bigint" => {
+                        let digits: String = map.next_value()?;
+                        return match digits.parse::<num_bigint::BigInt>() {
+                            Ok(n) => Ok(LTerm::from(n)),
+                            Err(_) => Err(de::Error::custom("invalid big integer")),
+                        };
+                    }
+                    "var" => name = Some(map.next_value()?),
+                    "id" => id = Some(map.next_value()?),
+                    "items" => items = Some(map.next_value()?),
+                    "tail" => tail = Some(map.next_value()?),
+                    "compound" | "children" => {
+                        return Err(de::Error::custom("compound terms cannot be deserialized"))
+                    }
+                    key => return Err(de::Error::unknown_field(key, FIELDS)),
+                }
+            }
+
+            match (name, id, items, tail) {
+                (Some(name), Some(id), None, None) => {
+                    // Variables created after this one must not reuse its identifier.
+                    let next_id = id
+                        .checked_add(1)
+                        .ok_or_else(|| de::Error::custom("variable identifier out of range"))?;
+                    UNIQUE_ID_COUNTER.fetch_max(next_id, Ordering::SeqCst);
+                    Ok(LTerm::from(LTermInner::Var(VarID(id), Symbol::from(name))))
+                }
+                (None, None, Some(items), Some(tail)) => {
+                    let mut list = tail;
+                    for item in items.into_iter().rev() {
+                        list = LTerm::cons(item, list);
+                    }
+                    Ok(list)
+                }
+                _ => Err(de::Error::custom(
+                    "expected a variable {\"var\", \"id\"} or an improper list {\"items\", \"tail\"}",
+                )),
+            }
+        }
+    }
+
+    const FIELDS: &[&str] = &["var", "id", "items", "tail"];
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -1091,6 +1494,92 @@ mod test {
         assert!(u.head_mut().is_none());
     }
 
+    #[test]
+    fn test_lterm_val_3() {
+        let mut u: LTerm<DefaultUser> = lterm!(1.5);
+        assert!(u.is_val());
+        assert!(u.is_float());
+        assert!(!u.is_number());
+        assert!(!u.is_var());
+        assert!(!u.is_bool());
+        assert!(!u.is_list());
+        assert!(!u.is_empty());
+        assert!(!u.is_non_empty_list());
+        assert!(!u.is_user());
+        assert!(!u.is_projection());
+        assert!(u.tail().is_none());
+        assert!(u.head().is_none());
+        assert!(u.tail_mut().is_none());
+        assert!(u.head_mut().is_none());
+        assert_eq!(u.get_float(), Some(1.5));
+        assert_eq!(u.get_number(), None);
+        assert_eq!(u.to_string(), "1.5");
+    }
+
+    #[test]
+    fn test_lterm_var_string_name() {
+        // Variables can be named at runtime
+        let name = format!("v{}", 42);
+        let u: LTerm<DefaultUser> = LTerm::var(name.clone());
+        let v: LTerm<DefaultUser> = LTerm::var(name.as_str());
+        assert!(u.is_var());
+        assert!(!u.is_any());
+        assert_eq!(u.get_name(), Some("v42"));
+        assert_eq!(format!("{}", u), "v42");
+        assert_ne!(u, v);
+        assert!(LTerm::<DefaultUser>::any().is_any());
+    }
+
+    #[test]
+    fn test_lterm_len() {
+        let u: LTerm<DefaultUser> = lterm!([1, 2, 3]);
+        assert_eq!(u.len(), Some(3));
+        assert!(u.is_proper_list());
+
+        let u: LTerm<DefaultUser> = lterm!([1, 2 | 3]);
+        assert_eq!(u.len(), None);
+        assert!(!u.is_proper_list());
+
+        let u: LTerm<DefaultUser> = lterm!(5);
+        assert_eq!(u.len(), None);
+        assert!(!u.is_proper_list());
+
+        let u: LTerm<DefaultUser> = lterm!([]);
+        assert_eq!(u.len(), Some(0));
+        assert!(u.is_proper_list());
+
+        // A variable tail is not followed
+        let x = LTerm::var("x");
+        let u: LTerm<DefaultUser> = lterm!([1, 2 | x]);
+        assert_eq!(u.len(), None);
+        assert!(!u.is_proper_list());
+        // whereas the iterator also produces the improper tail
+        assert_eq!(u.iter().count(), 3);
+    }
+
+    #[test]
+    fn test_lterm_from_vec() {
+        let u: LTerm<DefaultUser> = LTerm::from(vec![1, 2, 3]);
+        assert_eq!(u, lterm!([1, 2, 3]));
+        assert_eq!(u.len(), Some(3));
+
+        let u: LTerm<DefaultUser> = vec!["a", "b"].into();
+        assert_eq!(u, lterm!(["a", "b"]));
+
+        let u: LTerm<DefaultUser> = vec![String::from("a")].into();
+        assert_eq!(u, lterm!(["a"]));
+
+        let u: LTerm<DefaultUser> = LTerm::from(Vec::<isize>::new());
+        assert!(u.is_empty());
+
+        // Nested vectors become nested lists
+        let u: LTerm<DefaultUser> = LTerm::from(vec![vec![1], vec![2, 3]]);
+        assert_eq!(u, lterm!([[1], [2, 3]]));
+
+        let u: LTerm<DefaultUser> = "abc".chars().collect();
+        assert_eq!(u, lterm!(['a', 'b', 'c']));
+    }
+
     #[test]
     fn test_lterm_iter_1() {
         let u: LTerm<DefaultUser> = lterm!([]);
@@ -1265,6 +1754,22 @@ mod test {
         assert_ne!(lterm!([1]), lterm!(1) as LTerm<DefaultUser>);
     }
 
+    #[test]
+    fn test_lterm_eq_float() {
+        assert_eq!(lterm!(1.5) as LTerm<DefaultUser>, 1.5);
+        assert_eq!(1.5, lterm!(1.5) as LTerm<DefaultUser>);
+        assert_ne!(lterm!(1.5) as LTerm<DefaultUser>, 2.5);
+        assert_ne!(lterm!(1.0) as LTerm<DefaultUser>, 1);
+        assert_ne!(1, lterm!(1.0) as LTerm<DefaultUser>);
+        assert_ne!(lterm!(1.0) as LTerm<DefaultUser>, lterm!(1));
+        assert_ne!(lterm!([1.5]) as LTerm<DefaultUser>, 1.5);
+        assert_eq!(
+            lterm!([1.5, -0.5]) as LTerm<DefaultUser>,
+            lterm!([1.5, -0.5])
+        );
+        assert_eq!(lterm!(1.5) as LTerm<DefaultUser>, LValue::from(1.5));
+    }
+
     #[test]
     fn test_lterm_eq_3() {
         // LTerm vs. LValue
@@ -1345,4 +1850,128 @@ mod test {
             "Projection(x)"
         );
     }
+
+    #[test]
+    fn test_lterm_drop_long_list() {
+        let list: LTerm = (0..1_000_000).map(LTerm::from).collect();
+        let shared = list.tail().unwrap().clone();
+        drop(list);
+        assert_eq!(shared.iter().count(), 999_999);
+        drop(shared);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_lterm_serde_1() {
+        // Ground terms round-trip through JSON
+        let u: LTerm = lterm!([1, [2], "x", true]);
+        let json = serde_json::to_string(&u).unwrap();
+        assert_eq!(json, r#"[1,[2],"x",true]"#);
+        let v: LTerm = serde_json::from_str(&json).unwrap();
+        assert_eq!(u, v);
+
+        let u: LTerm = lterm!([]);
+        let v: LTerm = serde_json::from_str(&serde_json::to_string(&u).unwrap()).unwrap();
+        assert_eq!(u, v);
+
+        // Floats are kept distinct from numbers
+        let u: LTerm = lterm!([1, 1.0, -2.5]);
+        let json = serde_json::to_string(&u).unwrap();
+        assert_eq!(json, "[1,1.0,-2.5]");
+        let v: LTerm = serde_json::from_str(&json).unwrap();
+        assert_eq!(u, v);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_lterm_serde_2() {
+        // Variables keep their identity, and improper lists have a tail
+        let x = LTerm::var("x");
+        let y = LTerm::any();
+        let u: LTerm = LTerm::improper_from_vec(vec![x.clone(), lterm!(1), y.clone()]);
+        let json = serde_json::to_string(&u).unwrap();
+        let v: LTerm = serde_json::from_str(&json).unwrap();
+        assert_eq!(u, v);
+        assert_eq!(v.head().unwrap().to_string(), "x");
+
+        let json = serde_json::to_string(&x).unwrap();
+        let id = match x.as_ref() {
+            LTermInner::Var(id, _) => id.0,
+            _ => unreachable!(),
+        };
+        assert_eq!(json, format!(r#"{{"var":"x","id":{}}}"#, id));
+
+        // New variables do not reuse deserialized identifiers
+        let z: LTerm = serde_json::from_str(r#"{"var":"z","id":1000000000}"#).unwrap();
+        assert_ne!(z, LTerm::var("z"));
+
+        // The largest identifier would overflow the identifiers of the new variables
+        let json = format!(r#"{{"var":"z","id":{}}}"#, usize::MAX);
+        assert!(serde_json::from_str::<LTerm>(&json).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[crate::compound]
+    struct Pair(LTerm, LTerm);
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_lterm_serde_3() {
+        let u: LTerm = Into::into(Pair_compound::_InnerPair(lterm!(1), lterm!("a")));
+        let json = serde_json::to_string(&u).unwrap();
+        assert_eq!(json, r#"{"compound":"Pair","children":[1,"a"]}"#);
+        assert!(serde_json::from_str::<LTerm>(&json).is_err());
+
+        let u: LTerm = LTerm::projection(LTerm::var("x"));
+        assert!(serde_json::to_string(&u).is_err());
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_lterm_bigint() {
+        use crate::prelude::*;
+        use num_bigint::BigInt;
+
+        let n: BigInt = "1234567890123456789012345678901234567890".parse().unwrap();
+        let u: LTerm = LTerm::from(n.clone());
+        assert!(u.is_val());
+        assert!(u.is_bigint());
+        assert!(!u.is_number());
+        assert_eq!(u.get_bigint(), Some(&n));
+        assert_eq!(u.to_string(), "1234567890123456789012345678901234567890");
+        assert_ne!(u, LTerm::from(n.clone() + 1));
+
+        // The value unifies by equality and is reified as it is
+        let query = proto_vulcan_query!(|q| {
+            |x| {
+                x == u,
+                [q, 1] == [x, 1],
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q.get_bigint(), Some(&n));
+        assert!(iter.next().is_none());
+
+        let v = LTerm::from(n + 1);
+        let query = proto_vulcan_query!(|q| {
+            q == u,
+            q == v,
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[cfg(all(feature = "bigint", feature = "serde"))]
+    #[test]
+    fn test_lterm_serde_bigint() {
+        let n: num_bigint::BigInt = "-1234567890123456789012345678901234567890".parse().unwrap();
+        let u: LTerm = LTerm::from_vec(vec![lterm!(1), LTerm::from(n)]);
+        let json = serde_json::to_string(&u).unwrap();
+        assert_eq!(
+            json,
+            r#"[1,{"bigint":"-1234567890123456789012345678901234567890"}]"#
+        );
+        let v: LTerm = serde_json::from_str(&json).unwrap();
+        assert_eq!(u, v);
+        assert!(serde_json::from_str::<LTerm>(r#"{"bigint":"12x"}"#).is_err());
+    }
 }
@@ -0,0 +1,411 @@
+//! Runtime parser for terms written as text.
+use super::LTerm;
+use crate::engine::Engine;
+use crate::user::User;
+use std::collections::HashMap;
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+/// Error returned by [`LTerm::parse_sexpr`]. The positions are byte offsets into the input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// The input ended in the middle of a term.
+    UnexpectedEnd,
+    /// A character that cannot start or continue a term at this position.
+    UnexpectedChar(usize, char),
+    /// A malformed number literal.
+    InvalidNumber(usize, String),
+    /// A symbol that is not `true`, `false`, `_` or a `?`-prefixed variable.
+    UnknownSymbol(usize, String),
+    /// Input that remains after the term.
+    TrailingInput(usize),
+    /// A list at this position is nested deeper than `MAX_PARSE_DEPTH` lists.
+    TooDeep(usize),
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ParseError::UnexpectedEnd => write!(f, "unexpected end of input"),
+            ParseError::UnexpectedChar(pos, c) => {
+                write!(f, "unexpected character {:?} at {}", c, pos)
+            }
+            ParseError::InvalidNumber(pos, s) => write!(f, "invalid number {:?} at {}", s, pos),
+            ParseError::UnknownSymbol(pos, s) => write!(f, "unknown symbol {:?} at {}", s, pos),
+            ParseError::TrailingInput(pos) => write!(f, "unexpected input after term at {}", pos),
+            ParseError::TooDeep(pos) => write!(f, "list nested too deeply at {}", pos),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Maximum nesting depth of lists. The parser recurses into nested lists, therefore the depth
+/// is limited to keep the parser from overflowing the stack.
+pub const MAX_PARSE_DEPTH: usize = 1000;
+
+impl<U, E> LTerm<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    /// Parses a term from text.
+    ///
+    /// The syntax accepts:
+    ///  * integers `42`, `-7` and floats `1.5`, `-2.0e-3`
+    ///  * booleans `true` and `false`
+    ///  * strings `"foo"`, with the escapes `\"`, `\\`, `\n` and `\t`, and chars `'c'`
+    ///  * variables `?x`; the same name refers to the same variable within one input, and
+    ///    `_` is a fresh any-variable on each occurrence
+    ///  * lists `(1 2 3)` and `[1, 2, 3]`, and improper lists `(1 2 . 3)` and `[1, 2 | 3]`,
+    ///    nested at most `MAX_PARSE_DEPTH` levels deep
+    ///
+    /// Ground terms are parsed from their `Display` output.
+    ///
+    /// # Example
+    /// ```rust
+    /// extern crate proto_vulcan;
+    /// use proto_vulcan::prelude::*;
+    /// fn main() {
+    ///     let u: LTerm = LTerm::parse_sexpr("(1 \"a\" [true | 'b'])").unwrap();
+    ///     assert_eq!(u, lterm!([1, "a", [true | 'b']]));
+    ///     assert!(LTerm::<DefaultUser>::parse_sexpr("(1 2").is_err());
+    /// }
+    /// ```
+    pub fn parse_sexpr(input: &str) -> Result<LTerm<U, E>, ParseError> {
+        let mut parser = Parser {
+            input,
+            chars: input.char_indices().peekable(),
+            vars: HashMap::new(),
+            depth: 0,
+        };
+        let term = parser.term()?;
+        parser.skip_whitespace();
+        match parser.chars.peek() {
+            Some((pos, _)) => Err(ParseError::TrailingInput(*pos)),
+            None => Ok(term),
+        }
+    }
+}
+
+struct Parser<'a, U: User, E: Engine<U>> {
+    input: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+    vars: HashMap<&'a str, LTerm<U, E>>,
+    depth: usize,
+}
+
+fn is_delimiter(c: char) -> bool {
+    c.is_whitespace() || "()[],|\"'".contains(c)
+}
+
+impl<'a, U, E> Parser<'a, U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn skip_whitespace(&mut self) {
+        while let Some((_, c)) = self.chars.peek() {
+            if !c.is_whitespace() {
+                break;
+            }
+            self.chars.next();
+        }
+    }
+
+    fn peek(&mut self) -> Result<(usize, char), ParseError> {
+        self.skip_whitespace();
+        self.chars.peek().copied().ok_or(ParseError::UnexpectedEnd)
+    }
+
+    fn next(&mut self) -> Result<(usize, char), ParseError> {
+        self.chars.next().ok_or(ParseError::UnexpectedEnd)
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+        match self.peek()? {
+            (_, c) if c == expected => {
+                self.chars.next();
+                Ok(())
+            }
+            (pos, c) => Err(ParseError::UnexpectedChar(pos, c)),
+        }
+    }
+
+    fn term(&mut self) -> Result<LTerm<U, E>, ParseError> {
+        match self.peek()? {
+            (pos, '(') => {
+                self.chars.next();
+                self.nested(pos, Self::paren_list)
+            }
+            (pos, '[') => {
+                self.chars.next();
+                self.nested(pos, Self::bracket_list)
+            }
+            (_, '"') => {
+                self.chars.next();
+                self.string()
+            }
+            (_, '\'') => {
+                self.chars.next();
+                self.character()
+            }
+            (pos, c) if is_delimiter(c) => Err(ParseError::UnexpectedChar(pos, c)),
+            (pos, _) => self.atom(pos),
+        }
+    }
+
+    // Parses a list at `pos` with `list`, one level deeper than the enclosing list.
+    fn nested(
+        &mut self,
+        pos: usize,
+        list: fn(&mut Self) -> Result<LTerm<U, E>, ParseError>,
+    ) -> Result<LTerm<U, E>, ParseError> {
+        if self.depth == MAX_PARSE_DEPTH {
+            return Err(ParseError::TooDeep(pos));
+        }
+        self.depth += 1;
+        let result = list(self);
+        self.depth -= 1;
+        result
+    }
+
+    // `(a b c)` or `(a b . c)`
+    fn paren_list(&mut self) -> Result<LTerm<U, E>, ParseError> {
+        let mut items = vec![];
+        loop {
+            match self.peek()? {
+                (_, ')') => {
+                    self.chars.next();
+                    return Ok(LTerm::from_vec(items));
+                }
+                (_, '.') if !items.is_empty() => {
+                    self.chars.next();
+                    match self.chars.peek() {
+                        Some((_, c)) if is_delimiter(*c) => (),
+                        Some((pos, c)) => return Err(ParseError::UnexpectedChar(*pos, *c)),
+                        None => return Err(ParseError::UnexpectedEnd),
+                    }
+                    items.push(self.term()?);
+                    self.expect(')')?;
+                    return Ok(LTerm::improper_from_vec(items));
+                }
+                (pos, '.') => return Err(ParseError::UnexpectedChar(pos, '.')),
+                _ => items.push(self.term()?),
+            }
+        }
+    }
+
+    // `[a, b, c]` or `[a, b | c]`
+    fn bracket_list(&mut self) -> Result<LTerm<U, E>, ParseError> {
+        let mut items = vec![];
+        if let (_, ']') = self.peek()? {
+            self.chars.next();
+            return Ok(LTerm::empty_list());
+        }
+        loop {
+            items.push(self.term()?);
+            match self.peek()? {
+                (_, ',') => {
+                    self.chars.next();
+                }
+                (_, '|') => {
+                    self.chars.next();
+                    items.push(self.term()?);
+                    self.expect(']')?;
+                    return Ok(LTerm::improper_from_vec(items));
+                }
+                (_, ']') => {
+                    self.chars.next();
+                    return Ok(LTerm::from_vec(items));
+                }
+                (pos, c) => return Err(ParseError::UnexpectedChar(pos, c)),
+            }
+        }
+    }
+
+    fn escaped(&mut self) -> Result<char, ParseError> {
+        match self.next()? {
+            (_, '\\') => match self.next()? {
+                (_, 'n') => Ok('\n'),
+                (_, 't') => Ok('\t'),
+                (_, c @ '\\') | (_, c @ '"') | (_, c @ '\'') => Ok(c),
+                (pos, c) => Err(ParseError::UnexpectedChar(pos, c)),
+            },
+            (_, c) => Ok(c),
+        }
+    }
+
+    fn string(&mut self) -> Result<LTerm<U, E>, ParseError> {
+        let mut s = String::new();
+        loop {
+            match self.chars.peek() {
+                Some((_, '"')) => {
+                    self.chars.next();
+                    return Ok(LTerm::from(s));
+                }
+                Some(_) => s.push(self.escaped()?),
+                None => return Err(ParseError::UnexpectedEnd),
+            }
+        }
+    }
+
+    fn character(&mut self) -> Result<LTerm<U, E>, ParseError> {
+        match self.chars.peek() {
+            Some((pos, '\'')) => return Err(ParseError::UnexpectedChar(*pos, '\'')),
+            Some(_) => (),
+            None => return Err(ParseError::UnexpectedEnd),
+        }
+        let c = self.escaped()?;
+        match self.next()? {
+            (_, '\'') => Ok(LTerm::from(c)),
+            (pos, c) => Err(ParseError::UnexpectedChar(pos, c)),
+        }
+    }
+
+    // Numbers, booleans and variables
+    fn atom(&mut self, start: usize) -> Result<LTerm<U, E>, ParseError> {
+        let mut end = self.input.len();
+        while let Some((pos, c)) = self.chars.peek() {
+            if is_delimiter(*c) {
+                end = *pos;
+                break;
+            }
+            self.chars.next();
+        }
+        let atom = &self.input[start..end];
+
+        if atom.starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '+') {
+            if let Ok(n) = atom.parse::<isize>() {
+                Ok(LTerm::from(n))
+            } else if let Ok(x) = atom.parse::<f64>() {
+                Ok(LTerm::from(x))
+            } else {
+                Err(ParseError::InvalidNumber(start, String::from(atom)))
+            }
+        } else if atom == "true" {
+            Ok(LTerm::from(true))
+        } else if atom == "false" {
+            Ok(LTerm::from(false))
+        } else if atom == "_" {
+            Ok(LTerm::any())
+        } else if atom.len() > 1 && atom.starts_with('?') {
+            let name = &atom[1..];
+            let var = self.vars.entry(name).or_insert_with(|| LTerm::var(name));
+            Ok(var.clone())
+        } else {
+            Err(ParseError::UnknownSymbol(start, String::from(atom)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ParseError, MAX_PARSE_DEPTH};
+    use crate::prelude::*;
+
+    fn parse(input: &str) -> Result<LTerm, ParseError> {
+        LTerm::parse_sexpr(input)
+    }
+
+    #[test]
+    fn test_parse_sexpr_values() {
+        assert_eq!(parse("42").unwrap(), 42);
+        assert_eq!(parse(" -7 ").unwrap(), -7);
+        assert_eq!(parse("1.5").unwrap(), 1.5);
+        assert_eq!(parse("-2.0e-3").unwrap(), -2.0e-3);
+        assert_eq!(parse("true").unwrap(), true);
+        assert_eq!(parse("false").unwrap(), false);
+        assert_eq!(parse("\"a \\\"b\\\"\"").unwrap(), "a \"b\"");
+        assert_eq!(parse("'x'").unwrap(), 'x');
+        assert_eq!(parse("'\\n'").unwrap(), '\n');
+    }
+
+    #[test]
+    fn test_parse_sexpr_lists() {
+        assert_eq!(parse("()").unwrap(), lterm!([]));
+        assert_eq!(parse("[]").unwrap(), lterm!([]));
+        assert_eq!(parse("(1 2 3)").unwrap(), lterm!([1, 2, 3]));
+        assert_eq!(parse("[1, 2, 3]").unwrap(), lterm!([1, 2, 3]));
+        assert_eq!(parse("(1 (2) . 3)").unwrap(), lterm!([1, [2] | 3]));
+        assert_eq!(parse("[1, [2] | 3]").unwrap(), lterm!([1, [2] | 3]));
+        assert_eq!(
+            parse("[(1 2), [\"a\"|'b']]").unwrap(),
+            lterm!([[1, 2], ["a" | 'b']])
+        );
+    }
+
+    #[test]
+    fn test_parse_sexpr_vars() {
+        // The same name is the same variable
+        let u = parse("(?x ?y ?x _ _)").unwrap();
+        let items: Vec<&LTerm> = u.iter().collect();
+        assert!(items[0].is_var());
+        assert_eq!(items[0].get_name(), Some("x"));
+        assert_eq!(items[0], items[2]);
+        assert_ne!(items[0], items[1]);
+        assert!(items[3].is_any());
+        assert_ne!(items[3], items[4]);
+
+        // Different inputs have different variables
+        assert_ne!(parse("?x").unwrap(), parse("?x").unwrap());
+
+        let x = parse("?x").unwrap();
+        let query = proto_vulcan_query!(|q| { [x, q] == [1, x] });
+        assert_eq!(query.run().next().unwrap().q, 1);
+    }
+
+    #[test]
+    fn test_parse_sexpr_display() {
+        // Ground terms are parsed from their Display output
+        let terms: Vec<LTerm> = vec![
+            lterm!(1),
+            lterm!(-1.25),
+            lterm!(true),
+            lterm!('c'),
+            lterm!("Hello, world!"),
+            lterm!([]),
+            lterm!([1, [2, 3], "a", ['b', false], []]),
+            lterm!([1, 2 | 3]),
+            lterm!([[1 | 2] | [3 | 4.0]]),
+        ];
+        for u in terms {
+            assert_eq!(parse(&u.to_string()).unwrap(), u);
+        }
+    }
+
+    #[test]
+    fn test_parse_sexpr_errors() {
+        assert_eq!(parse(""), Err(ParseError::UnexpectedEnd));
+        assert_eq!(parse("(1 2"), Err(ParseError::UnexpectedEnd));
+        assert_eq!(parse("[1, 2)"), Err(ParseError::UnexpectedChar(5, ')')));
+        assert_eq!(parse("(. 1)"), Err(ParseError::UnexpectedChar(1, '.')));
+        assert_eq!(parse("(1 . 2 3)"), Err(ParseError::UnexpectedChar(7, '3')));
+        assert_eq!(parse("1 2"), Err(ParseError::TrailingInput(2)));
+        assert_eq!(
+            parse("1x"),
+            Err(ParseError::InvalidNumber(0, String::from("1x")))
+        );
+        assert_eq!(
+            parse("foo"),
+            Err(ParseError::UnknownSymbol(0, String::from("foo")))
+        );
+        assert_eq!(
+            parse("?"),
+            Err(ParseError::UnknownSymbol(0, String::from("?")))
+        );
+        assert_eq!(parse("''"), Err(ParseError::UnexpectedChar(1, '\'')));
+        assert_eq!(parse("\"abc"), Err(ParseError::UnexpectedEnd));
+    }
+
+    #[test]
+    fn test_parse_sexpr_depth() {
+        let depth = MAX_PARSE_DEPTH;
+        let input = format!("{}{}", "(".repeat(depth), ")".repeat(depth));
+        assert!(parse(&input).is_ok());
+
+        let input = format!("{}{}", "[".repeat(depth + 1), "]".repeat(depth + 1));
+        assert_eq!(parse(&input), Err(ParseError::TooDeep(depth)));
+        assert_eq!(parse(&"(".repeat(100_000)), Err(ParseError::TooDeep(depth)));
+    }
+}
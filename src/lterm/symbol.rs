@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Mutex, OnceLock};
+
+/// Interned variable name
+///
+/// Symbols are small `Copy`-values that are compared by their index in a global interner.
+/// The string of each distinct name is allocated only once, and it lives for the rest of the
+/// program, therefore symbols can be created from names that are only known at runtime.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Symbol(u32);
+
+/// Table of interned names, indexed by symbol. The table is split into segments of doubling
+/// sizes that are allocated as the table grows and never moved, therefore names can be read
+/// without locking while new names are appended under the lock of the interner.
+type Segment = Box<[OnceLock<&'static str>]>;
+
+const SEGMENTS: usize = 32;
+
+static NAMES: [OnceLock<Segment>; SEGMENTS] = [const { OnceLock::new() }; SEGMENTS];
+
+/// Segment and offset in the segment of the name of symbol `index`.
+fn location(index: u32) -> (usize, usize) {
+    let n = index as usize + 1;
+    let segment = (usize::BITS - 1 - n.leading_zeros()) as usize;
+    (segment, n - (1 << segment))
+}
+
+struct Interner {
+    symbols: HashMap<&'static str, Symbol>,
+}
+
+impl Interner {
+    fn push(&mut self, name: &'static str) -> Symbol {
+        let symbol = Symbol(self.symbols.len() as u32);
+        let (segment, offset) = location(symbol.0);
+        let segment = NAMES[segment].get_or_init(|| {
+            (0..1usize << segment)
+                .map(|_| OnceLock::new())
+                .collect::<Vec<_>>()
+                .into_boxed_slice()
+        });
+        let _ = segment[offset].set(name);
+        self.symbols.insert(name, symbol);
+        symbol
+    }
+}
+
+fn interner() -> &'static Mutex<Interner> {
+    static INTERNER: OnceLock<Mutex<Interner>> = OnceLock::new();
+    INTERNER.get_or_init(|| {
+        // The name of any-variables is the first symbol.
+        let mut interner = Interner {
+            symbols: HashMap::new(),
+        };
+        interner.push("_");
+        Mutex::new(interner)
+    })
+}
+
+impl Symbol {
+    /// The name `"_"` of any-variables.
+    pub const ANY: Symbol = Symbol(0);
+
+    /// Returns the symbol of `name`, interning the name if it has not been seen before.
+    pub fn intern(name: &str) -> Symbol {
+        let mut interner = interner().lock().unwrap();
+        if let Some(symbol) = interner.symbols.get(name) {
+            return *symbol;
+        }
+        interner.push(Box::leak(name.to_string().into_boxed_str()))
+    }
+
+    /// Returns the name of the symbol. The name is read without locking the interner.
+    pub fn as_str(self) -> &'static str {
+        if self.is_any() {
+            // The interner may not have been initialized yet.
+            return "_";
+        }
+        let (segment, offset) = location(self.0);
+        NAMES[segment]
+            .get()
+            .and_then(|segment| segment[offset].get())
+            .copied()
+            .expect("symbol is interned")
+    }
+
+    /// True if the symbol is the name of any-variables.
+    pub fn is_any(self) -> bool {
+        self == Symbol::ANY
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(name: &str) -> Symbol {
+        Symbol::intern(name)
+    }
+}
+
+impl From<String> for Symbol {
+    fn from(name: String) -> Symbol {
+        Symbol::intern(&name)
+    }
+}
+
+impl From<&String> for Symbol {
+    fn from(name: &String) -> Symbol {
+        Symbol::intern(name)
+    }
+}
+
+impl PartialEq<str> for Symbol {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Symbol {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Symbol;
+
+    #[test]
+    fn test_symbol_intern_1() {
+        let x = Symbol::intern("symbol_test_x");
+        assert_eq!(x, Symbol::intern(&String::from("symbol_test_x")));
+        assert_ne!(x, Symbol::intern("symbol_test_y"));
+        assert_eq!(x.as_str(), "symbol_test_x");
+        assert_eq!(x, "symbol_test_x");
+        assert_eq!(format!("{} {:?}", x, x), "symbol_test_x \"symbol_test_x\"");
+        assert!(Symbol::intern("_").is_any());
+        assert!(!x.is_any());
+    }
+
+    #[test]
+    fn test_symbol_intern_2() {
+        // Names are readable from other threads while new names are interned
+        let symbols: Vec<Symbol> = (0..1000)
+            .map(|i| Symbol::intern(&format!("symbol_test_{}", i)))
+            .collect();
+        let handles: Vec<_> = (0..4)
+            .map(|t| {
+                let symbols = symbols.clone();
+                std::thread::spawn(move || {
+                    for (i, symbol) in symbols.iter().enumerate() {
+                        let other = Symbol::intern(&format!("symbol_test_{}_{}", t, i));
+                        assert_eq!(symbol.as_str(), format!("symbol_test_{}", i));
+                        assert_eq!(other.as_str(), format!("symbol_test_{}_{}", t, i));
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(Symbol::ANY.as_str(), "_");
+    }
+}
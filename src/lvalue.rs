@@ -1,12 +1,119 @@
+#[cfg(feature = "bigint")]
+use num_bigint::BigInt;
+use std::any::Any;
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+/// A user-defined literal value kind.
+///
+/// Custom values are compared and hashed with the `PartialEq` and `Hash` implementations of
+/// their concrete type, and values of different concrete types are never equal. Unlike
+/// `User::UserTerm`, any number of custom value kinds can be used in the same program. The
+/// trait is implemented for all types that implement `Eq`, `Hash`, `Debug` and `Display`.
+pub trait CustomValue: fmt::Debug + fmt::Display {
+    fn as_any(&self) -> &dyn Any;
+
+    fn custom_eq(&self, other: &dyn CustomValue) -> bool;
+
+    fn custom_hash(&self, state: &mut dyn Hasher);
+}
+
+impl<T> CustomValue for T
+where
+    T: Any + Eq + Hash + fmt::Debug + fmt::Display,
+{
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn custom_eq(&self, other: &dyn CustomValue) -> bool {
+        match other.as_any().downcast_ref::<T>() {
+            Some(other_value) => self.eq(other_value),
+            None => false,
+        }
+    }
+
+    fn custom_hash(&self, mut state: &mut dyn Hasher) {
+        self.type_id().hash(&mut state);
+        self.hash(&mut state);
+    }
+}
 
 /// Literal Logic Value
-#[derive(PartialEq, Hash, Clone)]
+///
+/// Floating-point values are compared with the IEEE 754 equality of `f64`: `0.0` and `-0.0` are
+/// equal, and NaN is not equal to any value, including itself. Therefore a NaN value never
+/// unifies with another value, and the reflexivity required by `Eq` does not hold for NaN.
+/// Floats are hashed by their bit pattern, with `-0.0` hashed as `0.0`, such that equal values
+/// have equal hashes.
+///
+/// With the `bigint` feature, arbitrary-precision integers can be carried as ground values with
+/// `LValue::BigInt`. They are distinct from `LValue::Number`, also when the value would fit in
+/// an `isize`, and they are not supported by the CLP(FD) and CLP(Z) constraints. As the set of
+/// variants depends on the enabled features, `LValue` is non-exhaustive.
+#[derive(Clone)]
+#[non_exhaustive]
 pub enum LValue {
     Bool(bool),
     Number(isize),
+    Float(f64),
+    #[cfg(feature = "bigint")]
+    BigInt(BigInt),
     Char(char),
     String(String),
+    Custom(Rc<dyn CustomValue>),
+}
+
+impl LValue {
+    /// Constructs a custom value.
+    pub fn custom<T: CustomValue + 'static>(value: T) -> LValue {
+        LValue::Custom(Rc::new(value))
+    }
+
+    /// Returns a reference to the custom value if it is of type `T`.
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        match self {
+            LValue::Custom(value) => value.as_any().downcast_ref::<T>(),
+            _ => None,
+        }
+    }
+}
+
+impl PartialEq for LValue {
+    fn eq(&self, other: &LValue) -> bool {
+        match (self, other) {
+            (LValue::Bool(u), LValue::Bool(v)) => u == v,
+            (LValue::Number(u), LValue::Number(v)) => u == v,
+            (LValue::Float(u), LValue::Float(v)) => u == v,
+            #[cfg(feature = "bigint")]
+            (LValue::BigInt(u), LValue::BigInt(v)) => u == v,
+            (LValue::Char(u), LValue::Char(v)) => u == v,
+            (LValue::String(u), LValue::String(v)) => u == v,
+            (LValue::Custom(u), LValue::Custom(v)) => u.custom_eq(v.as_ref()),
+            _ => false,
+        }
+    }
+}
+
+impl Hash for LValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            LValue::Bool(u) => u.hash(state),
+            LValue::Number(u) => u.hash(state),
+            LValue::Float(u) => {
+                // 0.0 and -0.0 are equal, and must have the same hash.
+                let u = if *u == 0.0 { 0.0 } else { *u };
+                u.to_bits().hash(state)
+            }
+            #[cfg(feature = "bigint")]
+            LValue::BigInt(u) => u.hash(state),
+            LValue::Char(u) => u.hash(state),
+            LValue::String(u) => u.hash(state),
+            LValue::Custom(u) => u.custom_hash(state),
+        }
+    }
 }
 
 impl From<bool> for LValue {
@@ -21,6 +128,19 @@ impl From<isize> for LValue {
     }
 }
 
+impl From<f64> for LValue {
+    fn from(u: f64) -> LValue {
+        LValue::Float(u)
+    }
+}
+
+#[cfg(feature = "bigint")]
+impl From<BigInt> for LValue {
+    fn from(u: BigInt) -> LValue {
+        LValue::BigInt(u)
+    }
+}
+
 impl<T: Copy + Into<LValue>> From<&T> for LValue {
     fn from(u: &T) -> LValue {
         (*u).into()
@@ -81,6 +201,24 @@ impl PartialEq<LValue> for isize {
     }
 }
 
+impl PartialEq<f64> for LValue {
+    fn eq(&self, other: &f64) -> bool {
+        match self {
+            LValue::Float(x) => x == other,
+            _ => false,
+        }
+    }
+}
+
+impl PartialEq<LValue> for f64 {
+    fn eq(&self, other: &LValue) -> bool {
+        match other {
+            LValue::Float(x) => x == self,
+            _ => false,
+        }
+    }
+}
+
 impl PartialEq<char> for LValue {
     fn eq(&self, other: &char) -> bool {
         match self {
@@ -162,8 +300,12 @@ impl fmt::Debug for LValue {
         match self {
             LValue::Bool(val) => write!(f, "{:?}", val),
             LValue::Number(val) => write!(f, "{:?}", val),
+            LValue::Float(val) => write!(f, "{:?}", val),
+            #[cfg(feature = "bigint")]
+            LValue::BigInt(val) => write!(f, "{}", val),
             LValue::Char(val) => write!(f, "{:?}", val),
             LValue::String(val) => write!(f, "{:?}", val),
+            LValue::Custom(val) => write!(f, "{:?}", val),
         }
     }
 }
@@ -173,8 +315,13 @@ impl fmt::Display for LValue {
         match self {
             LValue::Bool(val) => write!(f, "{}", val),
             LValue::Number(val) => write!(f, "{}", val),
+            // Printed with a decimal point, such that `1.0` is distinct from the number `1`.
+            LValue::Float(val) => write!(f, "{:?}", val),
+            #[cfg(feature = "bigint")]
+            LValue::BigInt(val) => write!(f, "{}", val),
             LValue::Char(val) => write!(f, "'{}'", val),
             LValue::String(val) => write!(f, "\"{}\"", val),
+            LValue::Custom(val) => write!(f, "{}", val),
         }
     }
 }
@@ -234,6 +381,36 @@ mod test {
         assert!(u != v);
     }
 
+    #[test]
+    fn test_lvalue_float() {
+        let u = LValue::from(1.5);
+        assert!(u == 1.5);
+        assert!(1.5 == u);
+        assert!(u != 1);
+        assert!(1 != u);
+        assert!(u != true);
+        assert!(true != u);
+        assert!(u != "1.5");
+        assert!("1.5" != u);
+
+        let v = LValue::from(&1.0);
+        assert!(v == 1.0);
+        assert!(v != 1);
+        assert!(1 != v);
+
+        assert!(u == u);
+        assert!(u != v);
+
+        // Zeros are equal and have equal hashes, NaN is not equal to itself
+        let mut set = std::collections::HashSet::new();
+        set.insert(LValue::from(0.0));
+        assert!(LValue::from(0.0) == LValue::from(-0.0));
+        assert!(set.contains(&LValue::from(-0.0)));
+        let nan = LValue::from(f64::NAN);
+        assert!(nan != nan);
+        assert!(nan != LValue::from(f64::NAN));
+    }
+
     #[test]
     fn test_lvalue_char() {
         let u = LValue::from('1');
@@ -296,10 +473,49 @@ mod test {
         assert_eq!(format!("{}", LValue::from(false)), "false");
         assert_eq!(format!("{}", LValue::from(1234)), "1234");
         assert_eq!(format!("{}", LValue::from(-1234)), "-1234");
+        assert_eq!(format!("{}", LValue::from(1.0)), "1.0");
+        assert_eq!(format!("{}", LValue::from(-0.25)), "-0.25");
         assert_eq!(format!("{}", LValue::from('a')), "'a'");
         assert_eq!(
             format!("{}", LValue::from("Hello, world!")),
             "\"Hello, world!\""
         );
     }
+
+    #[derive(Debug, PartialEq, Eq, Hash)]
+    struct Celsius(isize);
+
+    impl fmt::Display for Celsius {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}°C", self.0)
+        }
+    }
+
+    #[derive(Debug, PartialEq, Eq, Hash)]
+    struct Fahrenheit(isize);
+
+    impl fmt::Display for Fahrenheit {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}°F", self.0)
+        }
+    }
+
+    #[test]
+    fn test_lvalue_custom() {
+        let u = LValue::custom(Celsius(10));
+        assert!(u == LValue::custom(Celsius(10)));
+        assert!(u != LValue::custom(Celsius(11)));
+        assert!(u != LValue::custom(Fahrenheit(10)));
+        assert!(u != 10);
+        assert!(10 != u);
+        assert_eq!(u.downcast_ref::<Celsius>(), Some(&Celsius(10)));
+        assert_eq!(u.downcast_ref::<Fahrenheit>(), None);
+        assert_eq!(u.to_string(), "10°C");
+        assert_eq!(format!("{:?}", u), "Celsius(10)");
+
+        let mut set = std::collections::HashSet::new();
+        set.insert(u.clone());
+        assert!(set.contains(&LValue::custom(Celsius(10))));
+        assert!(!set.contains(&LValue::custom(Fahrenheit(10))));
+    }
 }
@@ -1,13 +1,14 @@
 use crate::engine::Engine;
 use crate::goal::{AnyGoal, DFSGoal, Goal, InferredGoal};
 use crate::operator::conj::InferredConj;
-use crate::operator::OperatorParam;
+use crate::operator::{CondeForOperatorParam, OperatorParam};
 use crate::solver::{Solve, Solver};
 use crate::state::State;
-use crate::stream::{LazyStream, Stream};
+use crate::stream::{LazyStream, Stream, StreamIterator};
 use crate::user::User;
 use crate::GoalCast;
 use std::any::Any;
+use std::collections::VecDeque;
 use std::marker::PhantomData;
 use std::rc::Rc;
 
@@ -180,12 +181,210 @@ where
     Conde::from_conjunctions(param.body)
 }
 
+/// Round-robin scheduling of the branch streams of `conde_fair`.
+///
+/// Each call advances the stream at the front of the queue by one step, and moves the
+/// stream to the back of the queue.
+#[derive(Derivative)]
+#[derivative(Clone(bound = "U: User"))]
+struct RoundRobin<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    streams: VecDeque<Stream<U, E>>,
+}
+
+impl<U, E> StreamIterator<U, E> for RoundRobin<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn clone_box(&self) -> Box<dyn StreamIterator<U, E>> {
+        Box::new(self.clone())
+    }
+
+    fn next(&mut self, solver: &Solver<U, E>) -> Option<Stream<U, E>> {
+        loop {
+            match self.streams.pop_front()? {
+                Stream::Empty => (),
+                Stream::Unit(a) => return Some(Stream::unit(a)),
+                Stream::Cons(a, lazy) => {
+                    self.streams.push_back(Stream::Lazy(lazy));
+                    return Some(Stream::unit(a));
+                }
+                Stream::Lazy(LazyStream(lazy)) => {
+                    let stream = solver.engine().step(solver, *lazy);
+                    self.streams.push_back(stream);
+                    return Some(Stream::empty());
+                }
+            }
+        }
+    }
+}
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct CondeFair<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    conjunctions: Vec<Goal<U, E>>,
+}
+
+impl<U, E> Solve<U, E> for CondeFair<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, _solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        // Each branch is paused, so that no branch is started before the search reaches
+        // the disjunction.
+        let streams = self
+            .conjunctions
+            .iter()
+            .map(|conjunction| Stream::pause(Box::new(state.clone()), conjunction.clone()))
+            .collect();
+        Stream::iterator(Box::new(RoundRobin { streams }))
+    }
+}
+
+/// Fair disjunction operator.
+///
+/// The `conde_fair` operator is a disjunction of conjunctions like `conde`, but the
+/// branches are scheduled round-robin: every branch is advanced by one step in turn.
+/// Therefore every branch gets a turn before any branch emits its second answer, and
+/// a branch with an infinite stream of answers cannot delay the answers of the other
+/// branches, regardless of the order of the branches.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::operator::conde_fair;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| {
+///         conde_fair {
+///             loop { q == 1 },
+///             q == 2,
+///         }
+///     });
+///     assert!(query.run().take(2).any(|r| r.q == 2));
+/// }
+/// ```
+pub fn conde_fair<U, E>(param: OperatorParam<U, E, Goal<U, E>>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    let conjunctions = param
+        .body
+        .iter()
+        .map(|conjunction| InferredConj::from_array(conjunction).cast_into())
+        .collect();
+    Goal::dynamic(Rc::new(CondeFair { conjunctions }))
+}
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct CondeFor<I, U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+    I: Iterator + Clone + 'static,
+{
+    #[derivative(Debug = "ignore")]
+    iter: I,
+    #[derivative(Debug = "ignore")]
+    g: Rc<dyn Fn(I::Item) -> G>,
+    _phantom: PhantomData<U>,
+    _phantom2: PhantomData<E>,
+}
+
+impl<I, U, E, G> Solve<U, E> for CondeFor<I, U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+    I: Iterator + Clone + 'static,
+{
+    fn solve(&self, solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        // The first branch is solved now, and the rest of the branches are generated only
+        // when the search reaches them. The streams of the branches are interleaved like
+        // the streams of `conde`.
+        let mut iter = self.iter.clone();
+        let t = match iter.next() {
+            Some(t) => t,
+            None => return Stream::empty(),
+        };
+        let rest = CondeFor {
+            iter,
+            g: Rc::clone(&self.g),
+            _phantom: PhantomData,
+            _phantom2: PhantomData,
+        };
+        let new_stream = (*self.g)(t).solve(solver, state.clone());
+        if (self as &dyn Any).is::<CondeFor<I, U, E, Goal<U, E>>>() {
+            let rest = LazyStream::pause(Box::new(state), Goal::dynamic(Rc::new(rest)));
+            Stream::mplus(new_stream, rest)
+        } else {
+            let rest = LazyStream::pause_dfs(Box::new(state), DFSGoal::dynamic(Rc::new(rest)));
+            Stream::mplus_dfs(new_stream, rest)
+        }
+    }
+}
+
+/// Disjunction over an iterator.
+///
+/// The `conde for x in iter { <body> }` operator is a disjunction with a branch for each
+/// element `x` of the iterator. The branches are generated lazily from the iterator as the
+/// search reaches them, therefore the iterator can have very many or even infinitely many
+/// elements. Elements of the iterator must be convertible into `LTerm`s.
+///
+/// As with `conde`, the streams of the branches are interleaved, so a branch with infinitely
+/// many answers does not prevent the later branches from producing answers.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| {
+///         conde for x in 1.. {
+///             q == x,
+///         }
+///     });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().q, 1);
+///     assert_eq!(iter.next().unwrap().q, 2);
+///     assert_eq!(iter.next().unwrap().q, 3);
+/// }
+/// ```
+pub fn conde_for<I, U, E, G>(param: CondeForOperatorParam<I, U, E, G>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+    I: Iterator + Clone + 'static,
+{
+    InferredGoal::new(G::dynamic(Rc::new(CondeFor {
+        iter: param.iter,
+        g: param.g,
+        _phantom: PhantomData,
+        _phantom2: PhantomData,
+    })))
+}
+
 #[cfg(test)]
 mod test {
-    use super::{cond, conde};
+    use super::{cond, conde, conde_fair};
     use crate::operator::dfs;
     use crate::prelude::*;
     use crate::relation::member;
+    use std::cell::Cell;
+    use std::rc::Rc;
 
     #[test]
     fn test_conde_1() {
@@ -256,4 +455,142 @@ mod test {
         assert_eq!(iter.next().unwrap().q, 9);
         assert!(iter.next().is_none());
     }
+
+    #[test]
+    fn test_conde_for_1() {
+        let query = proto_vulcan_query!(|q| {
+            conde for x in vec![1, 2, 3] {
+                q == x,
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, 1);
+        assert_eq!(iter.next().unwrap().q, 2);
+        assert_eq!(iter.next().unwrap().q, 3);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_conde_for_2() {
+        // Only the branches reached by the search are generated
+        let generated = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&generated);
+        let branches = (0..1_000_000).map(move |x: isize| {
+            counter.set(counter.get() + 1);
+            x
+        });
+        let query = proto_vulcan_query!(|q| {
+            conde for x in branches {
+                q == x,
+                member(q, [0, 2, 4, 6]),
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, 0);
+        assert_eq!(iter.next().unwrap().q, 2);
+        assert_eq!(iter.next().unwrap().q, 4);
+        // The interleaved search generates only a few branches ahead of the answers
+        assert!(generated.get() <= 12);
+    }
+
+    #[test]
+    fn test_conde_for_4() {
+        // An infinite first branch does not starve the later branches
+        let query = proto_vulcan_query!(|q| {
+            conde for x in 0..3 {
+                loop { q == x },
+            }
+        });
+        let results: Vec<isize> = query
+            .run()
+            .take(20)
+            .map(|r| r.q.get_number().unwrap())
+            .collect();
+        assert!(results.contains(&0));
+        assert!(results.contains(&1));
+        assert!(results.contains(&2));
+    }
+
+    #[test]
+    fn test_conde_for_5() {
+        // In DFS the branches are searched in order
+        let query = proto_vulcan_query!(|q| {
+            dfs {
+                conde for x in vec![1, 4] {
+                    member(q, [x, [x]]),
+                }
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, 1);
+        assert_eq!(iter.next().unwrap().q, lterm!([1]));
+        assert_eq!(iter.next().unwrap().q, 4);
+        assert_eq!(iter.next().unwrap().q, lterm!([4]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_conde_for_3() {
+        let query = proto_vulcan_query!(|q| {
+            conde for x in Vec::<isize>::new() {
+                q == x,
+            }
+        });
+        let mut iter = query.run();
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_conde_fair_1() {
+        let query = proto_vulcan_query!(|q| {
+            conde_fair {
+                member(q, [1, 2, 3]),
+                member(q, [4, 5, 6]),
+                member(q, [7, 8, 9]),
+            }
+        });
+        let results: Vec<isize> = query.run().map(|r| r.q.get_number().unwrap()).collect();
+        assert_eq!(results.len(), 9);
+        // Every branch emits its first answer before any branch emits its second answer
+        let mut first = results[0..3].to_vec();
+        first.sort_unstable();
+        assert_eq!(first, vec![1, 4, 7]);
+    }
+
+    #[test]
+    fn test_conde_fair_2() {
+        // The answer of the finite branch appears early regardless of branch order
+        let query = proto_vulcan_query!(|q| {
+            conde_fair {
+                loop { conde { q == 1, q == 1 } },
+                loop { q == 1 },
+                loop { q == 1 },
+                q == 2,
+            }
+        });
+        assert!(query.run().take(4).any(|r| r.q == 2));
+
+        let query = proto_vulcan_query!(|q| {
+            conde_fair {
+                q == 2,
+                loop { conde { q == 1, q == 1 } },
+                loop { q == 1 },
+                loop { q == 1 },
+            }
+        });
+        assert!(query.run().take(4).any(|r| r.q == 2));
+    }
+
+    #[test]
+    fn test_conde_fair_3() {
+        let query = proto_vulcan_query!(|q| {
+            conde_fair {
+                [q == 1, q == 2],
+                q == 3,
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, 3);
+        assert!(iter.next().is_none());
+    }
 }
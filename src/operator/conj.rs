@@ -40,20 +40,22 @@ where
         Conj { goal_1, goal_2 }
     }
 
-    pub fn from_vec(mut v: Vec<Goal<U, E>>) -> Goal<U, E> {
+    /// Conjunction of the goals of `v`. If any of the goals is `fail`, the conjunction is
+    /// `fail`; the goals that are `succeed` are left out.
+    pub fn from_vec(v: Vec<Goal<U, E>>) -> Goal<U, E> {
+        if v.iter().any(|g| g.is_fail()) {
+            return Goal::fail();
+        }
         let mut p = Goal::succeed();
-        for g in v.drain(..).rev() {
+        for g in v.into_iter().rev().filter(|g| !g.is_succeed()) {
             p = Conj::new(g, p);
         }
         p
     }
 
+    /// Conjunction of the `goals`, see [`from_vec`](Conj::from_vec).
     pub fn from_array(goals: &[Goal<U, E>]) -> Goal<U, E> {
-        let mut p = Goal::succeed();
-        for g in goals.to_vec().drain(..).rev() {
-            p = Conj::new(g, p);
-        }
-        p
+        Conj::from_vec(goals.to_vec())
     }
 
     pub fn from_iter<I>(iter: I) -> Goal<U, E>
@@ -126,20 +128,22 @@ where
         DFSConj { goal_1, goal_2 }
     }
 
-    pub fn from_vec(mut v: Vec<DFSGoal<U, E>>) -> DFSGoal<U, E> {
+    /// Conjunction of the goals of `v`. If any of the goals is `fail`, the conjunction is
+    /// `fail`; the goals that are `succeed` are left out.
+    pub fn from_vec(v: Vec<DFSGoal<U, E>>) -> DFSGoal<U, E> {
+        if v.iter().any(|g| g.is_fail()) {
+            return DFSGoal::fail();
+        }
         let mut p = DFSGoal::succeed();
-        for g in v.drain(..).rev() {
+        for g in v.into_iter().rev().filter(|g| !g.is_succeed()) {
             p = DFSConj::new(g, p);
         }
         p
     }
 
+    /// Conjunction of the `goals`, see [`from_vec`](DFSConj::from_vec).
     pub fn from_array(goals: &[DFSGoal<U, E>]) -> DFSGoal<U, E> {
-        let mut p = DFSGoal::succeed();
-        for g in goals.to_vec().drain(..).rev() {
-            p = DFSConj::new(g, p);
-        }
-        p
+        DFSConj::from_vec(goals.to_vec())
     }
 
     pub fn from_iter<I>(iter: I) -> DFSGoal<U, E>
@@ -226,20 +230,22 @@ where
         }
     }
 
-    pub fn from_vec(mut v: Vec<G>) -> InferredGoal<U, E, G> {
+    /// Conjunction of the goals of `v`. If any of the goals is `fail`, the conjunction is
+    /// `fail`; the goals that are `succeed` are left out.
+    pub fn from_vec(v: Vec<G>) -> InferredGoal<U, E, G> {
+        if v.iter().any(|g| g.is_fail()) {
+            return InferredGoal::new(G::fail());
+        }
         let mut p = G::succeed();
-        for g in v.drain(..).rev() {
+        for g in v.into_iter().rev().filter(|g| !g.is_succeed()) {
             p = InferredConj::new(g, p).cast_into();
         }
         InferredGoal::new(p)
     }
 
+    /// Conjunction of the `goals`, see [`from_vec`](InferredConj::from_vec).
     pub fn from_array(goals: &[G]) -> InferredGoal<U, E, G> {
-        let mut p = G::succeed();
-        for g in goals.to_vec().drain(..).rev() {
-            p = InferredConj::new(g, p).cast_into();
-        }
-        InferredGoal::new(p)
+        InferredConj::from_vec(goals.to_vec())
     }
 
     pub fn from_iter<I>(iter: I) -> InferredGoal<U, E, G>
@@ -300,3 +306,55 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Conj, InferredConj};
+    use crate::prelude::*;
+    use crate::stream::Stream;
+    use crate::GoalCast;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    type TestGoal = Goal<DefaultUser, DefaultEngine<DefaultUser>>;
+
+    // A goal that records whether it has been solved.
+    fn expensive_relation(solved: &Rc<Cell<bool>>) -> TestGoal {
+        let solved = Rc::clone(solved);
+        proto_vulcan!(fngoal move |_solver, state| {
+            solved.set(true);
+            Stream::unit(Box::new(state))
+        })
+    }
+
+    #[test]
+    fn test_conj_fail_1() {
+        // A failing conjunct collapses the conjunction to fail when it is built
+        let solved = Rc::new(Cell::new(false));
+        let goal: TestGoal = proto_vulcan!([true, false, expensive_relation(&solved)]);
+        assert!(goal.is_fail());
+        let query = proto_vulcan_query!(|q| { q == 1, goal });
+        assert!(query.run().next().is_none());
+        assert!(!solved.get());
+
+        let solved = Rc::new(Cell::new(false));
+        let goal = Conj::from_vec(vec![expensive_relation(&solved), Goal::fail()]);
+        assert!(goal.is_fail());
+    }
+
+    #[test]
+    fn test_conj_succeed_1() {
+        // Succeeding conjuncts are left out
+        let goal: TestGoal = proto_vulcan!([true, true]);
+        assert!(goal.is_succeed());
+        let goal: TestGoal =
+            InferredConj::from_array(&[Goal::succeed(), Goal::succeed()]).cast_into();
+        assert!(goal.is_succeed());
+
+        let solved = Rc::new(Cell::new(false));
+        let goal: TestGoal = proto_vulcan!([true, expensive_relation(&solved), true]);
+        let query = proto_vulcan_query!(|q| { q == 1, goal });
+        assert_eq!(query.run().next().unwrap().q, 1);
+        assert!(solved.get());
+    }
+}
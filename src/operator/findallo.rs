@@ -0,0 +1,143 @@
+use crate::engine::Engine;
+/// Find all
+///
+/// Collects the reified template of every solution of a goal into a list.
+use crate::goal::{AnyGoal, Goal};
+use crate::lterm::LTerm;
+use crate::solver::{Solve, Solver};
+use crate::state::State;
+use crate::stream::Stream;
+use crate::user::User;
+use std::rc::Rc;
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct FindAll<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    template: LTerm<U, E>,
+    g: Goal<U, E>,
+    result: LTerm<U, E>,
+}
+
+impl<U, E> Solve<U, E> for FindAll<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        // The goal is run against a copy of the state; only the reified templates are kept
+        // from the states of the sub-search.
+        let mut stream = solver.start(&self.g, state.clone());
+        let mut templates = vec![];
+        while solver.peek(&mut stream).is_some() {
+            let solution = match std::mem::replace(&mut stream, Stream::Empty) {
+                Stream::Unit(solution) => solution,
+                Stream::Cons(solution, lazy) => {
+                    stream = Stream::Lazy(lazy);
+                    solution
+                }
+                _ => unreachable!(),
+            };
+            let smap = solution.smap_ref();
+            let v = smap.walk_star(&self.template);
+            templates.push(smap.reify(&v).walk_star(&v));
+        }
+
+        match state.unify(&self.result, &LTerm::from_vec(templates)) {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(error) => solver.fail(error),
+        }
+    }
+}
+
+/// Collects all solutions of a goal into a list.
+///
+/// `findallo(template, goal, result)` runs `goal` in a sub-search from the current state, and
+/// unifies `result` with the list of the values of `template` in each of the solutions, in the
+/// order the solutions are found. If the goal has no solutions, `result` is unified with the
+/// empty list. The variables that are left unbound in a solution are reified into fresh
+/// variables of the list, as with the results of a query.
+///
+/// The substitutions and constraints of the sub-search are not added to the current state.
+/// The goal must have a finite number of solutions; otherwise `findallo` does not terminate.
+/// Like `naf`, `findallo` is non-monotonic: binding the variables of the goal after
+/// `findallo` has been run does not change the collected list.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::operator::findallo;
+/// use proto_vulcan::relation::member;
+/// fn main() {
+///     let x = LTerm::var("x");
+///     let g: Goal<DefaultUser, DefaultEngine<DefaultUser>> = proto_vulcan!(member(x, [1, 2, 3]));
+///     let query = proto_vulcan_query!(|q| { findallo(x, { g }, q) });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().q, lterm!([1, 2, 3]));
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn findallo<U, E>(template: LTerm<U, E>, g: Goal<U, E>, result: LTerm<U, E>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    Goal::dynamic(Rc::new(FindAll {
+        template,
+        g,
+        result,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::findallo;
+    use crate::prelude::*;
+    use crate::relation::member::member;
+
+    type TestGoal = Goal<DefaultUser, DefaultEngine<DefaultUser>>;
+
+    #[test]
+    fn test_findallo_1() {
+        let x = LTerm::var("x");
+        let g: TestGoal = proto_vulcan!(member(x, [1, 2, 3]));
+        let query = proto_vulcan_query!(|q| { findallo(x, { g }, q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([1, 2, 3]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_findallo_2() {
+        // No solutions gives an empty list, and a non-matching list fails
+        let x = LTerm::var("x");
+        let g: TestGoal = proto_vulcan!([member(x, [1, 2, 3]), x == 4]);
+        let query = proto_vulcan_query!(|q| { findallo(x, { g }, q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([]));
+        assert!(iter.next().is_none());
+
+        let x = LTerm::var("x");
+        let g: TestGoal = proto_vulcan!(member(x, [1, 2, 3]));
+        let query = proto_vulcan_query!(|q| { findallo(x, { g }, [1, 2]) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_findallo_3() {
+        // The bindings of the sub-search are not added to the state
+        let x = LTerm::var("x");
+        let y = LTerm::var("y");
+        let g: TestGoal = proto_vulcan!([member(x, [1, 2]), y == x]);
+        let query = proto_vulcan_query!(|q, r| { findallo([x, y], { g }, q), r == x });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.q, lterm!([[1, 1], [2, 2]]));
+        assert!(result.r.is_any());
+        assert!(iter.next().is_none());
+    }
+}
@@ -0,0 +1,174 @@
+use crate::engine::Engine;
+/// If-then-else
+///
+/// Runs the condition, and continues each of its solutions with the then-branch. If the
+/// condition has no solutions, the else-branch is run from the original state.
+use crate::goal::{AnyGoal, Goal};
+use crate::operator::conj::Conj;
+use crate::operator::OperatorParam;
+use crate::solver::{Solve, Solver};
+use crate::state::State;
+use crate::stream::Stream;
+use crate::user::User;
+use std::rc::Rc;
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct Ifte<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    cond: Goal<U, E>,
+    then: Goal<U, E>,
+    otherwise: Goal<U, E>,
+}
+
+impl<U, E> Solve<U, E> for Ifte<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        let mut stream = solver.start(&self.cond, state.clone());
+
+        match solver.peek(&mut stream) {
+            Some(_) => Stream::bind(stream, self.then.clone()),
+            None => solver.start(&self.otherwise, state),
+        }
+    }
+}
+
+/// If-then-else soft cut operator.
+///
+/// `ifte { cond, then, else }` runs the condition `cond`. If it produces at least one
+/// solution, each solution is continued with `then`, and `else` is not run. If the condition
+/// produces no solutions, `else` is run from the original state. Each of the three clauses can
+/// be a conjunction of goals `[g1, g2, ...]`.
+///
+/// Unlike `condu`, the then-branch is run for every solution of the condition, not just the
+/// first one. Unlike `conda`, which commits when the first goal of a clause succeeds, the
+/// whole condition must succeed before the else-branch is discarded.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::operator::ifte;
+/// use proto_vulcan::relation::member;
+/// fn main() {
+///     let query = proto_vulcan_query!(|x, y| {
+///         ifte {
+///             member(x, [1, 2]),
+///             y == "then",
+///             [x == 0, y == "else"],
+///         }
+///     });
+///     let mut iter = query.run();
+///     let result = iter.next().unwrap();
+///     assert_eq!(result.x, 1);
+///     assert_eq!(result.y, "then");
+///     let result = iter.next().unwrap();
+///     assert_eq!(result.x, 2);
+///     assert_eq!(result.y, "then");
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn ifte<U, E>(param: OperatorParam<U, E, Goal<U, E>>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    match param.body {
+        [cond, then, otherwise] => Goal::dynamic(Rc::new(Ifte {
+            cond: Conj::from_vec(cond.to_vec()),
+            then: Conj::from_vec(then.to_vec()),
+            otherwise: Conj::from_vec(otherwise.to_vec()),
+        })),
+        _ => panic!("ifte requires three clauses: condition, then and else"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ifte;
+    use crate::operator::{conda, condu};
+    use crate::prelude::*;
+    use crate::relation::member::member;
+
+    #[test]
+    fn test_ifte_1() {
+        // The then-branch is run for every solution of the condition
+        let query = proto_vulcan_query!(|q| {
+            |x, y| {
+                q == [x, y],
+                ifte {
+                    member(x, [5, 6]),
+                    y == 2,
+                    y == 4,
+                }
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([5, 2]));
+        assert_eq!(iter.next().unwrap().q, lterm!([6, 2]));
+        assert!(iter.next().is_none());
+
+        // whereas condu commits to the first solution of the condition
+        let query = proto_vulcan_query!(|q| {
+            |x, y| {
+                q == [x, y],
+                condu {
+                    [member(x, [5, 6]), y == 2],
+                    y == 4,
+                }
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([5, 2]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_ifte_2() {
+        // The else-branch is run from the original state when the condition fails
+        let query = proto_vulcan_query!(|x, y| {
+            ifte {
+                [x == 1, false],
+                y == 2,
+                y == 4,
+            }
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert!(result.x.is_any());
+        assert_eq!(result.y, 4);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_ifte_3() {
+        // The whole condition must succeed, whereas conda commits to a clause when its first
+        // goal succeeds.
+        let query = proto_vulcan_query!(|x, y| {
+            ifte {
+                [member(x, [1, 2]), x == 3],
+                y == 2,
+                y == 4,
+            }
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert!(result.x.is_any());
+        assert_eq!(result.y, 4);
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|x, y| {
+            conda {
+                [member(x, [1, 2]), x == 3, y == 2],
+                y == 4,
+            }
+        });
+        assert!(query.run().next().is_none());
+    }
+}
@@ -14,6 +14,48 @@
 //! # fn main() {}
 //! ```
 //!
+//! Alternative patterns of an arm are separated with `|`, and they share the body of the arm.
+//! Each of the alternatives must bind the same pattern variables.
+//! ```rust
+//! # extern crate proto_vulcan;
+//! # use proto_vulcan::prelude::*;
+//! pub fn has_oneo<U: User, E: Engine<U>>(l: LTerm<U, E>, other: LTerm<U, E>) -> Goal<U, E> {
+//!     proto_vulcan!(match l {
+//!         [1, x] | [x, 1] => other == x,
+//!     })
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! A list pattern can match any number of leading elements and bind the remainder of the list
+//! with `..rest`. The pattern `[a, b, ..rest]` is equal to `[a, b | rest]`, and `[a, b, ..]`
+//! matches any list with at least two elements.
+//! ```rust
+//! # extern crate proto_vulcan;
+//! # use proto_vulcan::prelude::*;
+//! pub fn swapo<U: User, E: Engine<U>>(l: LTerm<U, E>, s: LTerm<U, E>) -> Goal<U, E> {
+//!     proto_vulcan!(match l {
+//!         [a, b, ..rest] => s == [b, a | rest],
+//!     })
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! An arm can be guarded with a finite domain, written as `if x in range` after the patterns.
+//! The guard is lowered to an `infdrange`-constraint that is part of matching the arm, and
+//! therefore requires the `clpfd`-feature.
+//! ```rust
+//! # extern crate proto_vulcan;
+//! # use proto_vulcan::prelude::*;
+//! pub fn digitso<U: User, E: Engine<U>>(l: LTerm<U, E>, kind: LTerm<U, E>) -> Goal<U, E> {
+//!     proto_vulcan_closure!(match l {
+//!         [x] if x in 0..=9 => kind == "digit",
+//!         [x] if x in 10..=99 => kind == "two digits",
+//!     })
+//! }
+//! # fn main() {}
+//! ```
+//!
 
 use crate::engine::Engine;
 use crate::goal::{Goal, GoalCast};
@@ -28,3 +70,114 @@ where
 {
     Conde::from_conjunctions(param.arms).cast_into()
 }
+
+#[cfg(all(test, feature = "clpfd"))]
+mod test {
+    use crate::operator::matcha;
+    use crate::prelude::*;
+
+    fn digitso<U: User, E: Engine<U>>(l: LTerm<U, E>, kind: LTerm<U, E>) -> Goal<U, E> {
+        proto_vulcan_closure!(match l {
+            [x] if x in 0..=9 => kind == "digit",
+            [x] if x in 10..=99 => kind == "two digits",
+            [_, _] => kind == "pair",
+        })
+    }
+
+    #[test]
+    fn test_matche_guard_1() {
+        let query = proto_vulcan_query!(|q| { digitso([5], q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, "digit");
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_matche_guard_2() {
+        let query = proto_vulcan_query!(|q| { digitso([42], q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, "two digits");
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_matche_guard_3() {
+        let query = proto_vulcan_query!(|q| { digitso([100], q) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_matche_guard_4() {
+        // The guard constrains a fresh variable to the domain of the arm
+        let query = proto_vulcan_query!(|x| { digitso([x], "two digits"), x == 15 });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().x, 15);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_matcha_guard_1() {
+        // Committed choice only commits to an arm whose guard succeeds
+        let query = proto_vulcan_query!(|q| {
+            matcha [50] {
+                [x] if x in 0..=9 => q == "digit",
+                [x] if x in 10..=99 => q == "two digits",
+                _ => q == "other",
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, "two digits");
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_matche_rest_1() {
+        let query = proto_vulcan_query!(|x, y, rest| {
+            match [1, 2, 3, 4] {
+                [a, b, ..r] => [x, y, rest] == [a, b, r],
+            }
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.x, 1);
+        assert_eq!(result.y, 2);
+        assert_eq!(result.rest, lterm!([3, 4]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_matche_rest_2() {
+        // The rest-pattern requires the leading elements, but the rest can be empty
+        let query = proto_vulcan_query!(|q| {
+            match [1, 2] {
+                [_, _, _, ..] => q == "three or more",
+                [_, _, ..rest] => q == rest,
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_matche_alternatives_1() {
+        let query = proto_vulcan_query!(|q, r| {
+            conde {
+                q == [1, 2],
+                q == [3, 1],
+                q == [3, 4],
+            },
+            match q {
+                [1, x] | [x, 1] => r == x,
+            }
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.q, lterm!([1, 2]));
+        assert_eq!(result.r, 2);
+        let result = iter.next().unwrap();
+        assert_eq!(result.q, lterm!([3, 1]));
+        assert_eq!(result.r, 3);
+        assert!(iter.next().is_none());
+    }
+}
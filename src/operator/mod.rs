@@ -55,6 +55,7 @@ use crate::stream::Stream;
 use crate::user::User;
 use std::fmt::Debug;
 use std::marker::PhantomData;
+use std::rc::Rc;
 
 // operator { <body> }
 pub struct OperatorParam<'a, U, E, G>
@@ -184,6 +185,40 @@ where
     }
 }
 
+// conde for x in iter { <body> }
+pub struct CondeForOperatorParam<I, U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+    I: Iterator + Clone + 'static,
+{
+    pub iter: I,
+    // Goal generator: generates the goal of a branch of the disjunction given element from
+    // the iterator.
+    pub g: Rc<dyn Fn(I::Item) -> G>,
+    _phantom: PhantomData<U>,
+    _phantom2: PhantomData<E>,
+}
+
+impl<I, U, E, G> CondeForOperatorParam<I, U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+    I: Iterator + Clone + 'static,
+{
+    #[inline]
+    pub fn new(iter: I, g: Rc<dyn Fn(I::Item) -> G>) -> CondeForOperatorParam<I, U, E, G> {
+        CondeForOperatorParam {
+            iter,
+            g,
+            _phantom: PhantomData,
+            _phantom2: PhantomData,
+        }
+    }
+}
+
 #[cfg(feature = "core")]
 #[doc(hidden)]
 pub mod anyo;
@@ -210,6 +245,9 @@ pub mod disj;
 #[doc(hidden)]
 pub mod everyg;
 
+#[cfg(feature = "extras")]
+#[doc(hidden)]
+pub mod findallo;
 #[cfg(feature = "core")]
 #[doc(hidden)]
 pub mod fngoal;
@@ -222,6 +260,13 @@ pub mod dfs;
 #[doc(hidden)]
 pub mod fresh;
 
+#[cfg(feature = "extras")]
+#[doc(hidden)]
+pub mod ifte;
+#[cfg(feature = "clpfd")]
+#[doc(hidden)]
+pub mod label_ff;
+
 #[cfg(feature = "extras")]
 #[doc(hidden)]
 pub mod matcha;
@@ -234,6 +279,9 @@ pub mod matche;
 #[doc(hidden)]
 pub mod matchu;
 
+#[cfg(feature = "extras")]
+#[doc(hidden)]
+pub mod naf;
 #[cfg(any(feature = "extras", feature = "clpfd"))]
 #[doc(hidden)]
 pub mod onceo;
@@ -262,10 +310,31 @@ pub use conde::conde;
 #[doc(inline)]
 pub use conde::cond;
 
+#[cfg(feature = "core")]
+#[doc(inline)]
+pub use conde::conde_for;
+
+#[cfg(feature = "core")]
+#[doc(inline)]
+pub use conde::conde_fair;
+
 #[cfg(feature = "extras")]
 #[doc(inline)]
 pub use condu::condu;
 
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use findallo::findallo;
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use ifte::ifte;
+#[cfg(feature = "clpfd")]
+#[doc(inline)]
+pub use label_ff::label_ff;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use naf::naf;
 #[cfg(any(feature = "extras", feature = "clpfd"))]
 #[doc(inline)]
 pub use onceo::onceo;
@@ -0,0 +1,132 @@
+use crate::engine::Engine;
+/// Negation as failure
+///
+/// Succeeds once, with the state unchanged, if the conjunction of body goals has no solution
+/// from the current state, and fails otherwise.
+use crate::goal::{AnyGoal, Goal};
+use crate::operator::conj::Conj;
+use crate::operator::OperatorParam;
+use crate::solver::{Solve, Solver};
+use crate::state::State;
+use crate::stream::Stream;
+use crate::user::User;
+use std::rc::Rc;
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct Naf<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    g: Goal<U, E>,
+}
+
+impl<U, E> Solve<U, E> for Naf<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        // The body is run against a copy of the state; its substitutions and constraints are
+        // discarded.
+        let mut stream = solver.start(&self.g, state.clone());
+        match solver.trunc(&mut stream) {
+            Some(_) => Stream::empty(),
+            None => Stream::unit(Box::new(state)),
+        }
+    }
+}
+
+/// Negation as failure operator.
+///
+/// `naf { g1, g2, ... }` succeeds once, leaving the state unchanged, exactly when the
+/// conjunction of the body goals produces no solution from the current state, and fails if
+/// it produces at least one. The search of the body is stopped at the first solution; if the
+/// body has no solutions but does not terminate, neither does `naf`.
+///
+/// Negation as failure is non-monotonic: adding information to the state may change the
+/// result of a `naf`-goal from failure to success. It is sound only when the terms of the
+/// body goals are ground at the time `naf` is run. For example, `naf { q == 5 }` fails when
+/// `q` is fresh, because `q` could still become `5`, even though there are values of `q` for
+/// which the negation holds. Order the goals such that the relevant variables are bound before
+/// the `naf`-goal, or use disequality constraints when possible.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::operator::naf;
+/// use proto_vulcan::relation::member;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| {
+///         member(q, [1, 2, 3, 4]),
+///         naf { member(q, [2, 4]) },
+///     });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().q, 1);
+///     assert_eq!(iter.next().unwrap().q, 3);
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn naf<U, E>(param: OperatorParam<U, E, Goal<U, E>>) -> Goal<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    let g = Conj::from_conjunctions(param.body);
+    Goal::dynamic(Rc::new(Naf { g }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::naf;
+    use crate::prelude::*;
+    use crate::relation::member::member;
+
+    #[test]
+    fn test_naf_1() {
+        // The negated goal fails for a ground term
+        let query = proto_vulcan_query!(|q| {
+            q == 6,
+            naf { q == 5 },
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, 6);
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| {
+            q == 5,
+            naf { q == 5 },
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_naf_2() {
+        // The negated goal succeeds for a fresh variable, therefore naf fails
+        let query = proto_vulcan_query!(|q| {
+            naf { q == 5 },
+            q == 6,
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_naf_3() {
+        // The state is unchanged and the solution is not duplicated
+        let query = proto_vulcan_query!(|q| {
+            |x| {
+                member(q, [1, 2]),
+                naf {
+                    member(x, [3, 4]),
+                    x == q,
+                },
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, 1);
+        assert_eq!(iter.next().unwrap().q, 2);
+        assert!(iter.next().is_none());
+    }
+}
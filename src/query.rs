@@ -1,11 +1,17 @@
 use crate::engine::{DefaultEngine, Engine};
-use crate::goal::Goal;
+use crate::goal::{Goal, GoalCast};
 use crate::lresult::LResult;
-use crate::lterm::LTerm;
-use crate::solver::Solver;
-use crate::state::State;
-use crate::stream::Stream;
+use crate::lterm::{LTerm, LTermInner};
+use crate::lvalue::LValue;
+use crate::operator::conj::Conj;
+use crate::operator::fresh::Fresh;
+use crate::relation::diseq::DisequalityConstraint;
+use crate::relation::eq::eq;
+use crate::solver::{Solver, StepStats};
+use crate::state::{reify, ConstraintError, State};
+use crate::stream::{Lazy, Stream};
 use crate::user::{DefaultUser, User};
+use std::io::{self, Write};
 use std::iter::FusedIterator;
 use std::marker::PhantomData;
 use std::rc::Rc;
@@ -18,6 +24,17 @@ where
     fn from_vec(v: Vec<LResult<U, E>>) -> Self;
 }
 
+/// Results of the query variables in the order of the variables.
+impl<U, E> QueryResult<U, E> for Vec<LResult<U, E>>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn from_vec(v: Vec<LResult<U, E>>) -> Self {
+        v
+    }
+}
+
 pub struct ResultIterator<R, U = DefaultUser, E = DefaultEngine<U>>
 where
     R: QueryResult<U, E>,
@@ -27,6 +44,8 @@ where
     solver: Solver<U, E>,
     variables: Vec<LTerm<U, E>>,
     stream: Stream<U, E>,
+    // Number of solutions that may still be produced, if bounded.
+    remaining: Option<usize>,
     _phantom: PhantomData<R>,
 }
 
@@ -43,27 +62,67 @@ where
         goal: Goal<U, E>,
         initial_state: State<U, E>,
     ) -> ResultIterator<R, U, E> {
-        let stream = solver.start(&goal, initial_state);
+        // The goal is started only when the first result is requested.
+        let stream = Stream::pause(Box::new(initial_state), goal);
         ResultIterator {
             solver,
             variables,
             stream,
+            remaining: None,
             _phantom: PhantomData,
         }
     }
 }
 
-#[doc(hidden)]
-impl<R, U, E> Iterator for ResultIterator<R, U, E>
+impl<R, U, E> ResultIterator<R, U, E>
 where
     R: QueryResult<U, E>,
     U: User,
     E: Engine<U>,
 {
-    type Item = R;
+    /// Converts the iterator into an iterator of structured answers.
+    pub fn answers(self) -> AnswerIterator<R, U, E> {
+        AnswerIterator { inner: self }
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        match self.solver.next(&mut self.stream) {
+    /// Enables recording of the reasons of failed branches.
+    ///
+    /// The recorded reasons can be taken with `take_failures`. When a branch fails because
+    /// of a constraint, the reason identifies the violated constraint and its operands.
+    pub fn record_failures(mut self) -> ResultIterator<R, U, E> {
+        self.solver.set_record_failures(true);
+        self
+    }
+
+    /// Takes the reasons of the branches that have failed since the previous call.
+    pub fn take_failures(&mut self) -> Vec<ConstraintError<U, E>> {
+        self.solver.take_failures()
+    }
+
+    /// Converts the iterator into an iterator of the solved states.
+    ///
+    /// See [`Query::run_states`].
+    pub fn states(self) -> StateIterator<R, U, E> {
+        StateIterator { inner: self }
+    }
+
+    fn next_state(&mut self) -> Option<Box<State<U, E>>> {
+        if self.remaining == Some(0) {
+            return None;
+        }
+        let state = self.solver.next(&mut self.stream)?;
+        if let Some(remaining) = self.remaining.as_mut() {
+            *remaining -= 1;
+            if *remaining == 0 {
+                // The rest of the search is never needed.
+                self.stream = Stream::empty();
+            }
+        }
+        Some(state)
+    }
+
+    fn next_results(&mut self) -> Option<Vec<LResult<U, E>>> {
+        match self.next_state() {
             Some(state) => {
                 // At this point the state has already gone through initial reification
                 // process
@@ -74,17 +133,166 @@ where
                     .variables
                     .iter()
                     .map(|v| {
-                        LResult::<U, E>(state.smap_ref().walk_star(v), Rc::clone(&reified_cstore))
+                        let term = state.smap_ref().walk_star(v);
+                        let domain = state
+                            .query_domain(v)
+                            .or_else(|| state.dstore_ref().get(&term))
+                            .cloned();
+                        LResult::<U, E>::new(term, Rc::clone(&reified_cstore), domain)
                     })
                     .collect();
 
-                Some(R::from_vec(results))
+                Some(results)
             }
             None => None,
         }
     }
 }
 
+/// A structured query answer.
+///
+/// Unlike the per-variable result structs generated by `proto_vulcan_query!`, the answer
+/// collects all partial-answer information into one place.
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"), Clone(bound = "U: User"))]
+pub struct Answer<U = DefaultUser, E = DefaultEngine<U>>
+where
+    U: User,
+    E: Engine<U>,
+{
+    /// Query variable names and their reified values.
+    pub bindings: Vec<(&'static str, LTerm<U, E>)>,
+    /// Residual disequalities. Disequalities that were posted together as a single
+    /// constraint are listed individually; at least one of them must hold.
+    pub disequalities: Vec<(LTerm<U, E>, LTerm<U, E>)>,
+    /// Finite domains of the query variables that were constrained to a finite domain when
+    /// the goal succeeded.
+    pub fd_domains: Vec<(LTerm<U, E>, Vec<isize>)>,
+}
+
+impl<U, E> Answer<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn from_results(variables: &[LTerm<U, E>], results: Vec<LResult<U, E>>) -> Answer<U, E> {
+        let mut disequalities = vec![];
+        if let Some(result) = results.first() {
+            for constraint in result.1.iter() {
+                if let Some(diseq) = constraint.downcast_ref::<DisequalityConstraint<U, E>>() {
+                    for (u, v) in diseq.smap_ref().iter() {
+                        disequalities.push((u.clone(), v.clone()));
+                    }
+                }
+            }
+        }
+
+        let mut bindings = vec![];
+        let mut fd_domains = vec![];
+        for (variable, result) in variables.iter().zip(results) {
+            if let Some(domain) = result.fd_domain() {
+                fd_domains.push((result.0.clone(), domain));
+            }
+            let name = match variable.as_ref() {
+                LTermInner::Var(_, name) => name.as_str(),
+                _ => "_",
+            };
+            bindings.push((name, result.0));
+        }
+
+        Answer {
+            bindings,
+            disequalities,
+            fd_domains,
+        }
+    }
+
+    /// Returns the value bound to the query variable `name`.
+    pub fn get(&self, name: &str) -> Option<&LTerm<U, E>> {
+        self.bindings
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, term)| term)
+    }
+}
+
+/// Iterator over structured query answers.
+pub struct AnswerIterator<R, U = DefaultUser, E = DefaultEngine<U>>
+where
+    R: QueryResult<U, E>,
+    U: User,
+    E: Engine<U>,
+{
+    inner: ResultIterator<R, U, E>,
+}
+
+impl<R, U, E> Iterator for AnswerIterator<R, U, E>
+where
+    R: QueryResult<U, E>,
+    U: User,
+    E: Engine<U>,
+{
+    type Item = Answer<U, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let results = self.inner.next_results()?;
+        Some(Answer::from_results(&self.inner.variables, results))
+    }
+}
+
+impl<R, U, E> FusedIterator for AnswerIterator<R, U, E>
+where
+    R: QueryResult<U, E>,
+    U: User,
+    E: Engine<U>,
+{
+}
+
+/// Iterator over the solved states of a query.
+pub struct StateIterator<R, U = DefaultUser, E = DefaultEngine<U>>
+where
+    R: QueryResult<U, E>,
+    U: User,
+    E: Engine<U>,
+{
+    inner: ResultIterator<R, U, E>,
+}
+
+impl<R, U, E> Iterator for StateIterator<R, U, E>
+where
+    R: QueryResult<U, E>,
+    U: User,
+    E: Engine<U>,
+{
+    type Item = State<U, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next_state().map(|state| *state)
+    }
+}
+
+impl<R, U, E> FusedIterator for StateIterator<R, U, E>
+where
+    R: QueryResult<U, E>,
+    U: User,
+    E: Engine<U>,
+{
+}
+
+#[doc(hidden)]
+impl<R, U, E> Iterator for ResultIterator<R, U, E>
+where
+    R: QueryResult<U, E>,
+    U: User,
+    E: Engine<U>,
+{
+    type Item = R;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_results().map(R::from_vec)
+    }
+}
+
 /* ResultIterator is fused because uncons() will always keep returning None on empty stream */
 #[doc(hidden)]
 impl<R, U, E> FusedIterator for ResultIterator<R, U, E>
@@ -118,8 +326,239 @@ where
         let user_globals = ();
         self.run_with_user(user_state, user_globals)
     }
+
+    /// Runs the query from a caller-supplied initial state instead of an empty state.
+    ///
+    /// The substitution, constraints, domains and user state of `initial_state` apply to the
+    /// query, and the query variables are reified as usual. Domains and constraints can be
+    /// attached to the query variables when the query is created with
+    /// [`from_goal`](Query::from_goal). A state from a previous search can be used to continue
+    /// solving incrementally.
+    ///
+    /// # Example
+    /// ```rust
+    /// extern crate proto_vulcan;
+    /// use proto_vulcan::lresult::LResult;
+    /// use proto_vulcan::prelude::*;
+    /// use proto_vulcan::query::Query;
+    /// use proto_vulcan::relation::member;
+    /// use proto_vulcan::state::{FiniteDomain, State};
+    /// use std::rc::Rc;
+    /// fn main() {
+    ///     let x = LTerm::var("x");
+    ///     let state = State::new(DefaultUser::new())
+    ///         .process_domain(&x, Rc::new(FiniteDomain::from(vec![2, 4])))
+    ///         .unwrap();
+    ///     let goal = proto_vulcan!(member(x, [1, 2, 3, 4]));
+    ///     let query =
+    ///         Query::<Vec<LResult<DefaultUser, DefaultEngine<DefaultUser>>>>::from_goal(goal, vec![x]);
+    ///     let xs: Vec<isize> = query
+    ///         .run_from(state)
+    ///         .answers()
+    ///         .map(|answer| answer.get("x").unwrap().get_number().unwrap())
+    ///         .collect();
+    ///     assert_eq!(xs, vec![2, 4]);
+    /// }
+    /// ```
+    pub fn run_from(
+        &self,
+        initial_state: State<DefaultUser, E>,
+    ) -> ResultIterator<R, DefaultUser, E> {
+        self.run_from_with_user(initial_state, ())
+    }
+
+    /// Runs the query, producing at most `max_solutions` solutions.
+    ///
+    /// Unlike `run().take(max_solutions)`, the search is stopped as soon as the last solution
+    /// has been produced, and the engine does no further work even if the iterator is polled
+    /// again. The search still runs until each of the solutions is found; a goal that diverges
+    /// without producing solutions is not stopped.
+    ///
+    /// # Example
+    /// ```rust
+    /// extern crate proto_vulcan;
+    /// use proto_vulcan::prelude::*;
+    /// fn main() {
+    ///     let query = proto_vulcan_query!(|q| {
+    ///         loop {
+    ///             q == 1,
+    ///         }
+    ///     });
+    ///     assert_eq!(query.run_bounded(3).count(), 3);
+    /// }
+    /// ```
+    pub fn run_bounded(&self, max_solutions: usize) -> ResultIterator<R, DefaultUser, E> {
+        let mut iter = self.run();
+        iter.remaining = Some(max_solutions);
+        iter
+    }
+
+    /// Runs the query and collects all of its results into a vector.
+    ///
+    /// The query must have a finite number of solutions; otherwise `run_all` does not return.
+    /// Use [`run_all_bounded`](Query::run_all_bounded) when the query may diverge.
+    ///
+    /// # Example
+    /// ```rust
+    /// extern crate proto_vulcan;
+    /// use proto_vulcan::prelude::*;
+    /// fn main() {
+    ///     let query = proto_vulcan_query!(|x, y| {
+    ///         [x, 1] != [2, y],
+    ///     });
+    ///     let results = query.run_all();
+    ///     assert_eq!(results.len(), 1);
+    ///     assert!(results[0].x.is_any_except(&2));
+    ///     assert!(results[0].y.is_any_except(&1));
+    /// }
+    /// ```
+    pub fn run_all(&self) -> Vec<R> {
+        self.run().collect()
+    }
+
+    /// Runs the query and collects at most `max_solutions` of its results into a vector.
+    ///
+    /// The search is stopped like with [`run_bounded`](Query::run_bounded).
+    pub fn run_all_bounded(&self, max_solutions: usize) -> Vec<R> {
+        self.run_bounded(max_solutions).collect()
+    }
+
+    /// Runs the query and returns an iterator over the full states of its solutions.
+    ///
+    /// Each state is the full state of a solution, including the substitution, the constraint
+    /// store, the finite-domain store and the user state. For the queries created with
+    /// `proto_vulcan_query!` and [`from_goal`](Query::from_goal), the states are those after
+    /// the query variables have been reified, and the constraints have been enforced: the
+    /// domains have been enumerated into values, therefore there is a state for each value,
+    /// and the domain store is empty. The domains that the query variables had before the
+    /// enumeration are available with [`State::query_domain`]. To inspect the stores before
+    /// enforcement, run the goal with a query created with [`Query::new`], which does not
+    /// reify the variables.
+    ///
+    /// This is an advanced view meant for debugging and inspecting the solver: the contents of
+    /// the states depend on the internal representation of the substitutions and the
+    /// constraints, and they may change between versions.
+    pub fn run_states(&self) -> StateIterator<R, DefaultUser, E> {
+        self.run().states()
+    }
+
+    /// Runs the query with a trace callback that is called on every engine step.
+    ///
+    /// The callback receives the lazy stream node that is about to be stepped, and the
+    /// nesting depth of the step; see [`Solver::set_trace`]. The steps are taken as the
+    /// returned iterator is consumed.
+    ///
+    /// # Example
+    /// ```rust
+    /// extern crate proto_vulcan;
+    /// use proto_vulcan::prelude::*;
+    /// use std::cell::Cell;
+    /// use std::rc::Rc;
+    /// fn main() {
+    ///     let query = proto_vulcan_query!(|q| {
+    ///         conde {
+    ///             q == 1,
+    ///             q == 2,
+    ///         }
+    ///     });
+    ///     let steps = Rc::new(Cell::new(0));
+    ///     let counter = Rc::clone(&steps);
+    ///     let iter = query.run_traced(move |_lazy, _depth| counter.set(counter.get() + 1));
+    ///     assert_eq!(iter.count(), 2);
+    ///     assert!(steps.get() > 0);
+    /// }
+    /// ```
+    pub fn run_traced<F>(&self, trace: F) -> ResultIterator<R, DefaultUser, E>
+    where
+        F: FnMut(&Lazy<DefaultUser, E>, usize) + 'static,
+    {
+        let mut iter = self.run();
+        iter.solver.set_trace(Some(Box::new(trace)));
+        iter
+    }
+
+    /// Runs the query with at most `step_limit` engine steps.
+    ///
+    /// When the limit is reached, the iterator ends with the solutions that were found so far.
+    /// The returned statistics are updated as the iterator is consumed; they report the number
+    /// of `Engine::step` invocations, and whether the search was cut short by the limit. The
+    /// limit counts steps, not time, therefore the outcome is the same on every run.
+    ///
+    /// # Example
+    /// ```rust
+    /// extern crate proto_vulcan;
+    /// use proto_vulcan::prelude::*;
+    /// fn main() {
+    ///     let query = proto_vulcan_query!(|q| {
+    ///         loop {
+    ///             q == 1,
+    ///         }
+    ///     });
+    ///     let (iter, stats) = query.run_with_step_limit(100);
+    ///     assert!(iter.count() > 0);
+    ///     assert_eq!(stats.steps(), 100);
+    ///     assert!(stats.limit_reached());
+    /// }
+    /// ```
+    pub fn run_with_step_limit(
+        &self,
+        step_limit: usize,
+    ) -> (ResultIterator<R, DefaultUser, E>, StepStats) {
+        let mut iter = self.run();
+        iter.solver.set_step_limit(Some(step_limit));
+        let stats = iter.solver.step_stats();
+        (iter, stats)
+    }
+
+    /// Runs the query and returns its only answer.
+    ///
+    /// Returns an error if the query has no solutions or more than one solution. At most two
+    /// solutions are computed.
+    ///
+    /// # Example
+    /// ```rust
+    /// extern crate proto_vulcan;
+    /// use proto_vulcan::prelude::*;
+    /// use proto_vulcan::query::ExactlyOneError;
+    /// use proto_vulcan::relation::member;
+    /// fn main() {
+    ///     let query = proto_vulcan_query!(|q| { member(q, [1]) });
+    ///     assert_eq!(query.exactly_one().unwrap().get("q"), Some(&lterm!(1)));
+    ///
+    ///     let query = proto_vulcan_query!(|q| { member(q, [1, 2]) });
+    ///     assert_eq!(query.exactly_one().err(), Some(ExactlyOneError::MultipleSolutions));
+    /// }
+    /// ```
+    pub fn exactly_one(&self) -> Result<Answer<DefaultUser, E>, ExactlyOneError> {
+        let mut answers = self.run().answers();
+        match (answers.next(), answers.next()) {
+            (Some(answer), None) => Ok(answer),
+            (Some(_), Some(_)) => Err(ExactlyOneError::MultipleSolutions),
+            (None, _) => Err(ExactlyOneError::NoSolutions),
+        }
+    }
+}
+
+/// Error returned by [`Query::exactly_one`] when the query does not have a unique solution.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExactlyOneError {
+    /// The query has no solutions.
+    NoSolutions,
+    /// The query has more than one solution.
+    MultipleSolutions,
 }
 
+impl std::fmt::Display for ExactlyOneError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ExactlyOneError::NoSolutions => write!(f, "query has no solutions"),
+            ExactlyOneError::MultipleSolutions => write!(f, "query has more than one solution"),
+        }
+    }
+}
+
+impl std::error::Error for ExactlyOneError {}
+
 impl<R, U, E> Query<R, U, E>
 where
     R: QueryResult<U, E>,
@@ -134,13 +573,57 @@ where
         }
     }
 
+    /// Creates a query that runs a goal built with the goal combinators.
+    ///
+    /// The values of `variables` are reified after the goal has succeeded, just like for the
+    /// queries created with `proto_vulcan_query!`, and the results are given in the order of
+    /// `variables`.
+    ///
+    /// # Example
+    /// ```rust
+    /// extern crate proto_vulcan;
+    /// use proto_vulcan::lresult::LResult;
+    /// use proto_vulcan::prelude::*;
+    /// use proto_vulcan::query::Query;
+    /// use proto_vulcan::relation::member;
+    /// fn main() {
+    ///     let x = LTerm::var("x");
+    ///     let goal = proto_vulcan!(member(x, [1, 2]));
+    ///     let query =
+    ///         Query::<Vec<LResult<DefaultUser, DefaultEngine<DefaultUser>>>>::from_goal(goal, vec![x]);
+    ///     let mut iter = query.run().answers();
+    ///     assert_eq!(iter.next().unwrap().get("x"), Some(&lterm!(1)));
+    ///     assert_eq!(iter.next().unwrap().get("x"), Some(&lterm!(2)));
+    ///     assert!(iter.next().is_none());
+    /// }
+    /// ```
+    pub fn from_goal(goal: Goal<U, E>, variables: Vec<LTerm<U, E>>) -> Query<R, U, E> {
+        let query = LTerm::var("__query__");
+        let body = Conj::from_array(&[
+            eq(query.clone(), LTerm::from_vec(variables.clone())).cast_into(),
+            goal,
+            reify(query.clone()),
+        ]);
+        let goal = Fresh::new(vec![query], body).cast_into();
+        Query::new(variables, goal)
+    }
+
     pub fn run_with_user(
         &self,
         user_state: U,
         user_globals: U::UserContext,
     ) -> ResultIterator<R, U, E> {
-        let initial_state = State::new(user_state);
-        let user_globals = user_globals;
+        self.run_from_with_user(State::new(user_state), user_globals)
+    }
+
+    /// Runs the query from the `initial_state`, with user context `user_globals`.
+    ///
+    /// See [`run_from`](Query::run_from).
+    pub fn run_from_with_user(
+        &self,
+        initial_state: State<U, E>,
+        user_globals: U::UserContext,
+    ) -> ResultIterator<R, U, E> {
         let solver = Solver::new(user_globals, false);
         ResultIterator::new(
             solver,
@@ -150,3 +633,655 @@ where
         )
     }
 }
+
+/// Handling of non-ground values in `write_solutions_csv`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NonGround {
+    /// Writing fails with `std::io::ErrorKind::InvalidData`.
+    Error,
+    /// The value is written as the given placeholder.
+    Placeholder(String),
+}
+
+/// Converts a ground term into a CSV field. Strings and characters are written without
+/// the quotes of their `Display` representation.
+fn csv_field<U, E>(term: &LTerm<U, E>) -> String
+where
+    U: User,
+    E: Engine<U>,
+{
+    let s = match term.as_ref() {
+        LTermInner::Val(LValue::String(s)) => s.clone(),
+        LTermInner::Val(LValue::Char(c)) => c.to_string(),
+        _ => term.to_string(),
+    };
+    if s.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s
+    }
+}
+
+/// Runs the query and writes the values of variables `vars` of each solution as a CSV row.
+///
+/// The first row is a header with the variable names. Values that are not ground are
+/// handled according to `non_ground`.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::query::{write_solutions_csv, NonGround};
+/// use proto_vulcan::relation::infdrange;
+/// fn main() {
+///     let query = proto_vulcan_query!(|x, y| {
+///         infdrange([x, y], &(1..=2)),
+///     });
+///     let mut csv = Vec::new();
+///     write_solutions_csv(&query, &["x", "y"], &mut csv, NonGround::Error).unwrap();
+///     assert_eq!(String::from_utf8(csv).unwrap(), "x,y\n1,1\n2,1\n1,2\n2,2\n");
+/// }
+/// ```
+pub fn write_solutions_csv<R, E, W>(
+    query: &Query<R, DefaultUser, E>,
+    vars: &[&str],
+    w: &mut W,
+    non_ground: NonGround,
+) -> io::Result<()>
+where
+    R: QueryResult<DefaultUser, E>,
+    E: Engine<DefaultUser>,
+    W: Write,
+{
+    write_solutions_csv_with_user(query, DefaultUser::new(), (), vars, w, non_ground)
+}
+
+/// Runs the query with the given user state and globals, and writes the values of variables
+/// `vars` of each solution as a CSV row, as `write_solutions_csv` does.
+pub fn write_solutions_csv_with_user<R, U, E, W>(
+    query: &Query<R, U, E>,
+    user_state: U,
+    user_globals: U::UserContext,
+    vars: &[&str],
+    w: &mut W,
+    non_ground: NonGround,
+) -> io::Result<()>
+where
+    R: QueryResult<U, E>,
+    U: User,
+    E: Engine<U>,
+    W: Write,
+{
+    writeln!(w, "{}", vars.join(","))?;
+    for answer in query.run_with_user(user_state, user_globals).answers() {
+        let mut fields = Vec::with_capacity(vars.len());
+        for var in vars {
+            let term = answer.get(var).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Unknown query variable \"{}\"", var),
+                )
+            })?;
+            if term.is_ground() {
+                fields.push(csv_field(term));
+            } else {
+                match &non_ground {
+                    NonGround::Error => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("Value of variable \"{}\" is not ground: {}", var, term),
+                        ))
+                    }
+                    NonGround::Placeholder(placeholder) => fields.push(placeholder.clone()),
+                }
+            }
+        }
+        writeln!(w, "{}", fields.join(","))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        write_solutions_csv, write_solutions_csv_with_user, ExactlyOneError, NonGround, Query,
+        QueryResult,
+    };
+    use crate::lresult::LResult;
+    use crate::operator::cond;
+    use crate::prelude::*;
+    use crate::relation::{append, infd, ltfd, member, nats};
+    use crate::state::{FiniteDomain, State};
+    use crate::stream::{Lazy, Stream};
+    use crate::user::User;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_query_free_vars_1() {
+        let query = proto_vulcan_query!(|x, y| { x == 1 });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.free_vars(), vec!["y"]);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_query_free_vars_2() {
+        let query = proto_vulcan_query!(|x, y| { x == y, y == 2 });
+        let mut iter = query.run();
+        assert!(iter.next().unwrap().free_vars().is_empty());
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_query_fd_domain_1() {
+        struct R(LResult<DefaultUser, DefaultEngine<DefaultUser>>);
+
+        impl QueryResult for R {
+            fn from_vec(mut v: Vec<LResult<DefaultUser, DefaultEngine<DefaultUser>>>) -> R {
+                R(v.remove(0))
+            }
+        }
+
+        // Without reification the domain is not expanded into answers
+        let x = LTerm::var("x");
+        let goal = proto_vulcan!([infd(x, &[1, 3, 5]), infd(x, &[1, 2, 3, 5, 7])]);
+        let query = Query::<R>::new(vec![x], goal);
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().0.fd_domain(), Some(vec![1, 3, 5]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_query_fd_domain_2() {
+        // The domain is recorded before it is enumerated into answers
+        let query = proto_vulcan_query!(|x, y| {
+            infd(x, &[1, 3, 5]),
+            infd(x, &[1, 2, 3, 5, 7]),
+            y == 1,
+        });
+        let mut iter = query.run();
+        for value in &[1, 3, 5] {
+            let result = iter.next().unwrap();
+            assert_eq!(result.x, *value);
+            assert_eq!(result.x.fd_domain(), Some(vec![1, 3, 5]));
+            assert_eq!(result.y.fd_domain(), None);
+        }
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_query_answers_1() {
+        struct R;
+
+        impl QueryResult for R {
+            fn from_vec(_v: Vec<LResult<DefaultUser, DefaultEngine<DefaultUser>>>) -> R {
+                R
+            }
+        }
+
+        // Only `x` is reified so that the domain of `y` is not expanded into answers
+        let x = LTerm::var("x");
+        let y = LTerm::var("y");
+        let reified = x.clone();
+        let goal = proto_vulcan!([
+            x != 1,
+            infd(y, &[1, 2, 3]),
+            fngoal move |_engine, state| {
+                let smap = state.smap_ref().reify(&reified);
+                Stream::unit(Box::new(state.with_smap(smap)))
+            }
+        ]);
+        let query = Query::<R>::new(vec![x, y.clone()], goal);
+        let mut iter = query.run().answers();
+        let answer = iter.next().unwrap();
+        let x = answer.get("x").unwrap().clone();
+        assert!(x.is_any());
+        assert_eq!(answer.get("y"), Some(&y));
+        assert_eq!(answer.disequalities, vec![(x, lterm!(1))]);
+        assert_eq!(answer.fd_domains, vec![(y, vec![1, 2, 3])]);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_query_answers_2() {
+        let query = proto_vulcan_query!(|x, y| { x == [1, y] });
+        let mut iter = query.run().answers();
+        let answer = iter.next().unwrap();
+        assert_eq!(answer.bindings.len(), 2);
+        assert_eq!(answer.bindings[0].0, "x");
+        assert_eq!(answer.bindings[1].0, "y");
+        assert!(answer.get("y").unwrap().is_any());
+        assert!(answer.disequalities.is_empty());
+        assert!(answer.fd_domains.is_empty());
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_query_failures_1() {
+        use crate::relation::numbero;
+        use crate::relation::typeo::TypeConstraint;
+        let query = proto_vulcan_query!(|x| {
+            numbero(x),
+            x == "one",
+        });
+        let mut iter = query.run().record_failures();
+        assert!(iter.next().is_none());
+        let failures = iter.take_failures();
+        assert_eq!(failures.len(), 1);
+        let constraint = failures[0].constraint().unwrap();
+        assert!(constraint.is::<TypeConstraint<DefaultUser, DefaultEngine<DefaultUser>>>());
+        assert_eq!(failures[0].operands().len(), 1);
+        assert!(iter.take_failures().is_empty());
+    }
+
+    #[test]
+    fn test_query_failures_2() {
+        // Failures are recorded only when enabled, and unification failures are not caused
+        // by any constraint.
+        let query = proto_vulcan_query!(|x| {
+            x == 1,
+            x == 2,
+        });
+        let mut iter = query.run();
+        assert!(iter.next().is_none());
+        assert!(iter.take_failures().is_empty());
+
+        let mut iter = query.run().record_failures();
+        assert!(iter.next().is_none());
+        let failures = iter.take_failures();
+        assert_eq!(failures.len(), 1);
+        assert!(failures[0].constraint().is_none());
+    }
+
+    #[test]
+    fn test_query_failures_3() {
+        use crate::relation::clpfd::ltefd::LessThanOrEqualFdConstraint;
+        use crate::relation::ltefd;
+        let query = proto_vulcan_query!(|x, y| {
+            infd([x, y], &[1, 2, 3]),
+            x == 3,
+            y == 2,
+            ltefd(x, y),
+        });
+        let mut iter = query.run().record_failures();
+        assert!(iter.next().is_none());
+        let failures = iter.take_failures();
+        let constraint = failures[0].constraint().unwrap();
+        assert!(
+            constraint.is::<LessThanOrEqualFdConstraint<DefaultUser, DefaultEngine<DefaultUser>>>()
+        );
+        assert_eq!(failures[0].operands().len(), 2);
+    }
+
+    #[test]
+    fn test_write_solutions_csv_1() {
+        let query = proto_vulcan_query!(|x, y| {
+            conde {
+                [x == 1, y == "one"],
+                [x == 2, y == "two, three"],
+                [x == [3, 4], y == 'c'],
+            }
+        });
+        let mut csv = Vec::new();
+        write_solutions_csv(&query, &["y", "x"], &mut csv, NonGround::Error).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "y,x\none,1\n\"two, three\",2\nc,\"[3, 4]\"\n"
+        );
+    }
+
+    #[test]
+    fn test_write_solutions_csv_2() {
+        let query = proto_vulcan_query!(|x, y| { x == [1, y] });
+        let mut csv = Vec::new();
+        let result = write_solutions_csv(&query, &["x", "y"], &mut csv, NonGround::Error);
+        assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+
+        let mut csv = Vec::new();
+        let placeholder = NonGround::Placeholder(String::from("_"));
+        write_solutions_csv(&query, &["x", "y"], &mut csv, placeholder).unwrap();
+        assert_eq!(String::from_utf8(csv).unwrap(), "x,y\n_,_\n");
+    }
+
+    #[test]
+    fn test_write_solutions_csv_3() {
+        let query = proto_vulcan_query!(|x| { x == 1 });
+        let mut csv = Vec::new();
+        let result = write_solutions_csv(&query, &["z"], &mut csv, NonGround::Error);
+        assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_write_solutions_csv_4() {
+        // Queries with other user types are written with a given user state
+        let x = LTerm::var("x");
+        let y = LTerm::var("y");
+        let goal = proto_vulcan!(append(x, y, [1]));
+        let query = Query::<Vec<LResult<NamedUser, DefaultEngine<NamedUser>>>, _, _>::from_goal(
+            goal,
+            vec![x, y],
+        );
+        let mut csv = Vec::new();
+        let user = NamedUser::default();
+        write_solutions_csv_with_user(&query, user, (), &["x", "y"], &mut csv, NonGround::Error)
+            .unwrap();
+        assert_eq!(String::from_utf8(csv).unwrap(), "x,y\n[],[1]\n[1],[]\n");
+    }
+
+    #[test]
+    fn test_query_from_goal_1() {
+        type R = Vec<LResult<DefaultUser, DefaultEngine<DefaultUser>>>;
+
+        let x = LTerm::var("x");
+        let y = LTerm::var("y");
+        let goal = proto_vulcan!([x == [1, y], infd(y, &[2, 3])]);
+        let query = Query::<R>::from_goal(goal, vec![x, y]);
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result[0], lterm!([1, 2]));
+        assert_eq!(result[1], 2);
+        let answer = query.run().answers().nth(1).unwrap();
+        assert_eq!(answer.get("x"), Some(&lterm!([1, 3])));
+        assert_eq!(answer.get("y"), Some(&lterm!(3)));
+        assert!(iter.nth(1).is_none());
+    }
+
+    #[test]
+    fn test_query_from_goal_2() {
+        type R = Vec<LResult<DefaultUser, DefaultEngine<DefaultUser>>>;
+
+        // Unbound variables are reified like in `proto_vulcan_query!`
+        let x = LTerm::var("x");
+        let goal = proto_vulcan!(x != 1);
+        let query = Query::<R>::from_goal(goal, vec![x]);
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert!(result[0].is_any_except(&1));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_query_exactly_one_1() {
+        let query = proto_vulcan_query!(|x, y| {
+            member(x, [1]),
+            y == [x, 2],
+        });
+        let answer = query.exactly_one().unwrap();
+        assert_eq!(answer.get("x"), Some(&lterm!(1)));
+        assert_eq!(answer.get("y"), Some(&lterm!([1, 2])));
+    }
+
+    #[test]
+    fn test_query_exactly_one_2() {
+        let query = proto_vulcan_query!(|q| { member(q, []) });
+        assert_eq!(
+            query.exactly_one().err(),
+            Some(ExactlyOneError::NoSolutions)
+        );
+
+        // Only two solutions are computed from an infinite stream
+        let query = proto_vulcan_query!(|q| { nats(0, q) });
+        assert_eq!(
+            query.exactly_one().err(),
+            Some(ExactlyOneError::MultipleSolutions)
+        );
+    }
+
+    #[test]
+    fn test_query_run_bounded_1() {
+        // A divergent goal terminates after the bounded number of solutions
+        let query = proto_vulcan_query!(|q| { nats(0, q) });
+        let solutions: Vec<isize> = query
+            .run_bounded(3)
+            .map(|r| r.q.get_number().unwrap())
+            .collect();
+        assert_eq!(solutions.len(), 3);
+
+        let query = proto_vulcan_query!(|q| { member(q, [1, 2]) });
+        assert_eq!(query.run_bounded(3).count(), 2);
+        assert_eq!(query.run_bounded(0).count(), 0);
+    }
+
+    #[test]
+    fn test_query_run_bounded_2() {
+        // No goals are solved after the last solution
+        let solved = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&solved);
+        let query = proto_vulcan_query!(|q| {
+            nats(0, q),
+            fngoal move |_solver, state| {
+                counter.set(counter.get() + 1);
+                Stream::unit(Box::new(state))
+            },
+        });
+        let mut iter = query.run_bounded(2);
+        assert!(iter.next().is_some());
+        assert!(iter.next().is_some());
+        let solved_before = solved.get();
+        assert!(iter.next().is_none());
+        assert!(iter.next().is_none());
+        assert_eq!(solved.get(), solved_before);
+    }
+
+    #[test]
+    fn test_query_run_all_1() {
+        let query = proto_vulcan_query!(|q| { member(q, [1, 2, 3]) });
+        let results: Vec<isize> = query
+            .run_all()
+            .iter()
+            .map(|r| r.q.get_number().unwrap())
+            .collect();
+        assert_eq!(results, vec![1, 2, 3]);
+
+        let query = proto_vulcan_query!(|q| { member(q, []) });
+        assert!(query.run_all().is_empty());
+
+        // A divergent goal is stopped after the bounded number of solutions
+        let query = proto_vulcan_query!(|q| { nats(0, q) });
+        let results = query.run_all_bounded(3);
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[2].q, 2);
+    }
+
+    #[test]
+    fn test_query_run_from_1() {
+        // The query respects the domains of the initial state
+        let x = LTerm::var("x");
+        let state = State::new(DefaultUser::new())
+            .process_domain(&x, Rc::new(FiniteDomain::from(1..=3)))
+            .unwrap();
+        let goal = proto_vulcan!([infd(x, &[2, 3, 4, 5]), member(x, [1, 2, 3, 4, 5])]);
+        let query = Query::<Vec<LResult<DefaultUser, DefaultEngine<DefaultUser>>>>::from_goal(
+            goal,
+            vec![x.clone()],
+        );
+        let xs: Vec<isize> = query
+            .run_from(state)
+            .answers()
+            .map(|answer| answer.get("x").unwrap().get_number().unwrap())
+            .collect();
+        assert_eq!(xs, vec![2, 3]);
+
+        // whereas from an empty state only the goal applies
+        assert_eq!(query.run().count(), 4);
+    }
+
+    #[test]
+    fn test_query_run_from_2() {
+        // Substitutions of the initial state are kept
+        let x = LTerm::var("x");
+        let state = State::new(DefaultUser::new())
+            .unify(&x, &lterm!(5))
+            .unwrap();
+        let goal = proto_vulcan!(member(x, [4, 5, 6]));
+        let query = Query::<Vec<LResult<DefaultUser, DefaultEngine<DefaultUser>>>>::from_goal(
+            goal,
+            vec![x],
+        );
+        let mut iter = query.run_from(state).answers();
+        assert_eq!(iter.next().unwrap().get("x"), Some(&lterm!(5)));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_query_run_states_1() {
+        // A query without reification leaves the domains in the domain store
+        let x = LTerm::var("x");
+        let y = LTerm::var("y");
+        let goal = proto_vulcan!([infd(x, &[1, 3, 5, 7]), infd(y, &[2, 3, 4]), ltfd(x, y)]);
+        let query = Query::<Vec<LResult<DefaultUser, DefaultEngine<DefaultUser>>>>::new(
+            vec![x.clone(), y.clone()],
+            goal,
+        );
+        let mut iter = query.run_states();
+        let state = iter.next().unwrap();
+        let domain = |v: &LTerm| {
+            let v = state.smap_ref().walk(v);
+            state
+                .dstore_ref()
+                .get(v)
+                .map(|d| d.iter().collect::<Vec<isize>>())
+        };
+        assert_eq!(domain(&x), Some(vec![1, 3]));
+        assert_eq!(domain(&y), Some(vec![2, 3, 4]));
+        assert!(state.constraint_count() > 0);
+        assert!(iter.next().is_none());
+
+        // With reification the domains have been enumerated, and only the recorded query
+        // domains remain
+        let query = proto_vulcan_query!(|x| { infd(x, &[1, 3]) });
+        let states: Vec<_> = query.run_states().collect();
+        assert_eq!(states.len(), 2);
+        assert!(states.iter().all(|state| state.domain_count() == 0));
+        assert!(states
+            .iter()
+            .all(|state| state.query_domain(&query.variables[0]).is_some()));
+    }
+
+    #[test]
+    fn test_query_step_limit_1() {
+        // A search that never produces a solution is stopped
+        let query = proto_vulcan_query!(|q| {
+            nats(0, q),
+            q == -1,
+        });
+        let (mut iter, stats) = query.run_with_step_limit(50);
+        assert!(iter.next().is_none());
+        assert_eq!(stats.steps(), 50);
+        assert!(stats.limit_reached());
+
+        // A search that ends within the limit
+        let query = proto_vulcan_query!(|q| { member(q, [1, 2, 3]) });
+        let (iter, stats) = query.run_with_step_limit(1000);
+        assert_eq!(iter.count(), 3);
+        assert!(stats.steps() > 0);
+        assert!(!stats.limit_reached());
+    }
+
+    #[test]
+    fn test_query_step_limit_2() {
+        // The steps are counted deterministically
+        let query = proto_vulcan_query!(|q| { nats(0, q) });
+        let run = |limit| {
+            let (iter, stats) = query.run_with_step_limit(limit);
+            let solutions: Vec<isize> = iter.map(|r| r.q.get_number().unwrap()).collect();
+            (solutions, stats.steps(), stats.limit_reached())
+        };
+        let (solutions, steps, limit_reached) = run(200);
+        assert!(!solutions.is_empty());
+        assert_eq!(steps, 200);
+        assert!(limit_reached);
+        assert_eq!(run(200), (solutions, steps, limit_reached));
+    }
+
+    #[test]
+    fn test_query_dfs_1() {
+        let query = proto_vulcan_query!(|q| {
+            cond {
+                member(q, [1, 2, 3]),
+                member(q, [4, 5, 6]),
+            }
+        });
+        let answers: Vec<isize> = query.run().map(|r| r.q.get_number().unwrap()).collect();
+        assert_eq!(answers, vec![1, 4, 2, 5, 3, 6]);
+
+        let query = proto_vulcan_query_dfs!(|q| {
+            cond {
+                member(q, [1, 2, 3]),
+                member(q, [4, 5, 6]),
+            }
+        });
+        let answers: Vec<isize> = query.run().map(|r| r.q.get_number().unwrap()).collect();
+        assert_eq!(answers, vec![1, 2, 3, 4, 5, 6]);
+    }
+
+    #[derive(Debug, Clone, Default)]
+    struct NamedUser {}
+
+    impl User for NamedUser {
+        type UserTerm = ();
+        type UserContext = ();
+
+        fn reified_name(index: usize) -> &'static str {
+            // The variables beyond the named ones are left as any-variables
+            ["_.0", "_.1", "_.2", "_.3"]
+                .get(index)
+                .copied()
+                .unwrap_or("_")
+        }
+    }
+
+    #[test]
+    fn test_query_reified_name_1() {
+        // Reified variables are named by the user in the order they appear in the results
+        let x = LTerm::var("x");
+        let y = LTerm::var("y");
+        let z = LTerm::var("z");
+        let goal = proto_vulcan!(append(x, y, z));
+        let query = Query::<Vec<LResult<NamedUser, DefaultEngine<NamedUser>>>, _, _>::from_goal(
+            goal,
+            vec![x, y, z],
+        );
+        let mut iter = query
+            .run_with_user(NamedUser::default(), ())
+            .map(|r| (r[0].0.clone(), r[1].0.clone(), r[2].0.clone()));
+        let (x, y, z) = iter.next().unwrap();
+        assert_eq!(format!("{} {} {}", x, y, z), "[] _.0 _.0");
+        let (x, y, z) = iter.next().unwrap();
+        assert_eq!(format!("{} {} {}", x, y, z), "[_.0] _.1 [_.0 | _.1]");
+
+        // The default names are any-variables
+        let query = proto_vulcan_query!(|q| { |a, b| { q == [a, b, a] } });
+        let q = query.run().next().unwrap().q;
+        let a = q.head().unwrap();
+        let b = q.tail().unwrap().head().unwrap();
+        assert!(a.is_any() && b.is_any());
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_query_run_traced_1() {
+        // The conde-goal is paused when the search starts, and so is its last clause when the
+        // other clauses have been interleaved into the stream.
+        let q = LTerm::var("q");
+        let goal = proto_vulcan!(conde {
+            q == 1,
+            q == 2,
+            q == 3,
+        });
+        let query =
+            Query::<Vec<LResult<DefaultUser, DefaultEngine<DefaultUser>>>>::new(vec![q], goal);
+        let pauses = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&pauses);
+        let iter = query.run_traced(move |lazy, depth| {
+            assert_eq!(depth, 0);
+            if let Lazy::Pause(_, _) = lazy {
+                counter.set(counter.get() + 1);
+            }
+        });
+        assert_eq!(iter.count(), 3);
+        assert_eq!(pauses.get(), 2);
+    }
+}
@@ -0,0 +1,228 @@
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::{LTerm, LTermInner};
+use crate::solver::{Solve, Solver};
+use crate::state::{Constraint, SMap, SResult, State};
+use crate::stream::Stream;
+use crate::user::User;
+use std::rc::Rc;
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct Absento<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    u: LTerm<U, E>,
+    v: LTerm<U, E>,
+}
+
+impl<U, E> Absento<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new<G: AnyGoal<U, E>>(u: LTerm<U, E>, v: LTerm<U, E>) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(Absento { u, v })))
+    }
+}
+
+impl<U, E> Solve<U, E> for Absento<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        match state.run_constraint(AbsentoConstraint::new(self.u.clone(), self.v.clone())) {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(error) => solver.fail(error),
+        }
+    }
+}
+
+/// A relation where term `u` does not occur anywhere in term `v`.
+///
+/// The relation adds a constraint that is verified again whenever the substitution is
+/// extended, therefore `v` cannot later be unified with a term that contains `u`.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::absento;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| {
+///         |x| {
+///             absento("closure", q),
+///             q == [1, x],
+///             x == "closure",
+///         }
+///     });
+///     assert!(query.run().next().is_none());
+/// }
+/// ```
+pub fn absento<U, E, G>(u: LTerm<U, E>, v: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    Absento::new(u, v)
+}
+
+/// Absence constraint
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct AbsentoConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    u: LTerm<U, E>,
+    v: LTerm<U, E>,
+}
+
+impl<U, E> AbsentoConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new(u: LTerm<U, E>, v: LTerm<U, E>) -> Rc<dyn Constraint<U, E>> {
+        Rc::new(AbsentoConstraint { u, v })
+    }
+
+    /// Checks that the ground term `u` is not a subterm of the walked term `v`. Returns `Err`
+    /// if `u` is found, and otherwise whether `v` still contains variables that may later be
+    /// bound to terms containing `u`.
+    fn check(smap: &SMap<U, E>, u: &LTerm<U, E>, v: &LTerm<U, E>) -> Result<bool, ()> {
+        if v.is_var() {
+            return Ok(true);
+        }
+
+        if u == v {
+            return Err(());
+        }
+
+        match v.as_ref() {
+            LTermInner::Cons(head, tail) => {
+                let head_pending = Self::check(smap, u, head)?;
+                let tail_pending = Self::check(smap, u, tail)?;
+                Ok(head_pending || tail_pending)
+            }
+            LTermInner::Compound(object) => {
+                let mut pending = false;
+                for child in object.children() {
+                    pending |= match child.as_term() {
+                        Some(term) => Self::check(smap, u, term)?,
+                        None => Self::check(smap, u, &child.walk_star(smap))?,
+                    };
+                }
+                Ok(pending)
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
+impl<U, E> Constraint<U, E> for AbsentoConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn run(self: Rc<Self>, state: State<U, E>) -> SResult<U, E> {
+        let u = state.smap_ref().walk_star(&self.u);
+        if !u.is_ground() {
+            // The absent term is not yet known, keep the constraint for later.
+            return Ok(state.with_constraint(self));
+        }
+
+        let v = state.smap_ref().walk_star(&self.v);
+        if Self::check(state.smap_ref(), &u, &v)? {
+            // Some parts of `v` are still unknown, keep the constraint for later.
+            Ok(state.with_constraint(self))
+        } else {
+            // The term `v` is fully known and does not contain `u`; the constraint is
+            // no longer needed.
+            Ok(state)
+        }
+    }
+
+    fn operands(&self) -> Vec<LTerm<U, E>> {
+        vec![self.u.clone(), self.v.clone()]
+    }
+}
+
+impl<U, E> std::fmt::Display for AbsentoConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "absento({}, {})", self.u, self.v)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::absento;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_absento_1() {
+        let query = proto_vulcan_query!(|q| {
+            absento("closure", q),
+            q == [1, "closure"],
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_absento_2() {
+        let query = proto_vulcan_query!(|q| {
+            |x, y| {
+                absento("closure", q),
+                q == [1, [x, y]],
+                y == 2,
+                x == "closure",
+            }
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_absento_3() {
+        let query = proto_vulcan_query!(|q| {
+            |x| {
+                absento("closure", q),
+                q == [1, x],
+                x == "lambda",
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([1, "lambda"]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_absento_4() {
+        // The absent term is resolved after the constraint is added
+        let query = proto_vulcan_query!(|q| {
+            |x| {
+                absento([x], q),
+                q == [0, [1]],
+                x == 1,
+            }
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_absento_5() {
+        let query = proto_vulcan_query!(|q| {
+            absento([1, 2], q),
+            q == [0, [1, 2]],
+        });
+        assert!(query.run().next().is_none());
+    }
+}
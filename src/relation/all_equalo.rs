@@ -0,0 +1,200 @@
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::{LTerm, LTermInner};
+use crate::solver::{Solve, Solver};
+use crate::state::{Constraint, ConstraintError, SResult, State};
+use crate::stream::Stream;
+use crate::user::User;
+use std::rc::Rc;
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct AllEqualo<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    list: LTerm<U, E>,
+}
+
+impl<U, E> AllEqualo<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new<G: AnyGoal<U, E>>(list: LTerm<U, E>) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(AllEqualo { list })))
+    }
+}
+
+impl<U, E> Solve<U, E> for AllEqualo<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        match state.run_constraint(AllEqualConstraint::new(self.list.clone())) {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(error) => solver.fail(error),
+        }
+    }
+}
+
+/// A relation where all elements of `list` are equal.
+///
+/// Each element is unified with the first element of the list. If the list is only partially
+/// instantiated, the known elements are unified immediately, and the rest of the list is
+/// constrained until it becomes known. This is the dual of `distinct`.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::all_equalo;
+/// fn main() {
+///     let query = proto_vulcan_query!(|x, y| {
+///         all_equalo([x, y, 5])
+///     });
+///     let result = query.run().next().unwrap();
+///     assert_eq!(result.x, 5);
+///     assert_eq!(result.y, 5);
+/// }
+/// ```
+pub fn all_equalo<U, E, G>(list: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    AllEqualo::new(list)
+}
+
+/// All-equal constraint
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct AllEqualConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    list: LTerm<U, E>,
+}
+
+impl<U, E> AllEqualConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new(list: LTerm<U, E>) -> Rc<dyn Constraint<U, E>> {
+        Rc::new(AllEqualConstraint { list })
+    }
+}
+
+impl<U, E> Constraint<U, E> for AllEqualConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn run(self: Rc<Self>, mut state: State<U, E>) -> SResult<U, E> {
+        let list = state.smap_ref().walk(&self.list).clone();
+        let (first, mut tail) = match list.as_ref() {
+            LTermInner::Var(_, _) => return Ok(state.with_constraint(self)),
+            LTermInner::Empty => return Ok(state),
+            LTermInner::Cons(head, tail) => (head.clone(), tail.clone()),
+            _ => return Err(ConstraintError::new()),
+        };
+
+        loop {
+            let walked_tail = state.smap_ref().walk(&tail).clone();
+            match walked_tail.as_ref() {
+                LTermInner::Empty => return Ok(state),
+                LTermInner::Cons(head, rest) => {
+                    state = state.unify(&first, head)?;
+                    tail = rest.clone();
+                }
+                LTermInner::Var(_, _) => {
+                    // The elements that are already known are equal to the first element. The
+                    // rest of the list is checked when it becomes known.
+                    let rest = LTerm::cons(first, walked_tail);
+                    return Ok(state.with_constraint(AllEqualConstraint::new(rest)));
+                }
+                _ => return Err(ConstraintError::new()),
+            }
+        }
+    }
+
+    fn operands(&self) -> Vec<LTerm<U, E>> {
+        vec![self.list.clone()]
+    }
+}
+
+impl<U, E> std::fmt::Display for AllEqualConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "all_equalo({})", self.list)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::all_equalo;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_all_equalo_1() {
+        let query = proto_vulcan_query!(|x, y| { all_equalo([x, y, 5]) });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.x, 5);
+        assert_eq!(result.y, 5);
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| { all_equalo([]) });
+        assert!(query.run().next().is_some());
+
+        let query = proto_vulcan_query!(|x| { all_equalo([1, x, 2]) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_all_equalo_2() {
+        // The rest of the list is checked when it becomes known
+        let query = proto_vulcan_query!(|q| {
+            |x, t| {
+                all_equalo(q),
+                q == [x | t],
+                x == 1,
+                t == [_, 2],
+            }
+        });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|q| {
+            |x, t| {
+                all_equalo(q),
+                q == [x | t],
+                x == 1,
+                t == [_, _],
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([1, 1, 1]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_all_equalo_3() {
+        // A list whose tail is never known is not enumerated; there is a single answer where
+        // the constraint is kept on the unknown tail
+        let query = proto_vulcan_query!(|q| {
+            all_equalo(q),
+            q == ["a" | _],
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q.head().unwrap(), &lterm!("a"));
+        assert!(iter.next().is_none());
+    }
+}
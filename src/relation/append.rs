@@ -30,14 +30,138 @@ where
     )
 }
 
+/// A length-bounded variant of `append`, where the length of `l` is at most `max_len`.
+///
+/// The recursion of `append` is cut off after `max_len` elements of `l`, therefore the
+/// relation has a finite number of answers even when none of the lists is known.
+///
+/// The bound `max_len` must be a number when the goal is constructed; otherwise the goal
+/// fails.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::append_bounded;
+/// fn main() {
+///     let query = proto_vulcan_query!(|l, s| {
+///         append_bounded(l, s, [1, 2, 3], 1)
+///     });
+///     let mut iter = query.run();
+///     let result = iter.next().unwrap();
+///     assert_eq!(result.l, lterm!([]));
+///     assert_eq!(result.s, lterm!([1, 2, 3]));
+///     let result = iter.next().unwrap();
+///     assert_eq!(result.l, lterm!([1]));
+///     assert_eq!(result.s, lterm!([2, 3]));
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn append_bounded<U, E, G>(
+    l: LTerm<U, E>,
+    s: LTerm<U, E>,
+    ls: LTerm<U, E>,
+    max_len: LTerm<U, E>,
+) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    let max_len = match max_len.get_number() {
+        Some(max_len) => max_len,
+        None => return InferredGoal::new(G::fail()),
+    };
+    if max_len <= 0 {
+        proto_vulcan!([l == [], s == ls])
+    } else {
+        proto_vulcan_closure!(
+            match [l, s, ls] {
+                [[], x, x] => ,
+                [[x | l1], l2, [x | l3]] => append_bounded(l1, l2, l3, {max_len - 1}),
+            }
+        )
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use super::append;
+    use super::{append, append_bounded};
     use crate::prelude::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
 
     #[test]
     fn test_append_1() {
         let query = proto_vulcan_query!(|q| { append([1, 2, 3], [4, 5], q) });
         assert!(query.run().next().unwrap().q == lterm!([1, 2, 3, 4, 5]));
     }
+
+    #[test]
+    fn test_append_bounded_1() {
+        let query = proto_vulcan_query!(|q| { append_bounded([1, 2, 3], [4, 5], q, 3) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([1, 2, 3, 4, 5]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_append_bounded_2() {
+        // The bound is shorter than the first list
+        let query = proto_vulcan_query!(|q| { append_bounded([1, 2, 3], [4, 5], q, 2) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_append_bounded_3() {
+        // In the most general direction the answers are enumerated up to the bound
+        let query = proto_vulcan_query!(|l, s, ls| { append_bounded(l, s, ls, 2) });
+        let mut lengths: Vec<usize> = query
+            .run()
+            .map(|result| {
+                assert!(result.s.is_any());
+                result.l.iter().count()
+            })
+            .collect();
+        lengths.sort_unstable();
+        assert_eq!(lengths, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_append_bounded_4() {
+        // A bound that is not a number fails
+        let query = proto_vulcan_query!(|q| {
+            |n| {
+                append_bounded([1], [2], q, n),
+            }
+        });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|q| { append_bounded([1], [2], q, "two") });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_append_steps() {
+        // The search does not depend on the representation of the substitutions: the answers
+        // and the number of engine steps taken to find them stay the same.
+        let query = proto_vulcan_query!(|x, y| { append(x, y, [1, 2, 3, 4]) });
+        let steps = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&steps);
+        let answers: Vec<(LTerm, LTerm)> = query
+            .run_traced(move |_lazy, _depth| counter.set(counter.get() + 1))
+            .map(|r| (r.x.clone(), r.y.clone()))
+            .collect();
+        assert_eq!(
+            answers,
+            vec![
+                (lterm!([]), lterm!([1, 2, 3, 4])),
+                (lterm!([1]), lterm!([2, 3, 4])),
+                (lterm!([1, 2]), lterm!([3, 4])),
+                (lterm!([1, 2, 3]), lterm!([4])),
+                (lterm!([1, 2, 3, 4]), lterm!([])),
+            ]
+        );
+        assert_eq!(steps.get(), 194);
+    }
 }
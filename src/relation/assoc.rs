@@ -0,0 +1,104 @@
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::operator::cond;
+use crate::user::User;
+
+/// A relation where the association list `alist` contains the pair `[key, value]`.
+///
+/// Each element of `alist` is a two-element list `[k, v]`. The pairs are tried in the order of
+/// the list, therefore the first solution is the first pair that matches `key`. Unlike
+/// `lookupo`, the later pairs with the same key are not shadowed, and when `key` is fresh,
+/// all of the bindings of `alist` are enumerated.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::assoco;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| {
+///         assoco("y", [["x", 1], ["y", 2], ["z", 3]], q)
+///     });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().q, 2);
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn assoco<U, E, G>(
+    key: LTerm<U, E>,
+    alist: LTerm<U, E>,
+    value: LTerm<U, E>,
+) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan_closure!(
+        match alist {
+            [[k, v] | rest] => cond {
+                [k == key, v == value],
+                assoco(key, rest, value),
+            }
+        }
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::assoco;
+    use crate::operator::dfs;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_assoco_1() {
+        // The first matching pair is found first
+        let query = proto_vulcan_query!(|q| { assoco("x", [["x", 1], ["y", 2], ["x", 3]], q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, 1);
+        assert_eq!(iter.next().unwrap().q, 3);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_assoco_2() {
+        let query = proto_vulcan_query!(|q| { assoco("w", [["x", 1], ["y", 2]], q) });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|q| { assoco("x", [], q) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_assoco_3() {
+        // Enumeration of the bindings
+        let query = proto_vulcan_query!(|k, v| { assoco(k, [["x", 1], ["y", 2], ["z", 3]], v) });
+        let mut iter = query.run();
+        for (key, value) in &[("x", 1), ("y", 2), ("z", 3)] {
+            let result = iter.next().unwrap();
+            assert_eq!(result.k, *key);
+            assert_eq!(result.v, *value);
+        }
+        assert!(iter.next().is_none());
+
+        // Reverse lookup
+        let query = proto_vulcan_query!(|q| { assoco(q, [["x", 1], ["y", 2]], 2) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, "y");
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_assoco_dfs() {
+        let query = proto_vulcan_query!(|q| {
+            dfs {
+                assoco("x", [["x", 1], ["y", 2], ["x", 3]], q),
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, 1);
+        assert_eq!(iter.next().unwrap().q, 3);
+        assert!(iter.next().is_none());
+    }
+}
@@ -0,0 +1,292 @@
+use crate::engine::Engine;
+/// Constrains |u| = v finite domains
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::{LTerm, LTermInner};
+use crate::lvalue::LValue;
+use crate::solver::{Solve, Solver};
+use crate::state::{Constraint, ConstraintError, FiniteDomain, SResult, State};
+use crate::stream::Stream;
+use crate::user::User;
+use std::rc::Rc;
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct AbsFd<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    u: LTerm<U, E>,
+    v: LTerm<U, E>,
+}
+
+impl<U, E> AbsFd<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new<G: AnyGoal<U, E>>(u: LTerm<U, E>, v: LTerm<U, E>) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(AbsFd { u, v })))
+    }
+}
+
+impl<U, E> Solve<U, E> for AbsFd<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        match state.run_constraint(AbsFdConstraint::new(self.u.clone(), self.v.clone())) {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(error) => solver.fail(error),
+        }
+    }
+}
+
+/// A finite-domain relation where `|u| = v`.
+///
+/// The domain of `v` is the set of absolute values of the domain of `u`, and the domain of `u`
+/// is the set of the values of the domain of `v` and their negations.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::{absfd, infdrange};
+/// fn main() {
+///     let query = proto_vulcan_query!(|u| {
+///         infdrange(u, &(-5..=5)),
+///         absfd(u, 3),
+///     });
+///     let us: Vec<isize> = query.run().map(|r| r.u.get_number().unwrap()).collect();
+///     assert_eq!(us, vec![-3, 3]);
+/// }
+/// ```
+pub fn absfd<U, E, G>(u: LTerm<U, E>, v: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    AbsFd::new(u, v)
+}
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct AbsFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    u: LTerm<U, E>,
+    v: LTerm<U, E>,
+}
+
+impl<U, E> AbsFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new(u: LTerm<U, E>, v: LTerm<U, E>) -> Rc<dyn Constraint<U, E>> {
+        Rc::new(AbsFdConstraint { u, v })
+    }
+}
+
+/// Domain of the absolute values of `domain`. The value `isize::MIN` does not have an
+/// absolute value, and is left out.
+fn abs_domain(domain: &FiniteDomain) -> FiniteDomain {
+    match domain.intersect(FiniteDomain::from(isize::MIN + 1..=isize::MAX)) {
+        FiniteDomain::Interval(r) if *r.start() >= 0 => FiniteDomain::Interval(r),
+        FiniteDomain::Interval(r) if *r.end() <= 0 => {
+            FiniteDomain::Interval(r.end().abs()..=r.start().abs())
+        }
+        FiniteDomain::Interval(r) => {
+            // An interval crossing zero maps to an interval starting from zero, where the
+            // absolute values of the negative and the positive parts overlap.
+            FiniteDomain::Interval(0..=std::cmp::max(r.start().abs(), *r.end()))
+        }
+        FiniteDomain::Sparse(v) => {
+            // Absolute values of the negative values are in reverse order, and may be equal
+            // to some of the positive values.
+            let mut values: Vec<isize> = v.iter().map(|x| x.abs()).collect();
+            values.sort_unstable();
+            values.dedup();
+            FiniteDomain::Sparse(values)
+        }
+        FiniteDomain::Empty => FiniteDomain::Empty,
+    }
+}
+
+/// Domain of the values whose absolute values are in `domain`. The domain is empty if
+/// `domain` has no non-negative values.
+fn signed_domain(domain: &FiniteDomain) -> FiniteDomain {
+    let positive = domain.intersect(FiniteDomain::from(0..=isize::MAX));
+    let negative = match &positive {
+        FiniteDomain::Interval(r) => FiniteDomain::Interval(-r.end()..=-r.start()),
+        FiniteDomain::Sparse(v) => FiniteDomain::Sparse(v.iter().rev().map(|x| -x).collect()),
+        FiniteDomain::Empty => FiniteDomain::Empty,
+    };
+    negative.union(&positive)
+}
+
+impl<U, E> Constraint<U, E> for AbsFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn run(self: Rc<Self>, state: State<U, E>) -> SResult<U, E> {
+        let smap = state.get_smap();
+        let uwalk = smap.walk(&self.u).clone();
+        let vwalk = smap.walk(&self.v).clone();
+
+        match (uwalk.as_ref(), vwalk.as_ref()) {
+            (LTermInner::Val(LValue::Number(u)), _) => {
+                // The absolute value is known and the constraint can be dropped.
+                match u.checked_abs() {
+                    Some(v) => state.unify(&vwalk, &LTerm::from(v)),
+                    None => Err(ConstraintError::new()),
+                }
+            }
+            (LTermInner::Var(_, _), LTermInner::Var(_, _))
+            | (LTermInner::Var(_, _), LTermInner::Val(LValue::Number(_))) => {
+                let mut state = state;
+                let maybe_udomain = state.dstore_ref().get(&uwalk).cloned();
+                if let Some(udomain) = maybe_udomain {
+                    state = state.process_domain(&vwalk, Rc::new(abs_domain(&udomain)))?;
+                }
+
+                // The domain of `v` may have been narrowed or resolved by the domain of `u`
+                let vwalk = state.smap_ref().walk(&vwalk).clone();
+                let maybe_vdomain = match vwalk.get_number() {
+                    Some(v) => Some(Rc::new(FiniteDomain::from(v))),
+                    None => state.dstore_ref().get(&vwalk).cloned(),
+                };
+                if let Some(vdomain) = maybe_vdomain {
+                    state = state.process_domain(&uwalk, Rc::new(signed_domain(&vdomain)))?;
+                }
+
+                // If `u` was resolved by the domain of `v`, the constraint can be run to
+                // completion; otherwise, it is kept until all variables converge into numbers.
+                if state.smap_ref().walk(&uwalk).is_number() {
+                    state.run_constraint(self)
+                } else {
+                    Ok(state.with_constraint(self))
+                }
+            }
+            _ => Err(ConstraintError::new()), /* Grounded to a term of invalid type */
+        }
+    }
+
+    fn operands(&self) -> Vec<LTerm<U, E>> {
+        vec![self.u.clone(), self.v.clone()]
+    }
+}
+
+impl<U, E> std::fmt::Display for AbsFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "absfd({}, {})", self.u, self.v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::absfd;
+    use crate::prelude::*;
+    use crate::relation::clpfd::infd::{infd, infdrange};
+    use crate::solver::Solver;
+    use crate::state::{FiniteDomain, State};
+
+    #[test]
+    fn test_absfd_1() {
+        // The domain of an interval crossing zero maps to the absolute values
+        let u = LTerm::var("u");
+        let v = LTerm::var("v");
+        let goal: Goal<DefaultUser, DefaultEngine<DefaultUser>> =
+            proto_vulcan!([infdrange(u, &(-3..=2)), absfd(u, v)]);
+        let mut solver = Solver::new((), false);
+        let state = solver
+            .solve_first(&goal, State::new(DefaultUser::new()))
+            .unwrap();
+        let vdomain = state.dstore_ref().get(&v).unwrap();
+        assert!(matches!(vdomain.as_ref(), FiniteDomain::Interval(r) if *r == (0..=3)));
+
+        let query = proto_vulcan_query!(|u, v| {
+            infdrange(u, &(-3..=2)),
+            absfd(u, v),
+        });
+        let result: Vec<(isize, isize)> = query
+            .run()
+            .map(|r| (r.u.get_number().unwrap(), r.v.get_number().unwrap()))
+            .collect();
+        assert_eq!(
+            result,
+            vec![(-3, 3), (-2, 2), (-1, 1), (0, 0), (1, 1), (2, 2)]
+        );
+    }
+
+    #[test]
+    fn test_absfd_2() {
+        // Sparse domains map in both directions
+        let query = proto_vulcan_query!(|v| {
+            |u| {
+                infd(u, &[-2, 1, 2]),
+                absfd(u, v),
+            }
+        });
+        let mut vs: Vec<isize> = query.run().map(|r| r.v.get_number().unwrap()).collect();
+        vs.sort_unstable();
+        assert_eq!(vs, vec![1, 2]);
+
+        let query = proto_vulcan_query!(|u| {
+            |v| {
+                infd(v, &[-1, 0, 2]),
+                absfd(u, v),
+            }
+        });
+        let us: Vec<isize> = query.run().map(|r| r.u.get_number().unwrap()).collect();
+        assert_eq!(us, vec![-2, 0, 2]);
+    }
+
+    #[test]
+    fn test_absfd_3() {
+        let query = proto_vulcan_query!(|v| { absfd(-4, v) });
+        assert_eq!(query.run().next().unwrap().v, 4);
+
+        let query = proto_vulcan_query!(|u| { absfd(u, -4) });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|u| { absfd(u, 0) });
+        assert_eq!(query.run().next().unwrap().u, 0);
+    }
+
+    #[test]
+    fn test_absfd_4() {
+        // isize::MIN does not have an absolute value
+        let query = proto_vulcan_query!(|v| { absfd({ isize::MIN }, v) });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|u, v| {
+            infd(u, &[isize::MIN, -1]),
+            absfd(u, v),
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(
+            (result.u.get_number(), result.v.get_number()),
+            (Some(-1), Some(1))
+        );
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|u, v| {
+            infdrange(u, &(isize::MIN..=isize::MIN + 1)),
+            absfd(u, v),
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().v, isize::MAX);
+        assert!(iter.next().is_none());
+    }
+}
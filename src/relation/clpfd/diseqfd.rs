@@ -4,7 +4,7 @@ use crate::goal::{AnyGoal, InferredGoal};
 use crate::lterm::{LTerm, LTermInner};
 use crate::lvalue::LValue;
 use crate::solver::{Solve, Solver};
-use crate::state::{Constraint, FiniteDomain, SResult, State};
+use crate::state::{Constraint, ConstraintError, FiniteDomain, SResult, State};
 use crate::stream::Stream;
 use crate::user::User;
 use std::rc::Rc;
@@ -35,12 +35,12 @@ where
     U: User,
     E: Engine<U>,
 {
-    fn solve(&self, _solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+    fn solve(&self, solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
         let u = self.u.clone();
         let v = self.v.clone();
-        match DiseqFdConstraint::new(u, v).run(state) {
+        match state.run_constraint(DiseqFdConstraint::new(u, v)) {
             Ok(state) => Stream::unit(Box::new(state)),
-            Err(_) => Stream::empty(),
+            Err(error) => solver.fail(error),
         }
     }
 }
@@ -98,8 +98,6 @@ where
     E: Engine<U>,
 {
     pub fn new(u: LTerm<U, E>, v: LTerm<U, E>) -> Rc<dyn Constraint<U, E>> {
-        assert!(u.is_var() || u.is_number());
-        assert!(v.is_var() || v.is_number());
         Rc::new(DiseqFdConstraint { u, v })
     }
 }
@@ -122,7 +120,7 @@ where
                 singleton_udomain = Rc::new(FiniteDomain::from(*u));
                 Some(&singleton_udomain)
             }
-            _ => None,
+            _ => return Err(ConstraintError::new()), /* Grounded to a term of invalid type */
         };
 
         let v = self.v.clone();
@@ -134,7 +132,7 @@ where
                 singleton_vdomain = Rc::new(FiniteDomain::from(*v));
                 Some(&singleton_vdomain)
             }
-            _ => None,
+            _ => return Err(ConstraintError::new()), /* Grounded to a term of invalid type */
         };
 
         match (maybe_udomain, maybe_vdomain) {
@@ -143,7 +141,7 @@ where
                 // fails in the current state and is dropped; if the values are different, the constraint
                 // succeeds and is dropped.
                 if udomain.min() == vdomain.min() {
-                    Err(())
+                    Err(ConstraintError::new())
                 } else {
                     Ok(state)
                 }
@@ -158,9 +156,9 @@ where
                 // until they can be resolved into singleton, or until they become disjoint.
                 let state = state.with_constraint(self);
                 if udomain.is_singleton() {
-                    state.process_domain(vwalk, Rc::new(vdomain.diff(udomain.as_ref()).ok_or(())?))
+                    state.process_domain(vwalk, Rc::new(vdomain.diff(udomain.as_ref())))
                 } else if vdomain.is_singleton() {
-                    state.process_domain(uwalk, Rc::new(udomain.diff(vdomain.as_ref()).ok_or(())?))
+                    state.process_domain(uwalk, Rc::new(udomain.diff(vdomain.as_ref())))
                 } else {
                     Ok(state)
                 }
@@ -255,4 +253,15 @@ mod tests {
         let mut iter = query.run();
         assert!(iter.next().is_none());
     }
+
+    #[test]
+    fn test_diseqfd_5() {
+        // A list where a number is expected fails instead of panicking
+        let query = proto_vulcan_query!(|q| {
+            infd(q, &[1, 2]),
+            diseqfd(q, [1]),
+        });
+        let mut iter = query.run();
+        assert!(iter.next().is_none());
+    }
 }
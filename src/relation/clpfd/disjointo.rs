@@ -0,0 +1,208 @@
+//! Constrain two lists of finite domain variables to take disjoint sets of values
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::relation::clpfd::diseqfd::DiseqFdConstraint;
+use crate::solver::{Solve, Solver};
+use crate::state::{Constraint, ConstraintError, SResult, State};
+use crate::stream::Stream;
+use crate::user::User;
+use std::rc::Rc;
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct DisjointFd<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    u: LTerm<U, E>,
+    v: LTerm<U, E>,
+}
+
+impl<U, E> DisjointFd<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new<G: AnyGoal<U, E>>(u: LTerm<U, E>, v: LTerm<U, E>) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(DisjointFd { u, v })))
+    }
+}
+
+impl<U, E> Solve<U, E> for DisjointFd<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        let u = self.u.clone();
+        let v = self.v.clone();
+        match state.run_constraint(DisjointFdConstraint::new(u, v)) {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(error) => solver.fail(error),
+        }
+    }
+}
+
+/// Disjointness relation for lists of finite domain variables.
+///
+/// Constrains the set of values taken by the variables in list `u` to be disjoint from the
+/// set of values taken by the variables in list `v`. The constraint is enforced by posting
+/// pairwise `diseqfd` constraints between the elements of the lists once both lists are known.
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::{disjointo, infd};
+/// fn main() {
+///     let query = proto_vulcan_query!(|x, y, z| {
+///         infd([x, y, z], &[1, 2]),
+///         disjointo([x, y], [z]),
+///         x == 1,
+///     });
+///     let mut iter = query.run();
+///     let result = iter.next().unwrap();
+///     assert_eq!(result.x, 1);
+///     assert_eq!(result.y, 1);
+///     assert_eq!(result.z, 2);
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn disjointo<U, E, G>(u: LTerm<U, E>, v: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    DisjointFd::new(u, v)
+}
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct DisjointFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    u: LTerm<U, E>,
+    v: LTerm<U, E>,
+}
+
+impl<U, E> DisjointFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new(u: LTerm<U, E>, v: LTerm<U, E>) -> Rc<dyn Constraint<U, E>> {
+        Rc::new(DisjointFdConstraint { u, v })
+    }
+}
+
+impl<U, E> Constraint<U, E> for DisjointFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn run(self: Rc<Self>, state: State<U, E>) -> SResult<U, E> {
+        let smap = state.get_smap();
+        let u = smap.walk_star(&self.u);
+        let v = smap.walk_star(&self.v);
+
+        if u.is_improper() || v.is_improper() || u.is_var() || v.is_var() {
+            // The lists are not yet fully known, keep the constraint for later.
+            return Ok(state.with_constraint(self));
+        }
+
+        if !u.is_list() || !v.is_list() {
+            return Err(ConstraintError::new());
+        }
+
+        // Both lists are known. Replace the constraint with pairwise disequalities.
+        let mut state = state;
+        for x in u.iter() {
+            for y in v.iter() {
+                if !(x.is_var() || x.is_number()) || !(y.is_var() || y.is_number()) {
+                    // Some elements are grounded to terms of invalid type.
+                    return Err(ConstraintError::new());
+                }
+                state = state.run_constraint(DiseqFdConstraint::new(x.clone(), y.clone()))?;
+            }
+        }
+        Ok(state)
+    }
+
+    fn operands(&self) -> Vec<LTerm<U, E>> {
+        vec![self.u.clone(), self.v.clone()]
+    }
+}
+
+impl<U, E> std::fmt::Display for DisjointFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "disjointo({}, {})", self.u, self.v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{disjointo, DisjointFdConstraint};
+    use crate::prelude::*;
+    use crate::relation::clpfd::infd::infd;
+
+    #[test]
+    fn test_disjointo_1() {
+        let query = proto_vulcan_query!(|x, y| {
+            infd([x, y], &[1, 2, 3]),
+            disjointo([x], [y]),
+            x == 2,
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.x, 2);
+        assert_eq!(result.y, 1);
+        let result = iter.next().unwrap();
+        assert_eq!(result.x, 2);
+        assert_eq!(result.y, 3);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_disjointo_2() {
+        // Two lists sharing a forced value fail
+        let query = proto_vulcan_query!(|x, y, z| {
+            infd([x, y, z], &[1, 2, 3]),
+            disjointo([x, y], [z]),
+            x == 3,
+            z == 3,
+        });
+        let mut iter = query.run();
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_disjointo_3() {
+        // Lists that become known after the constraint is posted
+        let query = proto_vulcan_query!(|a, b| {
+            |x, y| {
+                disjointo(a, b),
+                a == [x],
+                b == [y],
+                infd([x, y], &[4]),
+            }
+        });
+        let mut iter = query.run();
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_disjointo_display() {
+        let c = DisjointFdConstraint::<DefaultUser, DefaultEngine<DefaultUser>>::new(
+            lterm!([1, 2]),
+            lterm!([3]),
+        );
+        assert_eq!(c.to_string(), "disjointo([1, 2], [3])");
+    }
+}
@@ -4,7 +4,7 @@ use crate::goal::{AnyGoal, InferredGoal};
 use crate::lterm::{LTerm, LTermInner};
 use crate::lvalue::LValue;
 use crate::solver::{Solve, Solver};
-use crate::state::{Constraint, FiniteDomain, SResult, State};
+use crate::state::{Constraint, ConstraintError, FiniteDomain, SResult, State};
 use crate::stream::Stream;
 use crate::user::User;
 use std::rc::Rc;
@@ -34,11 +34,11 @@ where
     U: User,
     E: Engine<U>,
 {
-    fn solve(&self, _solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+    fn solve(&self, solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
         let u = self.u.clone();
-        match DistinctFdConstraint::new(u).run(state) {
+        match state.run_constraint(DistinctFdConstraint::new(u)) {
             Ok(state) => Stream::unit(Box::new(state)),
-            Err(_) => Stream::empty(),
+            Err(error) => solver.fail(error),
         }
     }
 }
@@ -68,7 +68,6 @@ where
     E: Engine<U>,
 {
     pub fn new(u: LTerm<U, E>) -> Rc<dyn Constraint<U, E>> {
-        assert!(u.is_list());
         Rc::new(DistinctFdConstraint { u })
     }
 }
@@ -81,7 +80,7 @@ where
     fn run(self: Rc<Self>, state: State<U, E>) -> SResult<U, E> {
         let smap = state.get_smap();
 
-        let v = smap.walk(&self.u);
+        let v = smap.walk_star(&self.u);
         match v.as_ref() {
             LTermInner::Var(_, _) => {
                 // The term has not yet been associated with a list of terms that we want
@@ -89,16 +88,34 @@ where
                 Ok(state.with_constraint(self))
             }
             LTermInner::Empty | LTermInner::Cons(_, _) => {
-                // Partition the list of terms to unresolved variables in `x` and constants in `n`.
-                let (x, n): (LTerm<U, E>, LTerm<U, E>) =
-                    v.iter().cloned().partition(|v| v.is_var());
+                // If the tail of the list is not yet known, the known prefix is constrained
+                // now and the elements that the list is later extended with are included
+                // when the tail becomes known.
+                let (v, tail) = split_list(&v);
+                if !(tail.is_var() || tail.is_empty()) {
+                    return Err(ConstraintError::new());
+                }
+
+                // Terms that are neither variables nor numbers cannot be handled with finite
+                // domains. They are treated as opaque and constrained with tree-disequality
+                // against every other term of the list. Each pair of terms is disunified once.
+                let is_opaque = |t: &LTerm<U, E>| !(t.is_var() || t.is_number());
+                let mut state = state;
+                for (i, t) in v.iter().enumerate() {
+                    for other in v.iter().skip(i + 1) {
+                        if is_opaque(t) || is_opaque(other) {
+                            state = state.disunify(t, other)?;
+                        }
+                    }
+                }
 
-                // Convert list of LTerm constants to Vec<usize>
-                let mut n = n
+                // Partition the list of terms to unresolved variables in `x` and constants in `n`.
+                let x: LTerm<U, E> = v.iter().filter(|t| t.is_var()).cloned().collect();
+                let mut n = v
                     .iter()
-                    .map(|t| match t.as_ref() {
-                        LTermInner::Val(LValue::Number(u)) => *u,
-                        _ => panic!("Invalid constant constraint {:?}", t),
+                    .filter_map(|t| match t.as_ref() {
+                        LTermInner::Val(LValue::Number(u)) => Some(*u),
+                        _ => None,
                     })
                     .collect::<Vec<isize>>();
 
@@ -121,18 +138,18 @@ where
                 if no_duplicates {
                     // There are no duplicate constant constraints. Create a new constraint
                     // to follow the fulfillment of the variable domain constraints.
-                    let c = DistinctFd2Constraint::new(self.u.clone(), x, n);
-                    Ok(state.with_constraint(c))
+                    let c = DistinctFd2Constraint::new(v.clone(), x, n, tail);
+                    state.run_constraint(c)
                 } else {
                     // If there are duplicate constants in the array, then the constraint is
                     // already violated.
-                    Err(())
+                    Err(ConstraintError::new())
                 }
             }
-            _ => panic!(
-                "Cannot constrain {:?}. The variable must be grounded to a list of terms.",
-                v
-            ),
+            _ => {
+                // The term is grounded to something else than a list of terms.
+                Err(ConstraintError::new())
+            }
         }
     }
 
@@ -151,6 +168,22 @@ where
     }
 }
 
+/// Splits a walked list into a proper list of its known elements and the tail that follows
+/// them. The tail of a proper list is the empty list.
+fn split_list<U, E>(v: &LTerm<U, E>) -> (LTerm<U, E>, LTerm<U, E>)
+where
+    U: User,
+    E: Engine<U>,
+{
+    let mut elements = vec![];
+    let mut rest = v;
+    while let LTermInner::Cons(head, tail) = rest.as_ref() {
+        elements.push(head.clone());
+        rest = tail;
+    }
+    (elements.into_iter().collect(), rest.clone())
+}
+
 #[derive(Derivative)]
 #[derivative(Debug(bound = "U: User"), Clone(bound = "U: User"))]
 pub struct DistinctFd2Constraint<U, E>
@@ -161,6 +194,8 @@ where
     u: LTerm<U, E>,
     y: LTerm<U, E>,
     n: Vec<isize>,
+    // Unknown tail of the list `u`, or empty list if the list is complete.
+    tail: LTerm<U, E>,
 }
 
 impl<U, E> DistinctFd2Constraint<U, E>
@@ -168,10 +203,24 @@ where
     U: User,
     E: Engine<U>,
 {
-    pub fn new(u: LTerm<U, E>, y: LTerm<U, E>, n: Vec<isize>) -> Rc<dyn Constraint<U, E>> {
+    pub fn new(
+        u: LTerm<U, E>,
+        y: LTerm<U, E>,
+        n: Vec<isize>,
+        tail: LTerm<U, E>,
+    ) -> Rc<dyn Constraint<U, E>> {
         assert!(u.is_list());
         assert!(y.is_list());
-        Rc::new(DistinctFd2Constraint { u, y, n })
+        Rc::new(DistinctFd2Constraint { u, y, n, tail })
+    }
+
+    /// The constrained list, including the unknown tail if the list is not yet complete.
+    pub fn list(&self) -> LTerm<U, E> {
+        let elements: Vec<LTerm<U, E>> = self.u.iter().cloned().collect();
+        elements
+            .into_iter()
+            .rev()
+            .fold(self.tail.clone(), |tail, head| LTerm::cons(head, tail))
     }
 }
 
@@ -182,9 +231,25 @@ where
 {
     fn run(mut self: Rc<Self>, state: State<U, E>) -> SResult<U, E> {
         let smap = state.get_smap();
+        let mut state = state;
 
         let mut x = LTerm::empty_list();
         let mut mself = Rc::make_mut(&mut self);
+        let fixed_before = mself.n.len();
+
+        if !mself.tail.is_empty() {
+            // The list has been extended since the previous run. The new elements are
+            // added to `u` and to the unresolved terms in `y`, from where they are handled
+            // in the same way as the original elements.
+            let (extension, tail) = split_list(&smap.walk_star(&mself.tail));
+            if !(tail.is_var() || tail.is_empty()) {
+                return Err(ConstraintError::new());
+            }
+            mself.u.extend(extension.iter().cloned());
+            mself.y.extend(extension.iter().cloned());
+            mself.tail = tail;
+        }
+
         for y in mself.y.into_iter() {
             let ywalk = smap.walk(&y);
             match ywalk.as_ref() {
@@ -194,37 +259,55 @@ where
                     // constraints.
                     x.extend(Some(y.clone()));
                 }
-                LTermInner::Val(val) => {
+                LTermInner::Val(LValue::Number(u)) => {
                     // A variable has been associated with a value and can be moved from y to n.
-                    match val {
-                        LValue::Number(u) => {
-                            match mself.n.binary_search(u) {
-                                Ok(_) => {
-                                    // Duplicate invalidates the constraint
-                                    return Err(());
-                                }
-                                Err(pos) => {
-                                    // Add the previously unseen value to the list of constant
-                                    // constraints.
-                                    mself.n.insert(pos, *u);
-                                }
-                            }
+                    match mself.n.binary_search(u) {
+                        Ok(_) => {
+                            // Duplicate invalidates the constraint
+                            return Err(ConstraintError::new());
+                        }
+                        Err(pos) => {
+                            // Add the previously unseen value to the list of constant
+                            // constraints.
+                            mself.n.insert(pos, *u);
+                        }
+                    }
+                }
+                _ => {
+                    // A variable has been associated with a term that is not a number. The
+                    // term is treated as opaque and constrained with tree-disequality against
+                    // the other terms of the list.
+                    let mut skipped = false;
+                    for other in mself.u.iter() {
+                        if !skipped && LTerm::ptr_eq(other, y) {
+                            skipped = true;
+                        } else {
+                            state = state.disunify(ywalk, other)?;
                         }
-                        _ => panic!("Invalid value {:?} in constraint", val),
                     }
                 }
-                _ => panic!("Invalid LTerm  {:?} in constraint", ywalk),
             }
         }
 
         // Create a new all-diff constraint with (hopefully) less unassociated variables in y and
         // more constants in n.
         mself.y = x.clone();
-        if mself.n.is_empty() {
-            Ok(state.with_constraint(self))
+        let fixed_any = mself.n.len() > fixed_before;
+        let state = if mself.n.is_empty() {
+            state.with_constraint(self)
         } else {
             let ndomain = Rc::new(FiniteDomain::from(mself.n.clone()));
-            state.with_constraint(self).exclude_from_domain(&x, ndomain)
+            state
+                .with_constraint(self)
+                .exclude_from_domain(&x, ndomain)?
+        };
+
+        if fixed_any {
+            // Removing the new constants from the domains is cheap, and may resolve more
+            // variables; the matching is computed when the constraint runs next time.
+            Ok(state)
+        } else {
+            prune_unmatchable(state, &x)
         }
     }
 
@@ -233,6 +316,271 @@ where
     }
 }
 
+/// Largest total number of variable-value pairs for which the maximum matching is computed.
+/// Larger domains are propagated only by removing the resolved values.
+const MAX_MATCHING_EDGES: usize = 1 << 16;
+
+/// Removes from the domains of variables in list `x` the values that cannot be taken in any
+/// assignment of distinct values to all of the variables; for example, if two variables have
+/// domain `[1, 2]`, then neither value is available for the other variables.
+///
+/// The pruning is done only when all of the variables have domains.
+fn prune_unmatchable<U, E>(mut state: State<U, E>, x: &LTerm<U, E>) -> SResult<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    let mut vars: Vec<LTerm<U, E>> = vec![];
+    let mut domains: Vec<Vec<isize>> = vec![];
+    let mut edges = 0;
+    for y in x {
+        let ywalk = state.smap_ref().walk(y);
+        if !ywalk.is_var() {
+            return Ok(state);
+        }
+        if vars.contains(ywalk) {
+            // The same variable cannot have two distinct values.
+            return Err(ConstraintError::new());
+        }
+        match state.dstore_ref().get(ywalk) {
+            Some(domain) => {
+                edges = domain.len().saturating_add(edges);
+                if edges > MAX_MATCHING_EDGES {
+                    return Ok(state);
+                }
+                vars.push(ywalk.clone());
+                domains.push(domain.to_vec());
+            }
+            None => return Ok(state),
+        }
+    }
+
+    let supported = supported_values(&domains).ok_or_else(ConstraintError::new)?;
+    for ((var, domain), values) in vars.iter().zip(domains.iter()).zip(supported) {
+        if values.len() < domain.len() {
+            // Earlier updates may have resolved the variable; walk it again.
+            let var = state.smap_ref().walk(var).clone();
+            state = state.process_domain(&var, Rc::new(FiniteDomain::from(values)))?;
+        }
+    }
+    Ok(state)
+}
+
+/// Computes for each of the variables with the given `domains` the values that the variable
+/// takes in at least one assignment where all variables have distinct values. Returns `None`
+/// if there is no such assignment.
+///
+/// This is the filtering algorithm of Régin: a maximum matching between variables and values
+/// is found with augmenting paths, and a value is supported if it is matched to the variable,
+/// or if the edge between them is on an even alternating path starting from a free value, or
+/// on an alternating cycle.
+fn supported_values(domains: &[Vec<isize>]) -> Option<Vec<Vec<isize>>> {
+    let mut values: Vec<isize> = domains.iter().flatten().copied().collect();
+    values.sort_unstable();
+    values.dedup();
+    let nvars = domains.len();
+    let nvalues = values.len();
+    if nvalues < nvars {
+        return None;
+    }
+
+    let adjacency: Vec<Vec<usize>> = domains
+        .iter()
+        .map(|domain| {
+            domain
+                .iter()
+                .map(|value| values.binary_search(value).unwrap())
+                .collect()
+        })
+        .collect();
+
+    // Maximum matching
+    let mut var_match = vec![0; nvars];
+    let mut value_match: Vec<Option<usize>> = vec![None; nvalues];
+    for var in 0..nvars {
+        let mut visited = vec![false; nvalues];
+        if !augment(var, &adjacency, &mut visited, &mut value_match) {
+            return None;
+        }
+    }
+    for (value, matched) in value_match.iter().enumerate() {
+        if let Some(var) = matched {
+            var_match[*var] = value;
+        }
+    }
+
+    // Directed graph where variables are nodes `0..nvars` and values are nodes
+    // `nvars..nvars + nvalues`. Matched edges point from variables to values, and the other
+    // edges from values to variables.
+    let mut graph: Vec<Vec<usize>> = vec![vec![]; nvars + nvalues];
+    for (var, var_values) in adjacency.iter().enumerate() {
+        for &value in var_values.iter() {
+            if var_match[var] == value {
+                graph[var].push(nvars + value);
+            } else {
+                graph[nvars + value].push(var);
+            }
+        }
+    }
+
+    // Nodes reachable from the free values
+    let mut reachable = vec![false; nvars + nvalues];
+    let mut stack: Vec<usize> = (0..nvalues)
+        .filter(|value| value_match[*value].is_none())
+        .map(|value| nvars + value)
+        .collect();
+    while let Some(node) = stack.pop() {
+        if !reachable[node] {
+            reachable[node] = true;
+            stack.extend(graph[node].iter().copied());
+        }
+    }
+
+    let component = strongly_connected_components(&graph);
+
+    let supported = adjacency
+        .iter()
+        .enumerate()
+        .map(|(var, var_values)| {
+            var_values
+                .iter()
+                .filter(|&&value| {
+                    var_match[var] == value
+                        || reachable[nvars + value]
+                        || component[var] == component[nvars + value]
+                })
+                .map(|&value| values[value])
+                .collect()
+        })
+        .collect();
+    Some(supported)
+}
+
+/// Finds an augmenting path from variable `var` and flips the matching along it.
+///
+/// The path is searched depth-first with an explicit stack of variables and the positions of
+/// the next values to try in their adjacency lists, so that long paths do not overflow the
+/// call stack.
+fn augment(
+    var: usize,
+    adjacency: &[Vec<usize>],
+    visited: &mut [bool],
+    value_match: &mut [Option<usize>],
+) -> bool {
+    let mut stack: Vec<(usize, usize)> = vec![(var, 0)];
+    // The value through which the path continues from each variable of the stack
+    let mut path: Vec<usize> = vec![];
+    while let Some(&(var, position)) = stack.last() {
+        if position == adjacency[var].len() {
+            stack.pop();
+            path.pop();
+            continue;
+        }
+        stack.last_mut().unwrap().1 += 1;
+
+        let value = adjacency[var][position];
+        if visited[value] {
+            continue;
+        }
+        visited[value] = true;
+        path.push(value);
+        match value_match[value] {
+            None => {
+                for (&(var, _), &value) in stack.iter().zip(path.iter()) {
+                    value_match[value] = Some(var);
+                }
+                return true;
+            }
+            Some(other) => stack.push((other, 0)),
+        }
+    }
+    false
+}
+
+/// Returns the strongly connected component of each node of the `graph`, computed with
+/// Tarjan's algorithm. The depth-first search keeps its own stack of nodes and edge positions
+/// instead of recursing.
+fn strongly_connected_components(graph: &[Vec<usize>]) -> Vec<usize> {
+    struct Tarjan<'a> {
+        graph: &'a [Vec<usize>],
+        index: Vec<Option<usize>>,
+        lowlink: Vec<usize>,
+        on_stack: Vec<bool>,
+        stack: Vec<usize>,
+        component: Vec<usize>,
+        next_index: usize,
+        next_component: usize,
+    }
+
+    impl<'a> Tarjan<'a> {
+        fn enter(&mut self, node: usize) {
+            self.index[node] = Some(self.next_index);
+            self.lowlink[node] = self.next_index;
+            self.next_index += 1;
+            self.stack.push(node);
+            self.on_stack[node] = true;
+        }
+
+        fn leave(&mut self, node: usize) {
+            if Some(self.lowlink[node]) == self.index[node] {
+                while let Some(member) = self.stack.pop() {
+                    self.on_stack[member] = false;
+                    self.component[member] = self.next_component;
+                    if member == node {
+                        break;
+                    }
+                }
+                self.next_component += 1;
+            }
+        }
+
+        fn visit(&mut self, root: usize) {
+            // Nodes of the depth-first search and the positions of their next edges
+            let mut calls: Vec<(usize, usize)> = vec![(root, 0)];
+            self.enter(root);
+            while let Some(&(node, position)) = calls.last() {
+                if let Some(&next) = self.graph[node].get(position) {
+                    calls.last_mut().unwrap().1 += 1;
+                    match self.index[next] {
+                        None => {
+                            self.enter(next);
+                            calls.push((next, 0));
+                        }
+                        Some(index) if self.on_stack[next] => {
+                            self.lowlink[node] = self.lowlink[node].min(index);
+                        }
+                        Some(_) => (),
+                    }
+                } else {
+                    calls.pop();
+                    self.leave(node);
+                    if let Some(&(parent, _)) = calls.last() {
+                        self.lowlink[parent] = self.lowlink[parent].min(self.lowlink[node]);
+                    }
+                }
+            }
+        }
+    }
+
+    let n = graph.len();
+    let mut tarjan = Tarjan {
+        graph,
+        index: vec![None; n],
+        lowlink: vec![0; n],
+        on_stack: vec![false; n],
+        stack: vec![],
+        component: vec![0; n],
+        next_index: 0,
+        next_component: 0,
+    };
+    for node in 0..n {
+        if tarjan.index[node].is_none() {
+            tarjan.visit(node);
+        }
+    }
+    tarjan.component
+}
+
 impl<U, E> std::fmt::Display for DistinctFd2Constraint<U, E>
 where
     U: User,
@@ -245,11 +593,14 @@ where
 
 #[cfg(test)]
 mod tests {
-    use super::distinctfd;
+    use super::{distinctfd, supported_values};
+    use crate::engine::DefaultEngine;
     use crate::prelude::*;
     use crate::relation::clpfd::diseqfd::diseqfd;
     use crate::relation::clpfd::infd::{infd, infdrange};
     use crate::relation::clpfd::ltefd::ltefd;
+    use crate::state::State;
+    use crate::user::DefaultUser;
 
     #[test]
     fn test_distinctfd_1() {
@@ -338,4 +689,276 @@ mod tests {
         let mut iter = query.run();
         assert!(iter.next().is_none());
     }
+
+    #[test]
+    fn test_distinctfd_8() {
+        // A number where a list is expected fails instead of panicking
+        let query = proto_vulcan_query!(|q| {
+            distinctfd(q),
+            q == 5,
+        });
+        let mut iter = query.run();
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_distinctfd_9() {
+        // A list element bound to a non-number is treated as an opaque term
+        let query = proto_vulcan_query!(|q, x| {
+            infd(q, &[1, 2]),
+            distinctfd([q, x]),
+            x == "a",
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.q, 1);
+        assert_eq!(result.x, "a");
+        let result = iter.next().unwrap();
+        assert_eq!(result.q, 2);
+        assert_eq!(result.x, "a");
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_distinctfd_10() {
+        let query = proto_vulcan_query!(|a| {
+            infdrange(a, &(0..=2)),
+            distinctfd([a, 1, "x"]),
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().a, 0);
+        assert_eq!(iter.next().unwrap().a, 2);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_distinctfd_11() {
+        // Opaque terms are distinct from each other
+        let query = proto_vulcan_query!(|q| {
+            |x| {
+                distinctfd([x, 1, "x"]),
+                x == "x",
+            }
+        });
+        let mut iter = query.run();
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_distinctfd_12() {
+        // Elements in the tail of the list are included when it becomes known
+        let query = proto_vulcan_query!(|q| {
+            |t| {
+                distinctfd([1 | t]),
+                t == [1],
+            }
+        });
+        let mut iter = query.run();
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_distinctfd_13() {
+        // The known prefix of a list is constrained before the tail is known
+        let query = proto_vulcan_query!(|x, y| {
+            |t| {
+                infdrange([x, y], &(1..=2)),
+                distinctfd([x, y | t]),
+                x == 1,
+            }
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.x, 1);
+        assert_eq!(result.y, 2);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_distinctfd_14() {
+        // The list is extended in several steps after the constraint is posted
+        let query = proto_vulcan_query!(|q| {
+            |x, y, z, t1, t2| {
+                infdrange([x, y, z], &(1..=3)),
+                distinctfd([x | t1]),
+                t1 == [y | t2],
+                x == 1,
+                y == 2,
+                t2 == [z],
+                q == [x, y, z],
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([1, 2, 3]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_distinctfd_15() {
+        let query = proto_vulcan_query!(|q| {
+            |t| {
+                distinctfd([1, 2 | t]),
+                t == [3, 2],
+            }
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_distinctfd_16() {
+        // Opaque elements added to the tail are distinct from the prefix
+        let query = proto_vulcan_query!(|t| {
+            distinctfd(["a", 1 | t]),
+            t == ["a"],
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_distinctfd_17() {
+        // Answer order is identical across repeated runs
+        let run = || {
+            let query = proto_vulcan_query!(|x, y, z| {
+                distinctfd([x, y, z]),
+                infd(x, &[1, 2, 3, 4]),
+                infd(y, &[1, 2]),
+                infd(z, &[1, 2, 3]),
+                diseqfd(x, 2),
+                conde {
+                    x == 1,
+                    x == 3,
+                    x == 4,
+                },
+            });
+            query
+                .run()
+                .map(|result| result.to_string())
+                .collect::<Vec<String>>()
+        };
+        let first = run();
+        assert_eq!(first.len(), 7);
+        for _ in 0..20 {
+            assert_eq!(run(), first);
+        }
+    }
+
+    #[test]
+    fn test_distinctfd_18() {
+        // Two variables with domain [1, 2] leave only 3 for the third variable, without
+        // enumerating the domains.
+        let x = LTerm::var("x");
+        let y = LTerm::var("y");
+        let z = LTerm::var("z");
+        let goal: Goal<DefaultUser, DefaultEngine<DefaultUser>> = proto_vulcan!([
+            infd([x, y], &[1, 2]),
+            infdrange(z, &(1..=3)),
+            distinctfd([x, y, z]),
+        ]);
+        let mut solver = Solver::new((), false);
+        let state = solver
+            .solve_first(&goal, State::new(DefaultUser::new()))
+            .unwrap();
+        assert_eq!(state.smap_ref().walk(&z), &LTerm::from(3));
+    }
+
+    #[test]
+    fn test_distinctfd_19() {
+        assert_eq!(
+            supported_values(&[vec![1, 2], vec![1, 2], vec![1, 2, 3, 4], vec![2, 3]]),
+            Some(vec![vec![1, 2], vec![1, 2], vec![4], vec![3]])
+        );
+        assert_eq!(
+            supported_values(&[vec![1, 2], vec![2, 3], vec![1, 3]]),
+            Some(vec![vec![1, 2], vec![2, 3], vec![1, 3]])
+        );
+        assert_eq!(supported_values(&[vec![1], vec![1, 2], vec![1, 2]]), None);
+    }
+
+    type DefaultGoal = Goal<DefaultUser, DefaultEngine<DefaultUser>>;
+
+    /// Number of failed branches before the first solution of a 4x4 sudoku, where the
+    /// all-different constraints of rows, columns and squares are given by `alldiff`.
+    fn sudoku_failures(alldiff: fn(LTerm) -> DefaultGoal) -> usize {
+        let query = proto_vulcan_query!(|q| {
+            |a1, a2, a3, a4, b1, b2, b3, b4, c1, c2, c3, c4, d1, d2, d3, d4| {
+                q == [a1, a2, a3, a4, b1, b2, b3, b4, c1, c2, c3, c4, d1, d2, d3, d4],
+                infdrange([a1, a2, a3, a4, b1, b2, b3, b4, c1, c2, c3, c4, d1, d2, d3, d4], &(1..=4)),
+                a1 == 1,
+                b3 == 2,
+                alldiff([a1, a2, a3, a4]),
+                alldiff([b1, b2, b3, b4]),
+                alldiff([c1, c2, c3, c4]),
+                alldiff([d1, d2, d3, d4]),
+                alldiff([a1, b1, c1, d1]),
+                alldiff([a2, b2, c2, d2]),
+                alldiff([a3, b3, c3, d3]),
+                alldiff([a4, b4, c4, d4]),
+                alldiff([a1, a2, b1, b2]),
+                alldiff([a3, a4, b3, b4]),
+                alldiff([c1, c2, d1, d2]),
+                alldiff([c3, c4, d3, d4]),
+            }
+        });
+        let mut iter = query.run().record_failures();
+        assert!(iter.next().is_some());
+        iter.take_failures().len()
+    }
+
+    #[test]
+    fn test_distinctfd_20() {
+        // Pairwise disequality propagates only resolved values, as distinctfd did before
+        // the matching was added.
+        fn pairwise(u: LTerm) -> DefaultGoal {
+            let terms: Vec<LTerm> = u.iter().cloned().collect();
+            let mut goals: Vec<DefaultGoal> = vec![];
+            for (i, a) in terms.iter().enumerate() {
+                for b in terms[i + 1..].iter() {
+                    let a = a.clone();
+                    let b = b.clone();
+                    goals.push(proto_vulcan!(diseqfd(a, b)));
+                }
+            }
+            goals
+                .into_iter()
+                .fold(Goal::succeed(), |g, h| proto_vulcan!([g, h]))
+        }
+        fn matching(u: LTerm) -> DefaultGoal {
+            proto_vulcan!(distinctfd(u))
+        }
+        let pairwise_failures = sudoku_failures(pairwise);
+        let matching_failures = sudoku_failures(matching);
+        assert!(matching_failures < pairwise_failures);
+    }
+
+    #[test]
+    fn test_distinctfd_21() {
+        // The opaque terms leave one disequality for each variable they are paired with
+        use crate::relation::diseq::DisequalityConstraint;
+        let x = LTerm::var("x");
+        let y = LTerm::var("y");
+        let goal: Goal<DefaultUser, DefaultEngine<DefaultUser>> =
+            proto_vulcan!([infd([x, y], &[1, 2, 3]), distinctfd([x, y, "a", "b"])]);
+        let mut solver = Solver::new((), false);
+        let state = solver
+            .solve_first(&goal, State::new(DefaultUser::new()))
+            .unwrap();
+        let disequalities = state
+            .cstore_ref()
+            .iter()
+            .filter(|c| c.downcast_ref::<DisequalityConstraint<_, _>>().is_some())
+            .count();
+        assert_eq!(disequalities, 4);
+    }
+
+    #[test]
+    fn test_distinctfd_22() {
+        // The last variable displaces each of the others along an augmenting path through
+        // all of the variables.
+        let n = 100_000;
+        let mut domains: Vec<Vec<isize>> = (0..n).map(|k| vec![k, k + 1]).collect();
+        domains.push(vec![0]);
+        let mut expected: Vec<Vec<isize>> = (0..n).map(|k| vec![k + 1]).collect();
+        expected.push(vec![0]);
+        assert_eq!(supported_values(&domains), Some(expected));
+    }
 }
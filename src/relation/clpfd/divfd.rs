@@ -0,0 +1,347 @@
+use crate::engine::Engine;
+/// Constrains u / v = w finite domains
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::{LTerm, LTermInner};
+use crate::lvalue::LValue;
+use crate::solver::{Solve, Solver};
+use crate::state::{Constraint, ConstraintError, FiniteDomain, SResult, State};
+use crate::stream::Stream;
+use crate::user::User;
+use std::rc::Rc;
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct DivFd<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    u: LTerm<U, E>,
+    v: LTerm<U, E>,
+    w: LTerm<U, E>,
+}
+
+impl<U, E> DivFd<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new<G: AnyGoal<U, E>>(
+        u: LTerm<U, E>,
+        v: LTerm<U, E>,
+        w: LTerm<U, E>,
+    ) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(DivFd { u, v, w })))
+    }
+}
+
+impl<U, E> Solve<U, E> for DivFd<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        match state.run_constraint(DivFdConstraint::new(
+            self.u.clone(),
+            self.v.clone(),
+            self.w.clone(),
+        )) {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(error) => solver.fail(error),
+        }
+    }
+}
+
+/// A finite-domain relation where `u / v = w` in Euclidean integer division.
+///
+/// The division agrees with the Euclidean modulo of `modfd`, so that `u = v * w + r`, where
+/// `r` is the non-negative remainder that is less than the absolute value of `v`. For a
+/// positive divisor, the quotient is rounded towards negative infinity.
+/// The divisor `v` is never zero: zero is removed from the domain of `v`, and the relation
+/// fails if `v` is zero. The domain of `w` is constrained by the domains of `u` and `v`, and
+/// the domain of `u` by the domains of `w` and `v`.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::{divfd, infdrange};
+/// fn main() {
+///     let query = proto_vulcan_query!(|u| {
+///         infdrange(u, &(0..=9)),
+///         divfd(u, 4, 2),
+///     });
+///     let us: Vec<isize> = query.run().map(|r| r.u.get_number().unwrap()).collect();
+///     assert_eq!(us, vec![8, 9]);
+/// }
+/// ```
+pub fn divfd<U, E, G>(u: LTerm<U, E>, v: LTerm<U, E>, w: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    DivFd::new(u, v, w)
+}
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct DivFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    u: LTerm<U, E>,
+    v: LTerm<U, E>,
+    w: LTerm<U, E>,
+}
+
+impl<U, E> DivFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new(u: LTerm<U, E>, v: LTerm<U, E>, w: LTerm<U, E>) -> Rc<dyn Constraint<U, E>> {
+        Rc::new(DivFdConstraint { u, v, w })
+    }
+}
+
+/// The smallest and the largest values of the negative and positive parts of a divisor domain.
+/// Integer division is monotonic in the divisor within each part, therefore the bounds of
+/// a quotient are found at these divisors.
+fn extreme_divisors(domain: &FiniteDomain) -> Vec<isize> {
+    let mut divisors = vec![];
+    let negative = domain.intersect(FiniteDomain::from(isize::MIN..=-1));
+    if !negative.is_empty() {
+        divisors.push(negative.min());
+        divisors.push(negative.max());
+    }
+    let positive = domain.intersect(FiniteDomain::from(1..=isize::MAX));
+    if !positive.is_empty() {
+        divisors.push(positive.min());
+        divisors.push(positive.max());
+    }
+    divisors
+}
+
+impl<U, E> Constraint<U, E> for DivFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn run(self: Rc<Self>, state: State<U, E>) -> SResult<U, E> {
+        let smap = state.get_smap();
+        let dstore = state.get_dstore();
+
+        let uwalk = smap.walk(&self.u);
+        let singleton_udomain;
+        let maybe_udomain = match uwalk.as_ref() {
+            LTermInner::Var(_, _) => dstore.get(uwalk),
+            LTermInner::Val(LValue::Number(u)) => {
+                singleton_udomain = Rc::new(FiniteDomain::from(*u));
+                Some(&singleton_udomain)
+            }
+            _ => return Err(ConstraintError::new()), /* Grounded to a term of invalid type */
+        };
+
+        let vwalk = smap.walk(&self.v);
+        let singleton_vdomain;
+        let maybe_vdomain = match vwalk.as_ref() {
+            LTermInner::Var(_, _) => dstore.get(vwalk),
+            LTermInner::Val(LValue::Number(v)) => {
+                singleton_vdomain = Rc::new(FiniteDomain::from(*v));
+                Some(&singleton_vdomain)
+            }
+            _ => return Err(ConstraintError::new()), /* Grounded to a term of invalid type */
+        };
+
+        let wwalk = smap.walk(&self.w);
+        let singleton_wdomain;
+        let maybe_wdomain = match wwalk.as_ref() {
+            LTermInner::Var(_, _) => dstore.get(wwalk),
+            LTermInner::Val(LValue::Number(w)) => {
+                singleton_wdomain = Rc::new(FiniteDomain::from(*w));
+                Some(&singleton_wdomain)
+            }
+            _ => return Err(ConstraintError::new()), /* Grounded to a term of invalid type */
+        };
+
+        // The divisor cannot be zero. If the domain of v is {0}, the constraint is violated.
+        let maybe_vdomain = match maybe_vdomain {
+            Some(vdomain) => {
+                let vdomain = vdomain.diff(FiniteDomain::from(0));
+                if vdomain.is_empty() {
+                    return Err(ConstraintError::new());
+                }
+                Some(vdomain)
+            }
+            None => None,
+        };
+
+        // If the dividend and the divisor are bound to numbers, then the quotient is known
+        // and the constraint can be dropped.
+        if uwalk.is_number() && vwalk.is_number() {
+            let u = uwalk.get_number().unwrap();
+            let v = vwalk.get_number().unwrap();
+            return match u.checked_div_euclid(v) {
+                Some(w) => state.unify(wwalk, &LTerm::from(w)),
+                None => Err(ConstraintError::new()),
+            };
+        }
+
+        match (maybe_udomain, maybe_vdomain, maybe_wdomain) {
+            (Some(udomain), Some(vdomain), Some(wdomain)) => {
+                let umin = udomain.min();
+                let umax = udomain.max();
+                let wmin = wdomain.min();
+                let wmax = wdomain.max();
+                let divisors = extreme_divisors(&vdomain);
+                // The constraint is: u / v = w  <=>  u = w * v + r, where 0 <= r < |v|
+                //
+                // Division is monotonic in the dividend, and in the divisor within the
+                // negative and positive parts of its domain. The bounds of w are therefore
+                // found by dividing the bounds of u with the extreme divisors:
+                //   w = u / v  =>  [min(u / v) .. max(u / v)], u in {umin, umax}
+                //
+                // The bounds of u are found from the products of the bounds of w and the
+                // extreme divisors, widened by the largest possible remainder:
+                //   u = w * v + r  =>  [min(w * v) .. max(w * v) + (|v|max - 1)]
+                //
+                // Zero is removed from the domain of v. The constraint is not dropped until
+                // all variables converge into numbers.
+                let quotients: Vec<isize> = [umin, umax]
+                    .iter()
+                    .flat_map(|u| {
+                        divisors
+                            .iter()
+                            .map(move |v| u.checked_div_euclid(*v).unwrap_or(isize::MAX))
+                    })
+                    .collect();
+                let products: Vec<isize> = [wmin, wmax]
+                    .iter()
+                    .flat_map(|w| divisors.iter().map(move |v| w.saturating_mul(*v)))
+                    .collect();
+                let remainder = divisors
+                    .iter()
+                    .map(|v| v.checked_abs().unwrap_or(isize::MAX) - 1)
+                    .max()
+                    .unwrap();
+                let qmin = *quotients.iter().min().unwrap();
+                let qmax = *quotients.iter().max().unwrap();
+                let pmin = *products.iter().min().unwrap();
+                let pmax = products.iter().max().unwrap().saturating_add(remainder);
+                Ok(state
+                    .process_domain(vwalk, Rc::new(vdomain))?
+                    .process_domain(wwalk, Rc::new(FiniteDomain::from(qmin..=qmax)))?
+                    .process_domain(uwalk, Rc::new(FiniteDomain::from(pmin..=pmax)))?
+                    .with_constraint(self))
+            }
+            // If all operators do not yet have domains, then keep the constraint until it can
+            // be used to constrain some domains.
+            _ => Ok(state.with_constraint(self)),
+        }
+    }
+
+    fn operands(&self) -> Vec<LTerm<U, E>> {
+        vec![self.u.clone(), self.v.clone(), self.w.clone()]
+    }
+}
+
+impl<U, E> std::fmt::Display for DivFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "divfd({}, {}, {})", self.u, self.v, self.w)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{divfd, DivFdConstraint};
+    use crate::prelude::*;
+    use crate::relation::clpfd::infd::infdrange;
+    use crate::relation::modfd;
+
+    #[test]
+    fn test_divfd_1() {
+        // Only the quotient is known
+        let query = proto_vulcan_query!(|q| {
+            |u, v| {
+                infdrange(u, &(0..=9)),
+                infdrange(v, &(1..=3)),
+                divfd(u, v, 3),
+                q == [u, v],
+            }
+        });
+        let mut result: Vec<LTerm> = query.run().map(|r| r.q.clone()).collect();
+        result.sort_by_key(|q| q.head().unwrap().get_number());
+        assert_eq!(
+            result,
+            vec![
+                lterm!([3, 1]),
+                lterm!([6, 2]),
+                lterm!([7, 2]),
+                lterm!([9, 3])
+            ]
+        );
+    }
+
+    #[test]
+    fn test_divfd_2() {
+        // Zero is never a divisor
+        let query = proto_vulcan_query!(|q| { divfd(6, 0, q) });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|q| {
+            |v| {
+                infdrange(v, &(0..=2)),
+                infdrange(q, &(0..=9)),
+                divfd(6, v, q),
+            }
+        });
+        let qs: Vec<isize> = query.run().map(|r| r.q.get_number().unwrap()).collect();
+        assert_eq!(qs, vec![3, 6]);
+    }
+
+    #[test]
+    fn test_divfd_3() {
+        // Division is Euclidean
+        let query = proto_vulcan_query!(|q| { divfd(-7, 2, q) });
+        assert_eq!(query.run().next().unwrap().q, -4);
+
+        let query = proto_vulcan_query!(|u| {
+            infdrange(u, &(-9..=9)),
+            divfd(u, -4, 1),
+        });
+        let us: Vec<isize> = query.run().map(|r| r.u.get_number().unwrap()).collect();
+        assert_eq!(us, vec![-4, -3, -2, -1]);
+    }
+
+    #[test]
+    fn test_divfd_4() {
+        // The quotient and the modulo agree for negative operands
+        for &(u, v) in &[(-7, 2), (7, -2), (-7, -2), (7, 2), (-8, 3)] {
+            let query = proto_vulcan_query!(|w, r| {
+                divfd({ u }, { v }, w),
+                modfd({ u }, { v }, r),
+            });
+            let result = query.run().next().unwrap();
+            let w = result.w.get_number().unwrap();
+            let r = result.r.get_number().unwrap();
+            assert_eq!(u, v * w + r);
+            assert!(0 <= r && r < v.abs());
+        }
+    }
+
+    #[test]
+    fn test_divfd_display() {
+        let c = DivFdConstraint::<DefaultUser, DefaultEngine<DefaultUser>>::new(
+            lterm!(7),
+            lterm!(2),
+            LTerm::var("w"),
+        );
+        assert_eq!(c.to_string(), "divfd(7, 2, w)");
+    }
+}
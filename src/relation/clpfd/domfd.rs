@@ -38,11 +38,11 @@ where
     U: User,
     E: Engine<U>,
 {
-    fn solve(&self, _solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+    fn solve(&self, solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
         let xwalk = state.smap_ref().walk(&self.x).clone();
         match state.process_domain(&xwalk, Rc::clone(&self.domain) as Rc<FiniteDomain>) {
             Ok(state) => Stream::unit(Box::new(state)),
-            Err(_) => Stream::empty(),
+            Err(error) => solver.fail(error),
         }
     }
 }
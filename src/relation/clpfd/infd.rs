@@ -5,7 +5,7 @@ use crate::operator::conj::InferredConj;
 use crate::relation::clpfd::domfd::DomFd;
 use crate::state::FiniteDomain;
 use crate::user::User;
-use std::ops::RangeInclusive;
+use std::ops::{Range, RangeInclusive};
 
 /// Associates the same domain to multiple variables
 pub fn infd<U, E, G>(u: LTerm<U, E>, domain: &[isize]) -> InferredGoal<U, E, G>
@@ -42,10 +42,27 @@ where
     }
 }
 
+/// Associates the domain `start..end`, excluding `end`, to one or more variables.
+///
+/// If the range is empty, the returned goal always fails.
+pub fn infdrange_exclusive<U, E, G>(u: LTerm<U, E>, domain: &Range<isize>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    if domain.is_empty() {
+        InferredGoal::new(G::fail())
+    } else {
+        infdrange(u, &(domain.start..=domain.end - 1))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::infd;
+    use super::{infd, infdrange_exclusive};
     use crate::prelude::*;
+    use crate::stream::Stream;
 
     #[test]
     fn test_infd_1() {
@@ -162,4 +179,68 @@ mod tests {
         let mut iter = query.run();
         assert!(iter.next().is_none());
     }
+
+    #[test]
+    fn test_fresh_in_1() {
+        let query = proto_vulcan_query!(|q| {
+            fresh_in |x, y| in 0..=1 {
+                q == [x, y],
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([0, 0]));
+        assert_eq!(iter.next().unwrap().q, lterm!([1, 0]));
+        assert_eq!(iter.next().unwrap().q, lterm!([0, 1]));
+        assert_eq!(iter.next().unwrap().q, lterm!([1, 1]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_fresh_in_2() {
+        // The domains are already present when the body is solved
+        let query = proto_vulcan_query!(|q| {
+            fresh_in |x| in 3..=5 {
+                q == x,
+                fngoal move |_engine, state| {
+                    let domain = state.dstore_ref().get(&x).unwrap();
+                    assert_eq!(domain.iter().collect::<Vec<isize>>(), vec![3, 4, 5]);
+                    Stream::unit(Box::new(state))
+                },
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, 3);
+    }
+
+    #[test]
+    fn test_fresh_in_3() {
+        let query = proto_vulcan_query!(|q| {
+            fresh_in |x| in 1..=3 {
+                x == 5,
+                q == x,
+            }
+        });
+        let mut iter = query.run();
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_infdrange_exclusive_1() {
+        let query = proto_vulcan_query!(|q| { infdrange_exclusive(q, &(1..4)) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, 1);
+        assert_eq!(iter.next().unwrap().q, 2);
+        assert_eq!(iter.next().unwrap().q, 3);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_infdrange_exclusive_2() {
+        let query = proto_vulcan_query!(|x, y| { infdrange_exclusive([x, y], &(0..2)) });
+        assert_eq!(query.run().count(), 4);
+
+        // Empty range fails
+        let query = proto_vulcan_query!(|q| { infdrange_exclusive(q, &(3..3)) });
+        assert!(query.run().next().is_none());
+    }
 }
@@ -3,7 +3,7 @@ use crate::engine::Engine;
 use crate::goal::{AnyGoal, InferredGoal};
 use crate::lterm::LTerm;
 use crate::solver::{Solve, Solver};
-use crate::state::{Constraint, SResult, State};
+use crate::state::{Constraint, ConstraintError, SResult, State};
 use crate::stream::Stream;
 use crate::user::User;
 use std::rc::Rc;
@@ -34,10 +34,13 @@ where
     U: User,
     E: Engine<U>,
 {
-    fn solve(&self, _solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
-        match LessThanOrEqualFdConstraint::new(self.u.clone(), self.v.clone()).run(state) {
+    fn solve(&self, solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        match state.run_constraint(LessThanOrEqualFdConstraint::new(
+            self.u.clone(),
+            self.v.clone(),
+        )) {
             Ok(state) => Stream::unit(Box::new(state)),
-            Err(_) => Stream::empty(),
+            Err(error) => solver.fail(error),
         }
     }
 }
@@ -69,8 +72,6 @@ where
     E: Engine<U>,
 {
     pub fn new(u: LTerm<U, E>, v: LTerm<U, E>) -> Rc<dyn Constraint<U, E>> {
-        assert!(u.is_var() || u.is_number());
-        assert!(v.is_var() || v.is_number());
         Rc::new(LessThanOrEqualFdConstraint { u, v })
     }
 }
@@ -90,6 +91,11 @@ where
         let vwalk = smap.walk(&self.v);
         let maybe_vdomain = dstore.get(vwalk);
 
+        if !(uwalk.is_var() || uwalk.is_number()) || !(vwalk.is_var() || vwalk.is_number()) {
+            // Some operands grounded to terms of invalid type.
+            return Err(ConstraintError::new());
+        }
+
         match (maybe_udomain, maybe_vdomain) {
             (Some(udomain), Some(vdomain)) => {
                 // Both variables of the constraints have assigned domains, we can evaluate
@@ -97,14 +103,8 @@ where
                 let vmax = vdomain.max();
                 let umin = udomain.min();
                 Ok(state
-                    .process_domain(
-                        &uwalk,
-                        Rc::new(udomain.copy_before(|u| vmax < *u).ok_or(())?),
-                    )?
-                    .process_domain(
-                        &vwalk,
-                        Rc::new(vdomain.drop_before(|v| umin <= *v).ok_or(())?),
-                    )?
+                    .process_domain(&uwalk, Rc::new(udomain.copy_before(|u| vmax < *u)))?
+                    .process_domain(&vwalk, Rc::new(vdomain.drop_before(|v| umin <= *v)))?
                     .with_constraint(self))
             }
             (Some(udomain), None) if vwalk.is_number() => {
@@ -112,16 +112,14 @@ where
                 // to a number. After the number constraint has been applied to the domain,
                 // the constraint is dropped.
                 let v = vwalk.get_number().unwrap();
-                Ok(state
-                    .process_domain(&uwalk, Rc::new(udomain.copy_before(|u| v < *u).ok_or(())?))?)
+                state.process_domain(&uwalk, Rc::new(udomain.copy_before(|u| v < *u)))
             }
             (None, Some(vdomain)) if uwalk.is_number() => {
                 // The variable `v` has an assigned domain, and variable `u` has been bound
                 // to a number. After the number constraint has been applied to the domain,
                 // the constraint is dropped.
                 let u = uwalk.get_number().unwrap();
-                Ok(state
-                    .process_domain(&vwalk, Rc::new(vdomain.drop_before(|v| u <= *v).ok_or(())?))?)
+                state.process_domain(&vwalk, Rc::new(vdomain.drop_before(|v| u <= *v)))
             }
             (None, None) if uwalk.is_number() && vwalk.is_number() => {
                 // Both variables are bound to numbers. Constraint is no longer needed if it
@@ -133,7 +131,7 @@ where
                     Ok(state)
                 } else {
                     // Constraint failed
-                    Err(())
+                    Err(ConstraintError::new())
                 }
             }
             _ => {
@@ -248,4 +246,15 @@ mod tests {
         });
         assert_eq!(expected.len(), 0);
     }
+
+    #[test]
+    fn test_ltefd_5() {
+        // A string where a number is expected fails instead of panicking
+        let query = proto_vulcan_query!(|q| {
+            infd(q, &[1, 2]),
+            ltefd(q, "a"),
+        });
+        let mut iter = query.run();
+        assert!(iter.next().is_none());
+    }
 }
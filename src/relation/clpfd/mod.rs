@@ -1,16 +1,51 @@
 //! # CLP(FD)
 //! Proto-vulcan implements finite-domain constraints. For disequality, a `diseqfd(x, y)`-relation
-//! must be used instead of `x != y`. Other supported CLP(FD) constraints are: `distinctfd`, `ltefd`
-//! `ltfd`, `plusfd`, `minusfd` and `timesfd`. Domains are assigned to variables with `infd` or
-//! `infdrange`. See `n-queens`-example for code using finite-domain constraints.
+//! must be used instead of `x != y`. Other supported CLP(FD) constraints are: `distinctfd`,
+//! `disjointo`, `ltefd`, `ltfd`, `plusfd`, `minusfd`, `timesfd`, `divfd`, `modfd`, `absfd`,
+//! `scaleo`, `sumfd` and `reifyeq`. Domains are assigned to variables with `infd`, `infdrange` or
+//! `infdrange_exclusive`. See `n-queens`-example for code using finite-domain constraints.
+//!
+//! Finite-domain variables are enumerated in the order in which they were given domains, unless
+//! they are labeled explicitly. The `label_ff` operator labels a list of variables first-fail,
+//! always choosing the unbound variable with the smallest domain next. With constraints such as
+//! `distinctfd`, where assigning one variable narrows the domains of the others, this typically
+//! explores fewer failing branches.
+//! All of the finite-domain relations can be imported at once with
+//! `use proto_vulcan::clpfd_prelude::*`.
+//!
+//! Fresh variables can be declared with a domain using the built-in syntax
+//! `fresh_in |x, y| in 0..=9 { <body> }`, which posts `infdrange` to each variable before the body.
+//! ```rust
+//! extern crate proto_vulcan;
+//! use proto_vulcan::prelude::*;
+//! use proto_vulcan::relation::ltfd;
+//! fn main() {
+//!     let query = proto_vulcan_query!(|q| {
+//!         fresh_in |x, y| in 1..=2 {
+//!             ltfd(x, y),
+//!             q == [x, y],
+//!         }
+//!     });
+//!     let mut iter = query.run();
+//!     assert_eq!(iter.next().unwrap().q, lterm!([1, 2]));
+//!     assert!(iter.next().is_none());
+//! }
+//! ```
 //!
 
+pub mod absfd;
 pub mod diseqfd;
+pub mod disjointo;
 pub mod distinctfd;
+pub mod divfd;
 pub mod domfd;
 pub mod infd;
 pub mod ltefd;
 pub mod ltfd;
 pub mod minusfd;
+pub mod modfd;
 pub mod plusfd;
+pub mod reifyeq;
+pub mod scaleo;
+pub mod sumfd;
 pub mod timesfd;
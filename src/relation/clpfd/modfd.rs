@@ -0,0 +1,294 @@
+use crate::engine::Engine;
+/// Constrains u mod v = w finite domains
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::{LTerm, LTermInner};
+use crate::lvalue::LValue;
+use crate::solver::{Solve, Solver};
+use crate::state::{Constraint, ConstraintError, FiniteDomain, SResult, State};
+use crate::stream::Stream;
+use crate::user::User;
+use std::rc::Rc;
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct ModFd<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    u: LTerm<U, E>,
+    v: LTerm<U, E>,
+    w: LTerm<U, E>,
+}
+
+impl<U, E> ModFd<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new<G: AnyGoal<U, E>>(
+        u: LTerm<U, E>,
+        v: LTerm<U, E>,
+        w: LTerm<U, E>,
+    ) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(ModFd { u, v, w })))
+    }
+}
+
+impl<U, E> Solve<U, E> for ModFd<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        match state.run_constraint(ModFdConstraint::new(
+            self.u.clone(),
+            self.v.clone(),
+            self.w.clone(),
+        )) {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(error) => solver.fail(error),
+        }
+    }
+}
+
+/// A finite-domain relation where `u mod v = w`.
+///
+/// The modulo is Euclidean: `w` is non-negative and less than the absolute value of `v`,
+/// regardless of the signs of `u` and `v`. The divisor `v` is never zero: zero is removed from
+/// the domain of `v`, and the relation fails if `v` is zero.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::{infdrange, modfd};
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| {
+///         infdrange(q, &(0..=9)),
+///         modfd(q, 3, 1),
+///     });
+///     let qs: Vec<isize> = query.run().map(|r| r.q.get_number().unwrap()).collect();
+///     assert_eq!(qs, vec![1, 4, 7]);
+/// }
+/// ```
+pub fn modfd<U, E, G>(u: LTerm<U, E>, v: LTerm<U, E>, w: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    ModFd::new(u, v, w)
+}
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct ModFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    u: LTerm<U, E>,
+    v: LTerm<U, E>,
+    w: LTerm<U, E>,
+}
+
+impl<U, E> ModFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new(u: LTerm<U, E>, v: LTerm<U, E>, w: LTerm<U, E>) -> Rc<dyn Constraint<U, E>> {
+        Rc::new(ModFdConstraint { u, v, w })
+    }
+}
+
+impl<U, E> Constraint<U, E> for ModFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn run(self: Rc<Self>, state: State<U, E>) -> SResult<U, E> {
+        let smap = state.get_smap();
+        let dstore = state.get_dstore();
+
+        let uwalk = smap.walk(&self.u);
+        let singleton_udomain;
+        let maybe_udomain = match uwalk.as_ref() {
+            LTermInner::Var(_, _) => dstore.get(uwalk),
+            LTermInner::Val(LValue::Number(u)) => {
+                singleton_udomain = Rc::new(FiniteDomain::from(*u));
+                Some(&singleton_udomain)
+            }
+            _ => return Err(ConstraintError::new()), /* Grounded to a term of invalid type */
+        };
+
+        let vwalk = smap.walk(&self.v);
+        let singleton_vdomain;
+        let maybe_vdomain = match vwalk.as_ref() {
+            LTermInner::Var(_, _) => dstore.get(vwalk),
+            LTermInner::Val(LValue::Number(v)) => {
+                singleton_vdomain = Rc::new(FiniteDomain::from(*v));
+                Some(&singleton_vdomain)
+            }
+            _ => return Err(ConstraintError::new()), /* Grounded to a term of invalid type */
+        };
+
+        let wwalk = smap.walk(&self.w);
+        match wwalk.as_ref() {
+            LTermInner::Var(_, _) | LTermInner::Val(LValue::Number(_)) => (),
+            _ => return Err(ConstraintError::new()), /* Grounded to a term of invalid type */
+        }
+
+        // If the dividend and the divisor are bound to numbers, then the modulo is known and
+        // the constraint can be dropped.
+        if uwalk.is_number() && vwalk.is_number() {
+            let u = uwalk.get_number().unwrap();
+            let v = vwalk.get_number().unwrap();
+            return match u.checked_rem_euclid(v) {
+                Some(w) => state.unify(wwalk, &LTerm::from(w)),
+                None => Err(ConstraintError::new()),
+            };
+        }
+
+        match maybe_vdomain {
+            Some(vdomain) => {
+                // The divisor cannot be zero. If the domain of v is {0}, the constraint is
+                // violated.
+                let vdomain = vdomain.diff(FiniteDomain::from(0));
+                if vdomain.is_empty() {
+                    return Err(ConstraintError::new());
+                }
+
+                // The modulo is in range [0 .. |v|max - 1].
+                let vabsmax = std::cmp::max(
+                    vdomain.min().checked_abs().unwrap_or(isize::MAX),
+                    vdomain.max().checked_abs().unwrap_or(isize::MAX),
+                );
+                let mut state = state
+                    .process_domain(vwalk, Rc::new(vdomain))?
+                    .process_domain(wwalk, Rc::new(FiniteDomain::from(0..=vabsmax - 1)))?;
+
+                // If the divisor is known, the dividend is constrained to the values that
+                // have a modulo in the domain of w.
+                if let (Some(v), Some(udomain)) = (vwalk.get_number(), maybe_udomain) {
+                    let wwalk = state.smap_ref().walk(wwalk).clone();
+                    let maybe_wdomain = match wwalk.get_number() {
+                        Some(w) => Some(Rc::new(FiniteDomain::from(w))),
+                        None => state.dstore_ref().get(&wwalk).cloned(),
+                    };
+                    if let Some(wdomain) = maybe_wdomain {
+                        let values: Vec<isize> = udomain
+                            .iter()
+                            .filter(|u| match u.checked_rem_euclid(v) {
+                                Some(w) => wdomain.contains(w),
+                                None => false,
+                            })
+                            .collect();
+                        state = state.process_domain(uwalk, Rc::new(FiniteDomain::from(values)))?;
+                    }
+                }
+
+                // The constraint is not dropped until all variables converge into numbers.
+                Ok(state.with_constraint(self))
+            }
+            // If the divisor does not yet have a domain, then keep the constraint until it
+            // can be used to constrain some domains.
+            None => Ok(state.with_constraint(self)),
+        }
+    }
+
+    fn operands(&self) -> Vec<LTerm<U, E>> {
+        vec![self.u.clone(), self.v.clone(), self.w.clone()]
+    }
+}
+
+impl<U, E> std::fmt::Display for ModFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "modfd({}, {}, {})", self.u, self.v, self.w)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{modfd, ModFdConstraint};
+    use crate::prelude::*;
+    use crate::relation::clpfd::infd::{infd, infdrange};
+
+    #[test]
+    fn test_modfd_1() {
+        let query = proto_vulcan_query!(|q| {
+            infdrange(q, &(0..=9)),
+            modfd(q, 3, 1),
+        });
+        let qs: Vec<isize> = query.run().map(|r| r.q.get_number().unwrap()).collect();
+        assert_eq!(qs, vec![1, 4, 7]);
+    }
+
+    #[test]
+    fn test_modfd_2() {
+        // The modulo is constrained by the domain of the divisor
+        let query = proto_vulcan_query!(|v, w| {
+            infdrange(v, &(2..=3)),
+            infdrange(w, &(0..=9)),
+            modfd(7, v, w),
+        });
+        let mut result: Vec<(isize, isize)> = query
+            .run()
+            .map(|r| (r.v.get_number().unwrap(), r.w.get_number().unwrap()))
+            .collect();
+        result.sort_unstable();
+        assert_eq!(result, vec![(2, 1), (3, 1)]);
+
+        let query = proto_vulcan_query!(|w| { modfd(-7, 3, w) });
+        assert_eq!(query.run().next().unwrap().w, 2);
+
+        let query = proto_vulcan_query!(|w| { modfd(7, 3, 2) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_modfd_3() {
+        // Zero is never a divisor
+        let query = proto_vulcan_query!(|w| { modfd(7, 0, w) });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|w| {
+            |v| {
+                infdrange(v, &(0..=0)),
+                infdrange(w, &(0..=9)),
+                modfd(7, v, w),
+            }
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_modfd_4() {
+        // The modulo of isize::MIN by -1 overflows, and is not in the domain of the dividend
+        let query = proto_vulcan_query!(|u, w| {
+            infd(u, &[isize::MIN, 3]),
+            infdrange(w, &(0..=1)),
+            modfd(u, -1, w),
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.u, 3);
+        assert_eq!(result.w, 0);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_modfd_display() {
+        let c = ModFdConstraint::<DefaultUser, DefaultEngine<DefaultUser>>::new(
+            lterm!(7),
+            lterm!(2),
+            LTerm::var("w"),
+        );
+        assert_eq!(c.to_string(), "modfd(7, 2, w)");
+    }
+}
@@ -4,7 +4,7 @@ use crate::goal::{AnyGoal, InferredGoal};
 use crate::lterm::{LTerm, LTermInner};
 use crate::lvalue::LValue;
 use crate::solver::{Solve, Solver};
-use crate::state::{Constraint, FiniteDomain, SResult, State};
+use crate::state::{Constraint, ConstraintError, FiniteDomain, SResult, State};
 use crate::stream::Stream;
 use crate::user::User;
 use std::rc::Rc;
@@ -40,10 +40,14 @@ where
     U: User,
     E: Engine<U>,
 {
-    fn solve(&self, _solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
-        match PlusFdConstraint::new(self.u.clone(), self.v.clone(), self.w.clone()).run(state) {
+    fn solve(&self, solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        match state.run_constraint(PlusFdConstraint::new(
+            self.u.clone(),
+            self.v.clone(),
+            self.w.clone(),
+        )) {
             Ok(state) => Stream::unit(Box::new(state)),
-            Err(_) => Stream::empty(),
+            Err(error) => solver.fail(error),
         }
     }
 }
@@ -75,9 +79,6 @@ where
     E: Engine<U>,
 {
     pub fn new(u: LTerm<U, E>, v: LTerm<U, E>, w: LTerm<U, E>) -> Rc<dyn Constraint<U, E>> {
-        assert!(u.is_var() || u.is_number());
-        assert!(v.is_var() || v.is_number());
-        assert!(w.is_var() || w.is_number());
         Rc::new(PlusFdConstraint { u, v, w })
     }
 }
@@ -99,7 +100,7 @@ where
                 singleton_udomain = Rc::new(FiniteDomain::from(*u));
                 Some(&singleton_udomain)
             }
-            _ => None,
+            _ => return Err(ConstraintError::new()), /* Grounded to a term of invalid type */
         };
 
         let vwalk = smap.walk(&self.v);
@@ -110,7 +111,7 @@ where
                 singleton_vdomain = Rc::new(FiniteDomain::from(*v));
                 Some(&singleton_vdomain)
             }
-            _ => None,
+            _ => return Err(ConstraintError::new()), /* Grounded to a term of invalid type */
         };
 
         let wwalk = smap.walk(&self.w);
@@ -121,19 +122,30 @@ where
                 singleton_wdomain = Rc::new(FiniteDomain::from(*w));
                 Some(&singleton_wdomain)
             }
-            _ => None,
+            _ => return Err(ConstraintError::new()), /* Grounded to a term of invalid type */
         };
 
-        // If all operators are bound to numbers, then we can drop the constraint or fail if
-        // constraint is not fulfilled.
-        if uwalk.is_number() && vwalk.is_number() && wwalk.is_number() {
-            if uwalk.get_number().unwrap() + vwalk.get_number().unwrap()
-                == wwalk.get_number().unwrap()
-            {
-                return Ok(state);
-            } else {
-                return Err(());
+        // If two of the operands are bound to numbers, then the third one can be computed
+        // directly, and the constraint is dropped. If all operands are bound to numbers, then
+        // we can drop the constraint or fail if constraint is not fulfilled.
+        match (uwalk.get_number(), vwalk.get_number(), wwalk.get_number()) {
+            (Some(u), Some(v), Some(w)) => {
+                return if u.checked_add(v) == Some(w) {
+                    Ok(state)
+                } else {
+                    Err(ConstraintError::new())
+                };
             }
+            (Some(u), Some(v), None) => {
+                return state.unify(wwalk, &LTerm::from(u.checked_add(v).ok_or(())?));
+            }
+            (Some(u), None, Some(w)) => {
+                return state.unify(vwalk, &LTerm::from(w.checked_sub(u).ok_or(())?));
+            }
+            (None, Some(v), Some(w)) => {
+                return state.unify(uwalk, &LTerm::from(w.checked_sub(v).ok_or(())?));
+            }
+            _ => (),
         }
 
         match (maybe_udomain, maybe_vdomain, maybe_wdomain) {
@@ -197,10 +209,11 @@ where
 
 #[cfg(test)]
 mod tests {
-    use super::plusfd;
+    use super::{plusfd, PlusFdConstraint};
     use crate::prelude::*;
     use crate::relation::clpfd::diseqfd::diseqfd;
     use crate::relation::clpfd::infd::infdrange;
+    use crate::state::State;
 
     #[test]
     fn test_plusfd_1() {
@@ -249,4 +262,44 @@ mod tests {
         });
         assert_eq!(expected.len(), 0);
     }
+
+    #[test]
+    fn test_plusfd_3() {
+        // A list where a number is expected fails instead of panicking
+        let query = proto_vulcan_query!(|q| {
+            |x| {
+                infdrange(q, &(0..=5)),
+                x == [1, 2],
+                plusfd(x, 1, q),
+            }
+        });
+        let mut iter = query.run();
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_plusfd_4() {
+        // With two numeric operands the third one is computed and the constraint is dropped
+        let x = LTerm::var("x");
+        let state: State<DefaultUser, DefaultEngine<DefaultUser>> = State::new(DefaultUser::new());
+        let state = PlusFdConstraint::new(lterm!(2), x.clone(), lterm!(5))
+            .run(state)
+            .unwrap();
+        assert!(state.cstore_ref().is_empty());
+        assert!(state.dstore_ref().is_empty());
+        assert_eq!(state.smap_ref().walk(&x), &lterm!(3));
+    }
+
+    #[test]
+    fn test_plusfd_5() {
+        let query = proto_vulcan_query!(|q| {
+            |x| {
+                infdrange(q, &(0..=5)),
+                x == 4,
+                plusfd(x, 3, q),
+            }
+        });
+        let mut iter = query.run();
+        assert!(iter.next().is_none());
+    }
 }
@@ -0,0 +1,287 @@
+use crate::engine::Engine;
+/// Reifies finite-domain equality into a 0/1 variable
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::{LTerm, LTermInner};
+use crate::lvalue::LValue;
+use crate::relation::clpfd::diseqfd::DiseqFdConstraint;
+use crate::solver::{Solve, Solver};
+use crate::state::{Constraint, ConstraintError, FiniteDomain, SResult, State};
+use crate::stream::Stream;
+use crate::user::User;
+use std::rc::Rc;
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct ReifyEq<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    b: LTerm<U, E>,
+    x: LTerm<U, E>,
+    y: LTerm<U, E>,
+}
+
+impl<U, E> ReifyEq<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new<G: AnyGoal<U, E>>(
+        b: LTerm<U, E>,
+        x: LTerm<U, E>,
+        y: LTerm<U, E>,
+    ) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(ReifyEq { b, x, y })))
+    }
+}
+
+impl<U, E> Solve<U, E> for ReifyEq<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        let c = ReifyEqConstraint::new(self.b.clone(), self.x.clone(), self.y.clone());
+        match state.run_constraint(c) {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(error) => solver.fail(error),
+        }
+    }
+}
+
+/// A finite-domain relation where `b` is `1` if `x == y`, and `0` otherwise.
+///
+/// The variable `b` is given the domain `0..=1`. When the domains of `x` and `y` become
+/// disjoint, `b` is set to `0`, and when both are bound to the same number, `b` is set to `1`.
+/// In the other direction, setting `b` to `1` unifies `x` and `y`, and setting it to `0` posts
+/// `diseqfd(x, y)`.
+///
+/// Reified equality is the building block of cardinality constraints: the number of terms of
+/// a list that are equal to some value is the sum of the reified equalities, for example
+/// with `sumfd`.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::{infdrange, reifyeq, sumfd};
+/// fn main() {
+///     // Number of fives in [x, y, 5]
+///     let query = proto_vulcan_query!(|x, y, n| {
+///         |bx, by, b5| {
+///             infdrange([x, y], &(4..=5)),
+///             reifyeq(bx, x, 5),
+///             reifyeq(by, y, 5),
+///             reifyeq(b5, 5, 5),
+///             sumfd([bx, by, b5], n),
+///         }
+///     });
+///     let mut count = 0;
+///     for result in query.run() {
+///         let fives = [&result.x, &result.y]
+///             .iter()
+///             .filter(|t| t.get_number() == Some(5))
+///             .count() as isize;
+///         assert_eq!(result.n, fives + 1);
+///         count += 1;
+///     }
+///     assert_eq!(count, 4);
+/// }
+/// ```
+pub fn reifyeq<U, E, G>(b: LTerm<U, E>, x: LTerm<U, E>, y: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    ReifyEq::new(b, x, y)
+}
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct ReifyEqConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    b: LTerm<U, E>,
+    x: LTerm<U, E>,
+    y: LTerm<U, E>,
+}
+
+impl<U, E> ReifyEqConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new(b: LTerm<U, E>, x: LTerm<U, E>, y: LTerm<U, E>) -> Rc<dyn Constraint<U, E>> {
+        Rc::new(ReifyEqConstraint { b, x, y })
+    }
+}
+
+/// The domain of a walked term; a number has a singleton domain.
+fn domain_of<U, E>(
+    state: &State<U, E>,
+    u: &LTerm<U, E>,
+) -> Result<Option<Rc<FiniteDomain>>, ConstraintError<U, E>>
+where
+    U: User,
+    E: Engine<U>,
+{
+    match u.as_ref() {
+        LTermInner::Var(_, _) => Ok(state.dstore_ref().get(u).cloned()),
+        LTermInner::Val(LValue::Number(n)) => Ok(Some(Rc::new(FiniteDomain::from(*n)))),
+        _ => Err(ConstraintError::new()), /* Grounded to a term of invalid type */
+    }
+}
+
+impl<U, E> Constraint<U, E> for ReifyEqConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn run(self: Rc<Self>, state: State<U, E>) -> SResult<U, E> {
+        let bwalk = state.smap_ref().walk(&self.b).clone();
+        let state = state.process_domain(&bwalk, Rc::new(FiniteDomain::from(0..=1)))?;
+
+        let bwalk = state.smap_ref().walk(&bwalk).clone();
+        let xwalk = state.smap_ref().walk(&self.x).clone();
+        let ywalk = state.smap_ref().walk(&self.y).clone();
+        match bwalk.get_number() {
+            Some(1) => {
+                // The terms are equal; the constraint is replaced by the unification.
+                state.unify(&xwalk, &ywalk)
+            }
+            Some(_) => {
+                // The terms are distinct; the constraint is replaced by finite-domain
+                // disequality.
+                state.run_constraint(DiseqFdConstraint::new(xwalk, ywalk))
+            }
+            None => {
+                if xwalk == ywalk {
+                    // The terms are the same variable or the same number
+                    return state.unify(&bwalk, &LTerm::from(1));
+                }
+                let maybe_xdomain = domain_of(&state, &xwalk)?;
+                let maybe_ydomain = domain_of(&state, &ywalk)?;
+                match (maybe_xdomain, maybe_ydomain) {
+                    (Some(xdomain), Some(ydomain)) if xdomain.is_disjoint(ydomain.as_ref()) => {
+                        // The terms can never be equal
+                        state.unify(&bwalk, &LTerm::from(0))
+                    }
+                    // The constraint is kept until either `b` or the terms are resolved.
+                    _ => Ok(state.with_constraint(self)),
+                }
+            }
+        }
+    }
+
+    fn operands(&self) -> Vec<LTerm<U, E>> {
+        vec![self.b.clone(), self.x.clone(), self.y.clone()]
+    }
+}
+
+impl<U, E> std::fmt::Display for ReifyEqConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "reifyeq({}, {}, {})", self.b, self.x, self.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::reifyeq;
+    use crate::prelude::*;
+    use crate::relation::clpfd::infd::{infd, infdrange};
+
+    #[test]
+    fn test_reifyeq_1() {
+        // Disjoint domains force b = 0
+        let query = proto_vulcan_query!(|b| {
+            |x, y| {
+                infd(x, &[1, 2]),
+                infd(y, &[3, 4]),
+                reifyeq(b, x, y),
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().b, 0);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_reifyeq_2() {
+        // Equal numbers force b = 1, and different numbers b = 0
+        let query = proto_vulcan_query!(|b| {
+            |x, y| {
+                infdrange([x, y], &(1..=3)),
+                reifyeq(b, x, y),
+                x == 2,
+                y == 2,
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().b, 1);
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|b| { reifyeq(b, 2, 3) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().b, 0);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_reifyeq_3() {
+        // b = 1 unifies the terms
+        let query = proto_vulcan_query!(|x, y| {
+            infd(x, &[1, 2]),
+            infd(y, &[2, 3]),
+            reifyeq(1, x, y),
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.x, 2);
+        assert_eq!(result.y, 2);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_reifyeq_4() {
+        // b = 0 posts a disequality
+        let query = proto_vulcan_query!(|x, y| {
+            |b| {
+                infdrange([x, y], &(1..=2)),
+                reifyeq(b, x, y),
+                b == 0,
+                x == 1,
+            }
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.x, 1);
+        assert_eq!(result.y, 2);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_reifyeq_5() {
+        // b is enumerated along with the terms, and other values of b fail
+        let query = proto_vulcan_query!(|b, x| {
+            infdrange(x, &(1..=3)),
+            reifyeq(b, x, 2),
+        });
+        let mut result: Vec<(isize, isize)> = query
+            .run()
+            .map(|r| (r.b.get_number().unwrap(), r.x.get_number().unwrap()))
+            .collect();
+        result.sort_unstable();
+        assert_eq!(result, vec![(0, 1), (0, 3), (1, 2)]);
+
+        let query = proto_vulcan_query!(|q| { reifyeq(2, q, q) });
+        assert!(query.run().next().is_none());
+    }
+}
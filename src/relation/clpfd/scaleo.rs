@@ -0,0 +1,362 @@
+use crate::engine::Engine;
+/// Constrains y = k * x finite domains, where k is a constant
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::{LTerm, LTermInner};
+use crate::lvalue::LValue;
+use crate::solver::{Solve, Solver};
+use crate::state::{Constraint, ConstraintError, FiniteDomain, SResult, State};
+use crate::stream::Stream;
+use crate::user::User;
+use std::rc::Rc;
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct ScaleFd<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    x: LTerm<U, E>,
+    k: LTerm<U, E>,
+    y: LTerm<U, E>,
+}
+
+impl<U, E> ScaleFd<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new<G: AnyGoal<U, E>>(
+        x: LTerm<U, E>,
+        k: LTerm<U, E>,
+        y: LTerm<U, E>,
+    ) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(ScaleFd { x, k, y })))
+    }
+}
+
+impl<U, E> Solve<U, E> for ScaleFd<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        let k = match state.smap_ref().walk(&self.k).get_number() {
+            Some(k) => k,
+            None => return solver.fail(ConstraintError::new()),
+        };
+        match state.run_constraint(ScaleFdConstraint::new(self.x.clone(), k, self.y.clone())) {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(error) => solver.fail(error),
+        }
+    }
+}
+
+/// A finite-domain relation where `y = k * x` for a constant factor `k`.
+///
+/// Unlike `timesfd(k, x, y)`, the domain of `x` is mapped directly through the multiplication,
+/// and the domain of `y` back through the division, therefore the domains are exact also for
+/// negative factors. Interval domains are mapped through their bounds, without expanding
+/// them; when `|k| > 1`, the interval of `y` also contains the values that are not multiples
+/// of `k`, which are excluded only when `y` is bound. The factor `k` must be a number when the
+/// relation is evaluated, otherwise the relation fails.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::{infdrange, scaleo};
+/// fn main() {
+///     let query = proto_vulcan_query!(|y| {
+///         |x| {
+///             infdrange(x, &(1..=3)),
+///             scaleo(x, -2, y),
+///         }
+///     });
+///     let ys: Vec<isize> = query.run().map(|r| r.y.get_number().unwrap()).collect();
+///     assert_eq!(ys, vec![-6, -4, -2]);
+/// }
+/// ```
+pub fn scaleo<U, E, G>(x: LTerm<U, E>, k: LTerm<U, E>, y: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    ScaleFd::new(x, k, y)
+}
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct ScaleFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    x: LTerm<U, E>,
+    k: isize,
+    y: LTerm<U, E>,
+}
+
+impl<U, E> ScaleFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new(x: LTerm<U, E>, k: isize, y: LTerm<U, E>) -> Rc<dyn Constraint<U, E>> {
+        Rc::new(ScaleFdConstraint { x, k, y })
+    }
+
+    /// Interval of the `x` for which `k * x` is in `lo..=hi`, for `|k| > 1`.
+    fn unscale_bounds(&self, lo: isize, hi: isize) -> FiniteDomain {
+        if self.k > 0 {
+            FiniteDomain::from(div_ceil(lo, self.k)..=div_floor(hi, self.k))
+        } else {
+            FiniteDomain::from(div_ceil(hi, self.k)..=div_floor(lo, self.k))
+        }
+    }
+
+    /// Domain of `k * x` for `x` in `domain`. The domain is empty if no product fits in
+    /// `isize`.
+    ///
+    /// For `|k| > 1`, an interval is mapped through its bounds, and the resulting interval
+    /// also contains the values between the multiples of `k`.
+    fn scale(&self, domain: &FiniteDomain) -> FiniteDomain {
+        match (domain, self.k) {
+            (FiniteDomain::Interval(r), 1) => FiniteDomain::from(r.clone()),
+            (FiniteDomain::Interval(r), -1) => match r.end().checked_neg() {
+                // The interval is reversed
+                Some(start) => {
+                    let end = r.start().checked_neg().unwrap_or(isize::MAX);
+                    FiniteDomain::from(start..=end)
+                }
+                None => FiniteDomain::Empty,
+            },
+            (FiniteDomain::Interval(_), _) => {
+                // Only the values whose products fit in `isize` are mapped.
+                match domain.intersect(self.unscale_bounds(isize::MIN, isize::MAX)) {
+                    FiniteDomain::Interval(r) => {
+                        let start = r.start() * self.k;
+                        let end = r.end() * self.k;
+                        FiniteDomain::from(start.min(end)..=start.max(end))
+                    }
+                    _ => FiniteDomain::Empty,
+                }
+            }
+            _ => {
+                let values: Vec<isize> = domain
+                    .iter()
+                    .filter_map(|x| x.checked_mul(self.k))
+                    .collect();
+                FiniteDomain::from(values)
+            }
+        }
+    }
+
+    /// Domain of `y / k` for the `y` in `domain` that are divisible by `k`. The domain is
+    /// empty if there are no such values.
+    fn unscale(&self, domain: &FiniteDomain) -> FiniteDomain {
+        match (domain, self.k) {
+            (FiniteDomain::Interval(r), 1) => FiniteDomain::from(r.clone()),
+            // Negation is its own inverse
+            (FiniteDomain::Interval(_), -1) => self.scale(domain),
+            (FiniteDomain::Interval(r), _) => self.unscale_bounds(*r.start(), *r.end()),
+            _ => {
+                let values: Vec<isize> = domain
+                    .iter()
+                    .filter(|y| y.checked_rem(self.k) == Some(0))
+                    .map(|y| y / self.k)
+                    .collect();
+                FiniteDomain::from(values)
+            }
+        }
+    }
+}
+
+/// Quotient `a / b` rounded towards negative infinity, for `|b| > 1`.
+fn div_floor(a: isize, b: isize) -> isize {
+    let q = a / b;
+    if a % b != 0 && (a < 0) != (b < 0) {
+        q - 1
+    } else {
+        q
+    }
+}
+
+/// Quotient `a / b` rounded towards positive infinity, for `|b| > 1`.
+fn div_ceil(a: isize, b: isize) -> isize {
+    let q = a / b;
+    if a % b != 0 && (a < 0) == (b < 0) {
+        q + 1
+    } else {
+        q
+    }
+}
+
+impl<U, E> Constraint<U, E> for ScaleFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn run(self: Rc<Self>, state: State<U, E>) -> SResult<U, E> {
+        let smap = state.get_smap();
+        let xwalk = smap.walk(&self.x).clone();
+        let ywalk = smap.walk(&self.y).clone();
+
+        if self.k == 0 {
+            return state.unify(&ywalk, &LTerm::from(0));
+        }
+
+        match (xwalk.as_ref(), ywalk.as_ref()) {
+            (LTermInner::Val(LValue::Number(x)), _) => match x.checked_mul(self.k) {
+                Some(y) => state.unify(&ywalk, &LTerm::from(y)),
+                None => Err(ConstraintError::new()),
+            },
+            (_, LTermInner::Val(LValue::Number(y))) => {
+                // The remainder of `isize::MIN / -1` overflows, as would the quotient.
+                if y.checked_rem(self.k) == Some(0) {
+                    state.unify(&xwalk, &LTerm::from(y / self.k))
+                } else {
+                    Err(ConstraintError::new())
+                }
+            }
+            (LTermInner::Var(_, _), LTermInner::Var(_, _)) => {
+                let mut state = state;
+                let maybe_xdomain = state.dstore_ref().get(&xwalk).cloned();
+                if let Some(xdomain) = maybe_xdomain {
+                    state = state.process_domain(&ywalk, Rc::new(self.scale(&xdomain)))?;
+                }
+
+                // The domain of `y` may have been narrowed or resolved by the domain of `x`
+                let ywalk = state.smap_ref().walk(&ywalk).clone();
+                if !ywalk.is_var() {
+                    return state.run_constraint(self);
+                }
+                let maybe_ydomain = state.dstore_ref().get(&ywalk).cloned();
+                if let Some(ydomain) = maybe_ydomain {
+                    state = state.process_domain(&xwalk, Rc::new(self.unscale(&ydomain)))?;
+                }
+                Ok(state.with_constraint(self))
+            }
+            _ => Err(ConstraintError::new()), /* Grounded to a term of invalid type */
+        }
+    }
+
+    fn operands(&self) -> Vec<LTerm<U, E>> {
+        vec![self.x.clone(), LTerm::from(self.k), self.y.clone()]
+    }
+}
+
+impl<U, E> std::fmt::Display for ScaleFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "scaleo({}, {}, {})", self.x, self.k, self.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::scaleo;
+    use crate::prelude::*;
+    use crate::relation::clpfd::infd::{infd, infdrange};
+
+    #[test]
+    fn test_scaleo_1() {
+        let query = proto_vulcan_query!(|x, y| {
+            infdrange(x, &(1..=3)),
+            scaleo(x, -2, y),
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(
+            [result.x.clone(), result.y.clone()],
+            [lterm!(1), lterm!(-2)]
+        );
+        let result = iter.next().unwrap();
+        assert_eq!(
+            [result.x.clone(), result.y.clone()],
+            [lterm!(2), lterm!(-4)]
+        );
+        let result = iter.next().unwrap();
+        assert_eq!(
+            [result.x.clone(), result.y.clone()],
+            [lterm!(3), lterm!(-6)]
+        );
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_scaleo_2() {
+        // Propagation from y to x
+        let query = proto_vulcan_query!(|x| {
+            |y| {
+                infdrange(y, &(-5..=5)),
+                scaleo(x, -2, y),
+            }
+        });
+        let xs: Vec<isize> = query.run().map(|r| r.x.get_number().unwrap()).collect();
+        assert_eq!(xs, vec![-2, -1, 0, 1, 2]);
+
+        let query = proto_vulcan_query!(|x| { scaleo(x, -2, 5) });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|x| { scaleo(x, -2, 6) });
+        assert_eq!(query.run().next().unwrap().x, -3);
+    }
+
+    #[test]
+    fn test_scaleo_3() {
+        // Negation maps the interval in reverse
+        let query = proto_vulcan_query!(|y| {
+            |x| {
+                infdrange(x, &(1..=3)),
+                scaleo(x, -1, y),
+                infd(y, &[-3, -1, 1]),
+            }
+        });
+        let ys: Vec<isize> = query.run().map(|r| r.y.get_number().unwrap()).collect();
+        assert_eq!(ys, vec![-3, -1]);
+
+        let query = proto_vulcan_query!(|y| {
+            |x| {
+                scaleo(x, 0, y),
+            }
+        });
+        assert_eq!(query.run().next().unwrap().y, 0);
+    }
+
+    #[test]
+    fn test_scaleo_4() {
+        // Wide intervals are mapped through their bounds
+        let query = proto_vulcan_query!(|x, y| {
+            infdrange(x, &(-(1 << 40)..=(1 << 40))),
+            infdrange(y, &(-7..=7)),
+            scaleo(x, 3, y),
+        });
+        let mut xs: Vec<isize> = query.run().map(|r| r.x.get_number().unwrap()).collect();
+        xs.sort_unstable();
+        assert_eq!(xs, vec![-2, -1, 0, 1, 2]);
+
+        let query = proto_vulcan_query!(|x| {
+            infdrange(x, &(isize::MAX - 1..=isize::MAX)),
+            scaleo(x, 2, 6),
+        });
+        assert!(query.run().next().is_none());
+
+        // The quotient of isize::MIN by -1 overflows
+        let query = proto_vulcan_query!(|x| { scaleo(x, -1, { isize::MIN }) });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|x| {
+            |y| {
+                infd(y, &[isize::MIN, 4]),
+                scaleo(x, -1, y),
+            }
+        });
+        let xs: Vec<isize> = query.run().map(|r| r.x.get_number().unwrap()).collect();
+        assert_eq!(xs, vec![-4]);
+    }
+}
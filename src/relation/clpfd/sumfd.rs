@@ -0,0 +1,327 @@
+use crate::engine::Engine;
+/// Constrains the sum of a list of finite domains
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::{LTerm, LTermInner};
+use crate::lvalue::LValue;
+use crate::solver::{Solve, Solver};
+use crate::state::{Constraint, ConstraintError, FiniteDomain, SResult, State};
+use crate::stream::Stream;
+use crate::user::User;
+use std::rc::Rc;
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct SumFd<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    list: LTerm<U, E>,
+    s: LTerm<U, E>,
+}
+
+impl<U, E> SumFd<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new<G: AnyGoal<U, E>>(list: LTerm<U, E>, s: LTerm<U, E>) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(SumFd { list, s })))
+    }
+}
+
+impl<U, E> Solve<U, E> for SumFd<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        match state.run_constraint(SumFdConstraint::new(self.list.clone(), self.s.clone())) {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(error) => solver.fail(error),
+        }
+    }
+}
+
+/// A finite-domain relation where the elements of `list` sum to `s`.
+///
+/// The bounds of `s` are constrained by the sum of the bounds of the elements, and the bounds
+/// of each element by the bounds of `s` and of the other elements. The domains are propagated
+/// once all of the elements of the list are known and have domains; until then, the constraint
+/// is kept. If `s` does not have a domain, it is given one from the bounds of the elements.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::{infdrange, sumfd};
+/// fn main() {
+///     let query = proto_vulcan_query!(|a, b| {
+///         infdrange([a, b], &(0..=9)),
+///         sumfd([a, b, 3], 4),
+///     });
+///     let result: Vec<(isize, isize)> = query
+///         .run()
+///         .map(|r| (r.a.get_number().unwrap(), r.b.get_number().unwrap()))
+///         .collect();
+///     assert_eq!(result, vec![(0, 1), (1, 0)]);
+/// }
+/// ```
+pub fn sumfd<U, E, G>(list: LTerm<U, E>, s: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    SumFd::new(list, s)
+}
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct SumFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    list: LTerm<U, E>,
+    s: LTerm<U, E>,
+}
+
+impl<U, E> SumFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new(list: LTerm<U, E>, s: LTerm<U, E>) -> Rc<dyn Constraint<U, E>> {
+        Rc::new(SumFdConstraint { list, s })
+    }
+
+    /// The list of the summed terms.
+    pub fn list(&self) -> &LTerm<U, E> {
+        &self.list
+    }
+
+    /// The sum of the terms.
+    pub fn sum(&self) -> &LTerm<U, E> {
+        &self.s
+    }
+}
+
+/// Bounds of a walked term: a number, or a variable with a domain.
+fn bounds<U, E>(state: &State<U, E>, u: &LTerm<U, E>) -> Option<(isize, isize)>
+where
+    U: User,
+    E: Engine<U>,
+{
+    match u.as_ref() {
+        LTermInner::Val(LValue::Number(n)) => Some((*n, *n)),
+        LTermInner::Var(_, _) => state
+            .dstore_ref()
+            .get(u)
+            .map(|domain| (domain.min(), domain.max())),
+        _ => None,
+    }
+}
+
+impl<U, E> Constraint<U, E> for SumFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn run(self: Rc<Self>, state: State<U, E>) -> SResult<U, E> {
+        let smap = state.get_smap();
+
+        // Walk the list, which may have been only partially instantiated.
+        let mut elements = vec![];
+        let mut rest = smap.walk(&self.list);
+        loop {
+            match rest.as_ref() {
+                LTermInner::Cons(head, tail) => {
+                    let head = smap.walk(head);
+                    match head.as_ref() {
+                        LTermInner::Var(_, _) | LTermInner::Val(LValue::Number(_)) => {
+                            elements.push(head.clone())
+                        }
+                        _ => return Err(ConstraintError::new()), /* Grounded to a term of invalid type */
+                    }
+                    rest = smap.walk(tail);
+                }
+                LTermInner::Empty => break,
+                // The rest of the list is not yet known; keep the constraint for later, with
+                // the walked elements so that their bindings wake it up.
+                LTermInner::Var(_, _) => {
+                    elements.push(rest.clone());
+                    let list = LTerm::improper_from_vec(elements);
+                    let s = smap.walk(&self.s).clone();
+                    return Ok(state.with_constraint(SumFdConstraint::new(list, s)));
+                }
+                _ => return Err(ConstraintError::new()), /* Grounded to a term of invalid type */
+            }
+        }
+
+        let swalk = smap.walk(&self.s).clone();
+        match swalk.as_ref() {
+            LTermInner::Var(_, _) | LTermInner::Val(LValue::Number(_)) => (),
+            _ => return Err(ConstraintError::new()), /* Grounded to a term of invalid type */
+        }
+
+        // If all elements are bound to numbers, then the sum is known and the constraint can
+        // be dropped.
+        let numbers: Option<Vec<isize>> = elements.iter().map(|u| u.get_number()).collect();
+        if let Some(numbers) = numbers {
+            return match numbers
+                .iter()
+                .try_fold(0isize, |sum, n| sum.checked_add(*n))
+            {
+                Some(sum) => state.unify(&swalk, &LTerm::from(sum)),
+                None => Err(ConstraintError::new()),
+            };
+        }
+
+        // If some element does not yet have a domain, then keep the constraint until it can
+        // be used to constrain some domains.
+        let element_bounds: Option<Vec<(isize, isize)>> =
+            elements.iter().map(|u| bounds(&state, u)).collect();
+        let element_bounds = match element_bounds {
+            Some(element_bounds) => element_bounds,
+            None => {
+                let list = LTerm::from_vec(elements);
+                return Ok(state.with_constraint(SumFdConstraint::new(list, swalk)));
+            }
+        };
+
+        // The constraint is: u_1 + ... + u_n = s
+        //
+        // Given the domains of the elements, the domain of s must be in range
+        //   s  =>  [sum(u_j min) .. sum(u_j max)]
+        // and given the domain of s, the domain of each element must be in range
+        //   u_i  =>  [s min - sum(u_j max, j != i) .. s max - sum(u_j min, j != i)]
+        //
+        // The constraint is not dropped until all variables converge into numbers.
+        let summin = element_bounds
+            .iter()
+            .fold(0isize, |sum, (min, _)| sum.saturating_add(*min));
+        let summax = element_bounds
+            .iter()
+            .fold(0isize, |sum, (_, max)| sum.saturating_add(*max));
+        let mut state =
+            state.process_domain(&swalk, Rc::new(FiniteDomain::from(summin..=summax)))?;
+
+        let swalk = state.smap_ref().walk(&swalk).clone();
+        let (smin, smax) = bounds(&state, &swalk).unwrap();
+        for (u, (umin, umax)) in elements.iter().zip(element_bounds.iter()) {
+            if u.is_var() {
+                let othersmax = summax.saturating_sub(*umax);
+                let othersmin = summin.saturating_sub(*umin);
+                let udomain = smin.saturating_sub(othersmax)..=smax.saturating_sub(othersmin);
+                state = state.process_domain(u, Rc::new(FiniteDomain::from(udomain)))?;
+            }
+        }
+
+        let list = LTerm::from_vec(elements);
+        Ok(state.with_constraint(SumFdConstraint::new(list, swalk)))
+    }
+
+    fn operands(&self) -> Vec<LTerm<U, E>> {
+        let mut operands: Vec<LTerm<U, E>> = self.list.iter().cloned().collect();
+        operands.push(self.s.clone());
+        operands
+    }
+}
+
+impl<U, E> std::fmt::Display for SumFdConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "sumfd({}, {})", self.list, self.s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sumfd;
+    use crate::prelude::*;
+    use crate::relation::clpfd::infd::{infd, infdrange};
+
+    #[test]
+    fn test_sumfd_1() {
+        let query = proto_vulcan_query!(|a, b, c| {
+            infd([a, b, c], &[1, 2, 3]),
+            sumfd([a, b, c], 6),
+        });
+        let result: Vec<Vec<isize>> = query
+            .run()
+            .map(|r| {
+                vec![
+                    r.a.get_number().unwrap(),
+                    r.b.get_number().unwrap(),
+                    r.c.get_number().unwrap(),
+                ]
+            })
+            .collect();
+        assert_eq!(result.len(), 7);
+        assert!(result.iter().all(|v| v.iter().sum::<isize>() == 6));
+        assert!(result.contains(&vec![2, 2, 2]));
+    }
+
+    #[test]
+    fn test_sumfd_2() {
+        // The sum is constrained by the domains of the elements
+        let query = proto_vulcan_query!(|s| {
+            |a, b| {
+                infdrange(a, &(0..=2)),
+                infdrange(b, &(3..=4)),
+                sumfd([a, b], s),
+            }
+        });
+        let ss: Vec<isize> = query.run().map(|r| r.s.get_number().unwrap()).collect();
+        assert_eq!(ss, vec![3, 4, 5, 6]);
+
+        // The elements are constrained by the sum
+        let query = proto_vulcan_query!(|a, b| {
+            infdrange([a, b], &(0..=9)),
+            sumfd([a, b], 17),
+        });
+        let result: Vec<(isize, isize)> = query
+            .run()
+            .map(|r| (r.a.get_number().unwrap(), r.b.get_number().unwrap()))
+            .collect();
+        assert_eq!(result, vec![(8, 9), (9, 8)]);
+    }
+
+    #[test]
+    fn test_sumfd_3() {
+        // The list is known only later
+        let query = proto_vulcan_query!(|q| {
+            |a, b| {
+                sumfd(q, 3),
+                infd([a, b], &[1, 2, 5]),
+                q == [a, b],
+            }
+        });
+        let mut result: Vec<LTerm> = query.run().map(|r| r.q.clone()).collect();
+        result.sort_by_key(|q| q.head().unwrap().get_number());
+        assert_eq!(result, vec![lterm!([1, 2]), lterm!([2, 1])]);
+
+        // The elements get their domains after the list is known
+        let query = proto_vulcan_query!(|q| {
+            |a, b, t| {
+                sumfd(q, 3),
+                q == [a | t],
+                t == [b],
+                infd([a, b], &[1, 2, 5]),
+            }
+        });
+        let mut result: Vec<LTerm> = query.run().map(|r| r.q.clone()).collect();
+        result.sort_by_key(|q| q.head().unwrap().get_number());
+        assert_eq!(result, vec![lterm!([1, 2]), lterm!([2, 1])]);
+
+        let query = proto_vulcan_query!(|q| { sumfd(5, q) });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|q| { sumfd([], q) });
+        assert_eq!(query.run().next().unwrap().q, 0);
+    }
+}
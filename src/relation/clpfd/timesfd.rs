@@ -4,7 +4,7 @@ use crate::goal::{AnyGoal, InferredGoal};
 use crate::lterm::{LTerm, LTermInner};
 use crate::lvalue::LValue;
 use crate::solver::{Solve, Solver};
-use crate::state::{Constraint, FiniteDomain, SResult, State};
+use crate::state::{Constraint, ConstraintError, FiniteDomain, SResult, State};
 use crate::stream::Stream;
 use crate::user::User;
 use std::rc::Rc;
@@ -40,10 +40,14 @@ where
     U: User,
     E: Engine<U>,
 {
-    fn solve(&self, _solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
-        match TimesFdConstraint::new(self.u.clone(), self.v.clone(), self.w.clone()).run(state) {
+    fn solve(&self, solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        match state.run_constraint(TimesFdConstraint::new(
+            self.u.clone(),
+            self.v.clone(),
+            self.w.clone(),
+        )) {
             Ok(state) => Stream::unit(Box::new(state)),
-            Err(_) => Stream::empty(),
+            Err(error) => solver.fail(error),
         }
     }
 }
@@ -75,9 +79,6 @@ where
     E: Engine<U>,
 {
     pub fn new(u: LTerm<U, E>, v: LTerm<U, E>, w: LTerm<U, E>) -> Rc<dyn Constraint<U, E>> {
-        assert!(u.is_var() || u.is_number());
-        assert!(v.is_var() || v.is_number());
-        assert!(w.is_var() || w.is_number());
         Rc::new(TimesFdConstraint { u, v, w })
     }
 }
@@ -99,7 +100,7 @@ where
                 singleton_udomain = Rc::new(FiniteDomain::from(*u));
                 Some(&singleton_udomain)
             }
-            _ => None,
+            _ => return Err(ConstraintError::new()), /* Grounded to a term of invalid type */
         };
 
         let vwalk = smap.walk(&self.v);
@@ -110,7 +111,7 @@ where
                 singleton_vdomain = Rc::new(FiniteDomain::from(*v));
                 Some(&singleton_vdomain)
             }
-            _ => None,
+            _ => return Err(ConstraintError::new()), /* Grounded to a term of invalid type */
         };
 
         let wwalk = smap.walk(&self.w);
@@ -121,7 +122,7 @@ where
                 singleton_wdomain = Rc::new(FiniteDomain::from(*w));
                 Some(&singleton_wdomain)
             }
-            _ => None,
+            _ => return Err(ConstraintError::new()), /* Grounded to a term of invalid type */
         };
 
         // If all operators are bound to numbers, then we can drop the constraint or fail if
@@ -132,7 +133,7 @@ where
             {
                 return Ok(state);
             } else {
-                return Err(());
+                return Err(ConstraintError::new());
             }
         }
 
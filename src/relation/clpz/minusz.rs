@@ -0,0 +1,85 @@
+use crate::engine::Engine;
+/// Constrains u - v = w
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::relation::clpz::plusz::PlusZ;
+use crate::user::User;
+
+/// Difference of unbounded integers.
+///
+/// `minusz(u, v, w)` constrains `u - v = w`. It is the sum `v + w = u`, therefore the
+/// constraint is solved as soon as any two of the three operands are grounded.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::minusz;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| { minusz(q, 3, -5) });
+///     assert_eq!(query.run().next().unwrap().q, -2);
+/// }
+/// ```
+pub fn minusz<U, E, G>(u: LTerm<U, E>, v: LTerm<U, E>, w: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    PlusZ::new(v, w, u)
+}
+
+#[cfg(test)]
+mod test {
+    use super::minusz;
+    use crate::prelude::*;
+    use crate::relation::clpz::plusz::plusz;
+
+    #[test]
+    fn test_minusz_1() {
+        let query = proto_vulcan_query!(|q| { minusz(7, 3, q) });
+        assert_eq!(query.run().next().unwrap().q, 4);
+
+        let query = proto_vulcan_query!(|q| { minusz(7, q, 10) });
+        assert_eq!(query.run().next().unwrap().q, -3);
+
+        let query = proto_vulcan_query!(|q| { minusz(q, -3, 10) });
+        assert_eq!(query.run().next().unwrap().q, 7);
+
+        let query = proto_vulcan_query!(|q| { minusz(7, 3, 5), q == 1 });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_minusz_2() {
+        // Chained sums and differences are solved in any order of grounding
+        let query = proto_vulcan_query!(|x, y| {
+            |z| {
+                minusz(y, x, 2),
+                plusz(x, 1, z),
+                z == 1_000_000_000_000,
+            }
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.x, 999_999_999_999);
+        assert_eq!(result.y, 1_000_000_000_001);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_minusz_3() {
+        let query = proto_vulcan_query!(|x, y| {
+            |s| {
+                plusz(x, y, s),
+                minusz(s, x, 3),
+                x == -5,
+            }
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.x, -5);
+        assert_eq!(result.y, 3);
+        assert!(iter.next().is_none());
+    }
+}
@@ -1,2 +1,3 @@
+pub mod minusz;
 pub mod plusz;
 pub mod timesz;
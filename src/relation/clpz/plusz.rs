@@ -4,7 +4,7 @@ use crate::goal::{AnyGoal, InferredGoal};
 use crate::lterm::{LTerm, LTermInner};
 use crate::lvalue::LValue;
 use crate::solver::{Solve, Solver};
-use crate::state::{Constraint, SResult, State};
+use crate::state::{Constraint, ConstraintError, SResult, State};
 use crate::stream::Stream;
 use crate::user::User;
 use std::rc::Rc;
@@ -40,10 +40,14 @@ where
     U: User,
     E: Engine<U>,
 {
-    fn solve(&self, _solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
-        match PlusZConstraint::new(self.u.clone(), self.v.clone(), self.w.clone()).run(state) {
+    fn solve(&self, solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        match state.run_constraint(PlusZConstraint::new(
+            self.u.clone(),
+            self.v.clone(),
+            self.w.clone(),
+        )) {
             Ok(state) => Stream::unit(Box::new(state)),
-            Err(_) => Stream::empty(),
+            Err(error) => solver.fail(error),
         }
     }
 }
@@ -76,9 +80,6 @@ where
     E: Engine<U>,
 {
     pub fn new(u: LTerm<U, E>, v: LTerm<U, E>, w: LTerm<U, E>) -> Rc<dyn Constraint<U, E>> {
-        assert!(u.is_var() || u.is_number());
-        assert!(v.is_var() || v.is_number());
-        assert!(w.is_var() || w.is_number());
         Rc::new(PlusZConstraint { u, v, w })
     }
 }
@@ -100,10 +101,10 @@ where
                 LTermInner::Val(LValue::Number(w)),
             ) => {
                 /* All operands grounded. */
-                if u * v == *w {
+                if u.checked_add(*v) == Some(*w) {
                     Ok(state)
                 } else {
-                    Err(())
+                    Err(ConstraintError::new())
                 }
             }
             (
@@ -112,10 +113,13 @@ where
                 LTermInner::Var(_, _),
             ) => {
                 /* u and v grounded */
-                state
-                    .smap_to_mut()
-                    .extend(wwalk.clone(), LTerm::from(u + v));
-                state.run_constraints()
+                match u.checked_add(*v) {
+                    Some(w) => {
+                        state.smap_to_mut().extend(wwalk.clone(), LTerm::from(w));
+                        state.run_constraints()
+                    }
+                    None => Err(ConstraintError::new()),
+                }
             }
             (
                 LTermInner::Val(LValue::Number(u)),
@@ -123,10 +127,13 @@ where
                 LTermInner::Val(LValue::Number(w)),
             ) => {
                 /* u and w grounded */
-                state
-                    .smap_to_mut()
-                    .extend(vwalk.clone(), LTerm::from(w - u));
-                state.run_constraints()
+                match w.checked_sub(*u) {
+                    Some(v) => {
+                        state.smap_to_mut().extend(vwalk.clone(), LTerm::from(v));
+                        state.run_constraints()
+                    }
+                    None => Err(ConstraintError::new()),
+                }
             }
             (
                 LTermInner::Var(_, _),
@@ -134,20 +141,24 @@ where
                 LTermInner::Val(LValue::Number(w)),
             ) => {
                 /* v and w grounded */
-                state
-                    .smap_to_mut()
-                    .extend(uwalk.clone(), LTerm::from(w - v));
-                state.run_constraints()
+                match w.checked_sub(*v) {
+                    Some(u) => {
+                        state.smap_to_mut().extend(uwalk.clone(), LTerm::from(u));
+                        state.run_constraints()
+                    }
+                    None => Err(ConstraintError::new()),
+                }
             }
             (LTermInner::Var(_, _), LTermInner::Var(_, _), LTermInner::Val(LValue::Number(_)))
             | (LTermInner::Var(_, _), LTermInner::Val(LValue::Number(_)), LTermInner::Var(_, _))
-            | (LTermInner::Val(LValue::Number(_)), LTermInner::Var(_, _), LTermInner::Var(_, _)) => {
+            | (LTermInner::Val(LValue::Number(_)), LTermInner::Var(_, _), LTermInner::Var(_, _))
+            | (LTermInner::Var(_, _), LTermInner::Var(_, _), LTermInner::Var(_, _)) => {
                 /* Not enough terms grounded to verify constraint. */
                 Ok(state.with_constraint(self))
             }
             _ => {
                 /* Some operands grounded to terms of invalid type. */
-                Err(())
+                Err(ConstraintError::new())
             }
         }
     }
@@ -195,4 +206,50 @@ mod test {
         assert_eq!(iter.next().unwrap().q, 6);
         assert!(iter.next().is_none());
     }
+
+    #[test]
+    fn test_plusz_3() {
+        let query = proto_vulcan_query!(|q| { plusz([1], 1, q) });
+
+        let mut iter = query.run();
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_plusz_4() {
+        // Grounded operands are checked with the sum
+        let query = proto_vulcan_query!(|q| { plusz(2, 3, 5), q == 1 });
+        assert_eq!(query.run().next().unwrap().q, 1);
+
+        let query = proto_vulcan_query!(|q| { plusz(2, 3, 6), q == 1 });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_plusz_5() {
+        // The constraint waits until enough of the operands are grounded
+        let query = proto_vulcan_query!(|q| {
+            |r, p| {
+                plusz(q, r, p),
+                r == 4,
+                p == -6,
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, -10);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_plusz_6() {
+        // Sums and differences that overflow fail
+        let query = proto_vulcan_query!(|q| { plusz({ isize::MAX }, 1, q) });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|q| { plusz({ isize::MAX }, 1, { isize::MIN }) });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|q| { plusz(q, 1, { isize::MIN }) });
+        assert!(query.run().next().is_none());
+    }
 }
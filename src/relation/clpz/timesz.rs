@@ -4,7 +4,7 @@ use crate::goal::{AnyGoal, InferredGoal};
 use crate::lterm::{LTerm, LTermInner};
 use crate::lvalue::LValue;
 use crate::solver::{Solve, Solver};
-use crate::state::{Constraint, SResult, State};
+use crate::state::{Constraint, ConstraintError, SResult, State};
 use crate::stream::Stream;
 use crate::user::User;
 use std::rc::Rc;
@@ -40,10 +40,14 @@ where
     U: User,
     E: Engine<U>,
 {
-    fn solve(&self, _solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
-        match TimesZConstraint::new(self.u.clone(), self.v.clone(), self.w.clone()).run(state) {
+    fn solve(&self, solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        match state.run_constraint(TimesZConstraint::new(
+            self.u.clone(),
+            self.v.clone(),
+            self.w.clone(),
+        )) {
             Ok(state) => Stream::unit(Box::new(state)),
-            Err(_) => Stream::empty(),
+            Err(error) => solver.fail(error),
         }
     }
 }
@@ -75,9 +79,6 @@ where
     E: Engine<U>,
 {
     pub fn new(u: LTerm<U, E>, v: LTerm<U, E>, w: LTerm<U, E>) -> Rc<dyn Constraint<U, E>> {
-        assert!(u.is_var() || u.is_number());
-        assert!(v.is_var() || v.is_number());
-        assert!(w.is_var() || w.is_number());
         Rc::new(TimesZConstraint { u, v, w })
     }
 }
@@ -99,10 +100,10 @@ where
                 LTermInner::Val(LValue::Number(w)),
             ) => {
                 /* All operands grounded. */
-                if u * v == *w {
+                if u.checked_mul(*v) == Some(*w) {
                     Ok(state)
                 } else {
-                    Err(())
+                    Err(ConstraintError::new())
                 }
             }
             (
@@ -111,42 +112,60 @@ where
                 LTermInner::Var(_, _),
             ) => {
                 /* u and v grounded */
-                state
-                    .smap_to_mut()
-                    .extend(wwalk.clone(), LTerm::from(u * v));
-                state.run_constraints()
+                match u.checked_mul(*v) {
+                    Some(w) => {
+                        state.smap_to_mut().extend(wwalk.clone(), LTerm::from(w));
+                        state.run_constraints()
+                    }
+                    None => Err(ConstraintError::new()),
+                }
             }
             (
-                LTermInner::Val(LValue::Number(u)),
+                LTermInner::Val(LValue::Number(x)),
                 LTermInner::Var(_, _),
                 LTermInner::Val(LValue::Number(w)),
-            ) => {
-                /* u and w grounded */
-                state
-                    .smap_to_mut()
-                    .extend(vwalk.clone(), LTerm::from(w / u));
-                state.run_constraints()
-            }
-            (
+            )
+            | (
                 LTermInner::Var(_, _),
-                LTermInner::Val(LValue::Number(v)),
+                LTermInner::Val(LValue::Number(x)),
                 LTermInner::Val(LValue::Number(w)),
             ) => {
-                /* v and w grounded */
-                state
-                    .smap_to_mut()
-                    .extend(uwalk.clone(), LTerm::from(w / v));
+                /* One factor and the product grounded */
+                let y = if uwalk.is_var() { &uwalk } else { &vwalk };
+                if *x == 0 {
+                    // Any value of the other factor gives a zero product.
+                    if *w == 0 {
+                        Ok(state)
+                    } else {
+                        Err(ConstraintError::new())
+                    }
+                } else {
+                    match (w.checked_rem(*x), w.checked_div(*x)) {
+                        (Some(0), Some(quotient)) => {
+                            state.smap_to_mut().extend(y.clone(), LTerm::from(quotient));
+                            state.run_constraints()
+                        }
+                        /* Not divisible by the factor, or the quotient overflows. */
+                        _ => Err(ConstraintError::new()),
+                    }
+                }
+            }
+            (LTermInner::Val(LValue::Number(0)), LTermInner::Var(_, _), LTermInner::Var(_, _))
+            | (LTermInner::Var(_, _), LTermInner::Val(LValue::Number(0)), LTermInner::Var(_, _)) => {
+                /* A zero factor gives a zero product */
+                state.smap_to_mut().extend(wwalk.clone(), LTerm::from(0));
                 state.run_constraints()
             }
             (LTermInner::Var(_, _), LTermInner::Var(_, _), LTermInner::Val(LValue::Number(_)))
             | (LTermInner::Var(_, _), LTermInner::Val(LValue::Number(_)), LTermInner::Var(_, _))
-            | (LTermInner::Val(LValue::Number(_)), LTermInner::Var(_, _), LTermInner::Var(_, _)) => {
+            | (LTermInner::Val(LValue::Number(_)), LTermInner::Var(_, _), LTermInner::Var(_, _))
+            | (LTermInner::Var(_, _), LTermInner::Var(_, _), LTermInner::Var(_, _)) => {
                 /* Not enough terms grounded to verify constraint. */
                 Ok(state.with_constraint(self))
             }
             _ => {
                 /* Some operands grounded to terms of invalid type. */
-                Err(())
+                Err(ConstraintError::new())
             }
         }
     }
@@ -194,4 +213,65 @@ mod test {
         assert_eq!(iter.next().unwrap().q, 4);
         assert!(iter.next().is_none());
     }
+
+    #[test]
+    fn test_timesz_3() {
+        // Zero factors
+        let query = proto_vulcan_query!(|q| { |r| { timesz(0, r, q) } });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, 0);
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| { timesz(q, 0, 0) });
+        let mut iter = query.run();
+        assert!(iter.next().unwrap().q.is_any());
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| { timesz(0, q, 5) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_timesz_4() {
+        // Signs of the factors, and products that are not divisible by the factor
+        let query = proto_vulcan_query!(|q| { timesz(-3, q, 12) });
+        assert_eq!(query.run().next().unwrap().q, -4);
+
+        let query = proto_vulcan_query!(|q| { timesz(q, -3, -12) });
+        assert_eq!(query.run().next().unwrap().q, 4);
+
+        let query = proto_vulcan_query!(|q| { timesz(-3, -4, q) });
+        assert_eq!(query.run().next().unwrap().q, 12);
+
+        let query = proto_vulcan_query!(|q| { timesz(2, q, 7) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_timesz_5() {
+        // The constraint waits until enough of the operands are grounded
+        let query = proto_vulcan_query!(|q| {
+            |r, p| {
+                timesz(q, r, p),
+                p == 21,
+                r == 7,
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, 3);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_timesz_6() {
+        // Products and quotients that overflow fail
+        let query = proto_vulcan_query!(|q| { timesz({ isize::MAX }, 2, q) });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|q| { timesz({ isize::MIN }, -1, { isize::MIN }) });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|q| { timesz(q, -1, { isize::MIN }) });
+        assert!(query.run().next().is_none());
+    }
 }
@@ -0,0 +1,171 @@
+use crate::compound::CompoundList;
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, GoalCast, InferredGoal};
+use crate::lterm::LTerm;
+use crate::operator::conj::InferredConj;
+use crate::relation::eq;
+use crate::user::User;
+
+/// A relation where compound list `compound` is a list cell with `head`, and a tail that has
+/// the same elements as `rest`.
+fn compound_cell<U, E, G, T>(
+    compound: T,
+    head: LTerm<U, E>,
+    rest: LTerm<U, E>,
+) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+    T: CompoundList<U, E>,
+{
+    let tail = T::new_var("tail");
+    let cell = T::cons(head, tail.clone());
+    InferredConj::from_array(&[
+        eq(compound.into(), cell.into()).cast_into(),
+        compound_listo(tail, rest).cast_into(),
+    ])
+}
+
+/// A relation where compound list `compound` has the same elements as the list `list`.
+///
+/// The relation bridges typed compound lists to the standard list relations, such as
+/// `append` and `member`, which operate on untyped lists. The recursion follows `list`,
+/// therefore when converting from a compound list to an untyped list, the compound list
+/// should be bound first.
+///
+/// The type of the compound list cannot be inferred from the relation arguments in
+/// proto-vulcan expressions, therefore the relation is typically wrapped in a relation
+/// that is specific to the compound list type.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::compound::CompoundList;
+/// use proto_vulcan::goal::InferredGoal;
+/// use proto_vulcan::relation::compound_listo;
+///
+/// #[compound]
+/// struct Cell(LTerm, Cell);
+///
+/// impl<U: User, E: Engine<U>> CompoundList<U, E> for Cell<U, E> {
+///     fn cons(head: LTerm<U, E>, tail: Cell<U, E>) -> Cell<U, E> {
+///         Cell_compound::_InnerCell(head, tail).into()
+///     }
+/// }
+///
+/// fn cell_listo<U, E, G>(cell: Cell<U, E>, list: LTerm<U, E>) -> InferredGoal<U, E, G>
+/// where
+///     U: User,
+///     E: Engine<U>,
+///     G: AnyGoal<U, E>,
+/// {
+///     compound_listo(cell, list)
+/// }
+///
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| {
+///         cell_listo(Cell(1, Cell(2, [])), q)
+///     });
+///     assert!(query.run().next().unwrap().q == lterm!([1, 2]));
+/// }
+/// ```
+pub fn compound_listo<U, E, G, T>(compound: T, list: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+    T: CompoundList<U, E>,
+{
+    proto_vulcan_closure!(match list {
+        [] => compound == [],
+        [head | rest] => compound_cell::<U, E, _, T>(compound.clone(), head.clone(), rest.clone()),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::compound_listo;
+    use crate::compound::CompoundList;
+    use crate::goal::InferredGoal;
+    use crate::operator::dfs;
+    use crate::prelude::*;
+    use crate::relation::append;
+
+    #[compound]
+    struct Cell(LTerm, Cell);
+
+    impl<U: User, E: Engine<U>> CompoundList<U, E> for Cell<U, E> {
+        fn cons(head: LTerm<U, E>, tail: Cell<U, E>) -> Cell<U, E> {
+            Cell_compound::_InnerCell(head, tail).into()
+        }
+    }
+
+    fn cell_listo<U, E, G>(cell: Cell<U, E>, list: LTerm<U, E>) -> InferredGoal<U, E, G>
+    where
+        U: User,
+        E: Engine<U>,
+        G: AnyGoal<U, E>,
+    {
+        compound_listo(cell, list)
+    }
+
+    #[test]
+    fn test_compound_listo_1() {
+        let query = proto_vulcan_query!(|q| { cell_listo(Cell(1, Cell(2, Cell(3, []))), q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([1, 2, 3]));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q: Cell| { cell_listo(q, []) });
+        assert_eq!(query.run().next().unwrap().q, lterm!([]));
+    }
+
+    #[test]
+    fn test_compound_listo_2() {
+        let query = proto_vulcan_query!(|q: Cell| { cell_listo(q, [1, 2]) });
+        let mut iter = query.run();
+        let expected: LTerm = Cell::cons(
+            LTerm::from(1),
+            Cell::cons(LTerm::from(2), CompoundTerm::new_none()),
+        )
+        .into();
+        assert_eq!(iter.next().unwrap().q, expected);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_compound_listo_3() {
+        // Appending two compound lists
+        let query = proto_vulcan_query!(|q: Cell, a, b, c| {
+            cell_listo(Cell(1, Cell(2, [])), a),
+            cell_listo(Cell(3, []), b),
+            append(a, b, c),
+            cell_listo(q, c),
+        });
+        let mut iter = query.run();
+        let expected: LTerm = Cell::cons(
+            LTerm::from(1),
+            Cell::cons(
+                LTerm::from(2),
+                Cell::cons(LTerm::from(3), CompoundTerm::new_none()),
+            ),
+        )
+        .into();
+        assert_eq!(iter.next().unwrap().q, expected);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_compound_listo_dfs() {
+        let query = proto_vulcan_query!(|q| {
+            dfs {
+                cell_listo(Cell(1, Cell(2, [])), q),
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([1, 2]));
+        assert!(iter.next().is_none());
+    }
+}
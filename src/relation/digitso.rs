@@ -0,0 +1,175 @@
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::state::ConstraintError;
+use crate::stream::Stream;
+use crate::user::User;
+
+/// Digits of non-negative `n` in `base`, the most significant digit first.
+fn to_digits(mut n: isize, base: isize) -> Vec<isize> {
+    let mut digits = vec![n % base];
+    n /= base;
+    while n > 0 {
+        digits.push(n % base);
+        n /= base;
+    }
+    digits.reverse();
+    digits
+}
+
+/// Number from its digits in `base`. Returns `None` if the digits are not a canonical
+/// representation of a number, or if the number does not fit in `isize`.
+fn from_digits(digits: &[isize], base: isize) -> Option<isize> {
+    match digits {
+        [] => return None,
+        [0, _, ..] => return None,
+        _ => (),
+    }
+    digits.iter().try_fold(0isize, |n, digit| {
+        if *digit < 0 || *digit >= base {
+            None
+        } else {
+            n.checked_mul(base)?.checked_add(*digit)
+        }
+    })
+}
+
+/// A relation where `digits` is the list of digits of non-negative number `n` in `base`, the
+/// most significant digit first.
+///
+/// The relation decomposes `n` when it is a number, and reconstructs `n` from `digits` when
+/// it is a list of numbers. The digits are canonical: there are no leading zeros, and zero has
+/// the single digit `[0]`.
+///
+/// # Limitations
+/// The relation is not fully relational. The `base` must be a number of at least 2, and
+/// either `n` or all of the `digits` must be known when the relation is evaluated; otherwise
+/// the relation fails. Therefore, in cryptarithm puzzles the digits should be bound, for
+/// example with `distinctfd` and `infd`, before the numbers are constructed.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::digitso;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| {
+///         digitso(123, 10, q)
+///     });
+///     assert!(query.run().next().unwrap().q == lterm!([1, 2, 3]));
+/// }
+/// ```
+pub fn digitso<U, E, G>(
+    n: LTerm<U, E>,
+    base: LTerm<U, E>,
+    digits: LTerm<U, E>,
+) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan!(fngoal move |solver, state| {
+        let smap = state.smap_ref();
+        let base_value = match smap.walk(&base).get_number() {
+            Some(b) if b >= 2 => b,
+            _ => return solver.fail(ConstraintError::new()),
+        };
+
+        let result = match smap.walk(&n).get_number() {
+            Some(n_value) if n_value >= 0 => {
+                let d = to_digits(n_value, base_value);
+                let d = LTerm::from_vec(d.into_iter().map(LTerm::from).collect());
+                state.unify(&digits, &d)
+            }
+            Some(_) => return solver.fail(ConstraintError::new()),
+            None => {
+                let d = smap.walk_star(&digits);
+                if !d.is_list() || d.is_improper() {
+                    return solver.fail(ConstraintError::new());
+                }
+                let values: Option<Vec<isize>> = d.iter().map(|digit| digit.get_number()).collect();
+                match values.and_then(|values| from_digits(&values, base_value)) {
+                    Some(n_value) => state.unify(&n, &LTerm::from(n_value)),
+                    None => return solver.fail(ConstraintError::new()),
+                }
+            }
+        };
+
+        match result {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(error) => solver.fail(error),
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::digitso;
+    use crate::operator::dfs;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_digitso_1() {
+        let query = proto_vulcan_query!(|q| { digitso(123, 10, q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([1, 2, 3]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_digitso_2() {
+        let query = proto_vulcan_query!(|q| { digitso(q, 10, [1, 2, 3]) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, 123);
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| { digitso(q, 2, [1, 1, 0]) });
+        assert_eq!(query.run().next().unwrap().q, 6);
+    }
+
+    #[test]
+    fn test_digitso_3() {
+        let query = proto_vulcan_query!(|q| { digitso(0, 10, q) });
+        assert_eq!(query.run().next().unwrap().q, lterm!([0]));
+
+        // Leading zeros and digits outside of the base are not valid
+        let query = proto_vulcan_query!(|q| { digitso(q, 10, [0, 1]) });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|q| { digitso(q, 2, [1, 2]) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_digitso_4() {
+        // Digits become known later in the conjunction
+        let query = proto_vulcan_query!(|q| {
+            |a, b| {
+                [a, b] == [4, 2],
+                digitso(q, 10, [a, b]),
+            }
+        });
+        assert_eq!(query.run().next().unwrap().q, 42);
+
+        let query = proto_vulcan_query!(|q| {
+            |a, b| {
+                digitso(q, 10, [a, b]),
+                [a, b] == [4, 2],
+            }
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_digitso_dfs() {
+        let query = proto_vulcan_query!(|q| {
+            dfs {
+                digitso(q, 2, [1, 0, 1]),
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, 5);
+        assert!(iter.next().is_none());
+    }
+}
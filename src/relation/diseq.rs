@@ -26,7 +26,7 @@ use crate::engine::Engine;
 use crate::goal::{AnyGoal, InferredGoal};
 use crate::lterm::LTerm;
 use crate::solver::{Solve, Solver};
-use crate::state::{unify_rec, Constraint, SMap, SResult, State};
+use crate::state::{unify_rec, Constraint, ConstraintError, SMap, SResult, State};
 use crate::stream::Stream;
 use crate::user::User;
 use std::rc::Rc;
@@ -57,11 +57,11 @@ where
     U: User,
     E: Engine<U>,
 {
-    fn solve(&self, _solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+    fn solve(&self, solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
         // Return state where u and v are unified under s, or None if unification is not possible
         match state.disunify(&self.u, &self.v) {
             Ok(state) => Stream::unit(Box::new(state)),
-            Err(_) => Stream::empty(),
+            Err(error) => solver.fail(error),
         }
     }
 }
@@ -123,7 +123,7 @@ where
                 let mut state = State::new(Default::default()).with_smap(other.smap_ref().clone());
                 for (u, v) in self.0.iter() {
                     match unify_rec(state, &mut extension, &u, &v) {
-                        Err(()) => return false,
+                        Err(_) => return false,
                         Ok(s) => state = s,
                     }
                 }
@@ -166,7 +166,7 @@ where
         }
 
         if extension.is_empty() {
-            Err(())
+            Err(ConstraintError::new())
         } else {
             let c = DisequalityConstraint::new(extension);
             Ok(state.with_constraint(c))
@@ -184,10 +184,13 @@ where
     E: Engine<U>,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        for (u, v) in self.0.iter() {
-            write!(f, "{} != {},", u, v)?;
+        for (count, (u, v)) in self.0.iter().enumerate() {
+            if count > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{} != {}", u, v)?;
         }
-        write!(f, "")
+        Ok(())
     }
 }
 
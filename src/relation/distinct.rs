@@ -41,4 +41,32 @@ mod test {
         let mut iter = query.run();
         assert!(iter.next().unwrap().q == lterm!([1, 2]));
     }
+
+    #[test]
+    fn test_distinct_2() {
+        let query = proto_vulcan_query!(|a| { distinct([a, 1, "x"]) });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert!(result.a.is_any_except(&1));
+        assert!(result.a.is_any_except(&"x"));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_distinct_3() {
+        let query = proto_vulcan_query!(|q| {
+            |a| {
+                distinct([a, 1, "x"]),
+                conde {
+                    a == 1,
+                    a == "x",
+                    a == 'y',
+                },
+                q == a,
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, 'y');
+        assert!(iter.next().is_none());
+    }
 }
@@ -0,0 +1,77 @@
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::relation::length::decrementfd;
+use crate::user::User;
+
+/// A relation where `suffix` is list `l` without its first `n` elements.
+///
+/// The count `n` is a finite-domain number, and `l` must have at least `n` elements. When `n`
+/// is fresh, the suffixes of `l` are enumerated from the longest.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::dropo;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| { dropo(2, [1, 2, 3, 4], q) });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().q, lterm!([3, 4]));
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn dropo<U, E, G>(n: LTerm<U, E>, l: LTerm<U, E>, suffix: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan_closure!(
+        match [n, l] {
+            [0, _] => suffix == l,
+            [_, [_ | rest]] => |m| {
+                decrementfd(n, m),
+                dropo(m, rest, suffix),
+            }
+        }
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::dropo;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_dropo_1() {
+        let query = proto_vulcan_query!(|q| { dropo(2, [1, 2, 3, 4], q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([3, 4]));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| { dropo(2, [1, 2], q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([]));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| { dropo(3, [1, 2], q) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_dropo_2() {
+        // The count is found from the suffix, and all suffixes are enumerated
+        let query = proto_vulcan_query!(|q| { dropo(q, [1, 2, 3], [3]) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, 2);
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|n, q| { dropo(n, [1, 2], q) });
+        let results: Vec<String> = query
+            .run()
+            .map(|result| format!("{} {}", result.n, result.q))
+            .collect();
+        assert_eq!(results, vec!["0 [1, 2]", "1 [2]", "2 []"]);
+    }
+}
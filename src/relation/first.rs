@@ -50,4 +50,11 @@ mod test {
         assert!(iter.next().unwrap().q == lterm!([1, 2]));
         assert!(iter.next().is_none());
     }
+
+    #[test]
+    fn test_first_5() {
+        let query = proto_vulcan_query!(|q| { first(1, q) });
+        let mut iter = query.run();
+        assert!(iter.next().is_none());
+    }
 }
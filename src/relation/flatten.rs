@@ -0,0 +1,124 @@
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::operator::cond;
+use crate::relation::append;
+use crate::relation::typeo::{Typeo, ValueKind};
+use crate::user::User;
+
+/// A relation that constrains `u` to be a literal value.
+fn atomo<U, E, G>(u: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    Typeo::new(u, ValueKind::Value)
+}
+
+/// A relation where `flat` is the left-to-right flattening of the nested list `tree`.
+///
+/// Each element of `tree` is either flattened recursively, if it is a list, or kept as an
+/// atom. The atoms are the literal values; an element that is still fresh is constrained to be
+/// a value when it is kept as an atom. Other kinds of elements, such as compound terms, are not
+/// flattened.
+///
+/// When `tree` is a proper list of known shape, there is a single solution. When `tree` is
+/// fresh, i.e. the relation is run fully backwards, there are infinitely many nested lists
+/// that flatten to `flat`, such as lists with any number of empty sublists, and the search
+/// does not terminate after the solutions have been produced. Wrap the goal in `onceo` to take
+/// only the first solution.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::operator::onceo;
+/// use proto_vulcan::relation::flatteno;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| { flatteno([[1, [2]], 3], q) });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().q, lterm!([1, 2, 3]));
+///     assert!(iter.next().is_none());
+///
+///     // Backwards, only the first of the infinitely many solutions is taken
+///     let query = proto_vulcan_query!(|q| { onceo { flatteno(q, [1, 2]) } });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().q, lterm!([1, 2]));
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn flatteno<U, E, G>(tree: LTerm<U, E>, flat: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan_closure!(
+        match tree {
+            [] => flat == [],
+            [head | tail] => |flat_tail| {
+                flatteno(tail, flat_tail),
+                cond {
+                    |flat_head| {
+                        flatteno(head, flat_head),
+                        append(flat_head, flat_tail, flat),
+                    },
+                    [atomo(head), flat == [head | flat_tail]],
+                },
+            }
+        }
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::flatteno;
+    use crate::operator::{dfs, onceo};
+    use crate::prelude::*;
+
+    #[test]
+    fn test_flatteno_1() {
+        let query = proto_vulcan_query!(|q| { flatteno([[1, [2]], 3], q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([1, 2, 3]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_flatteno_2() {
+        let query = proto_vulcan_query!(|q| { flatteno([], q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([]));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| { flatteno([1, [], [2, [[3]]]], q) });
+        assert_eq!(query.run().next().unwrap().q, lterm!([1, 2, 3]));
+
+        let query = proto_vulcan_query!(|q| { flatteno([1, [2]], [2, 1]) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_flatteno_3() {
+        // Backwards with onceo
+        let query = proto_vulcan_query!(|q| { onceo { flatteno(q, [1, 2]) } });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        let flat = proto_vulcan_query!(|r| { onceo { flatteno({ result.q.clone() }, r) } });
+        assert_eq!(flat.run().next().unwrap().r, lterm!([1, 2]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_flatteno_dfs() {
+        let query = proto_vulcan_query!(|q| {
+            dfs {
+                flatteno([[1, [2]], 3], q),
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([1, 2, 3]));
+        assert!(iter.next().is_none());
+    }
+}
@@ -0,0 +1,162 @@
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, GoalCast, InferredGoal};
+use crate::lterm::LTerm;
+use crate::relation::{infdrange, ltefd, sumfd};
+use crate::state::ConstraintError;
+use crate::user::User;
+
+/// A relation where `parts` has at most `len` parts, and each of the parts is at least `min`
+/// and at most `max`, in non-decreasing order.
+fn ordered_parts<U, E, G>(
+    parts: LTerm<U, E>,
+    min: LTerm<U, E>,
+    max: isize,
+    len: isize,
+) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    if len == 0 {
+        return proto_vulcan!(parts == []);
+    }
+    proto_vulcan_closure!(
+        match parts {
+            [] => ,
+            [part | rest] => {
+                infdrange(part, &(1..=max)),
+                ltefd(min, part),
+                ordered_parts(rest, part, {max}, {len - 1}),
+            }
+        }
+    )
+}
+
+/// A relation where `parts` is a partition of number `n` into positive integers.
+///
+/// The parts are in non-decreasing order, therefore each partition is found only once and not
+/// as all of its permutations. The number of parts is given by the length of `parts`, and if
+/// the length is not known, the partitions of all lengths are enumerated.
+///
+/// The number `n` must be known when the goal is solved; otherwise the goal fails.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::integer_partitiono;
+/// fn main() {
+///     let query = proto_vulcan_query!(|a, b| {
+///         integer_partitiono(5, [a, b])
+///     });
+///     let mut iter = query.run();
+///     let result = iter.next().unwrap();
+///     assert_eq!([result.a.get_number(), result.b.get_number()], [Some(1), Some(4)]);
+///     let result = iter.next().unwrap();
+///     assert_eq!([result.a.get_number(), result.b.get_number()], [Some(2), Some(3)]);
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn integer_partitiono<U, E, G>(n: LTerm<U, E>, parts: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan!(fngoal move |solver, state| {
+        let max = match state.smap_ref().walk(&n).get_number() {
+            Some(max) if max >= 0 => max,
+            _ => return solver.fail(ConstraintError::new()),
+        };
+
+        // Each of the parts is at least one, therefore there are at most `max` parts.
+        let n = n.clone();
+        let parts = parts.clone();
+        let goal: InferredGoal<U, E, G> = proto_vulcan!([
+            sumfd(parts, n),
+            ordered_parts(parts, 1, { max }, { max }),
+        ]);
+        GoalCast::<U, E, G>::cast_into(goal).solve(solver, state)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::integer_partitiono;
+    use crate::prelude::*;
+
+    fn partitions(query_result: impl Iterator<Item = LTerm>) -> Vec<Vec<isize>> {
+        let mut partitions: Vec<Vec<isize>> = query_result
+            .map(|parts| parts.iter().map(|p| p.get_number().unwrap()).collect())
+            .collect();
+        partitions.sort();
+        partitions
+    }
+
+    #[test]
+    fn test_integer_partitiono_1() {
+        let query = proto_vulcan_query!(|q| {
+            |a, b| {
+                q == [a, b],
+                integer_partitiono(5, q),
+            }
+        });
+        let result = partitions(query.run().map(|r| r.q.clone()));
+        assert_eq!(result, vec![vec![1, 4], vec![2, 3]]);
+    }
+
+    #[test]
+    fn test_integer_partitiono_2() {
+        // All lengths
+        let query = proto_vulcan_query!(|q| { integer_partitiono(4, q) });
+        let result = partitions(query.run().map(|r| r.q.clone()));
+        assert_eq!(
+            result,
+            vec![
+                vec![1, 1, 1, 1],
+                vec![1, 1, 2],
+                vec![1, 3],
+                vec![2, 2],
+                vec![4]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_integer_partitiono_3() {
+        let query = proto_vulcan_query!(|q| {
+            |a, b, c| {
+                q == [a, b, c],
+                integer_partitiono(2, q),
+            }
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_integer_partitiono_4() {
+        // A number that is not known fails
+        let query = proto_vulcan_query!(|n, parts| { integer_partitiono(n, parts) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_integer_partitiono_5() {
+        // The number is known only when the goal is solved
+        let query = proto_vulcan_query!(|q| {
+            |n, a, b| {
+                n == 5,
+                q == [a, b],
+                integer_partitiono(n, q),
+            }
+        });
+        let result = partitions(query.run().map(|r| r.q.clone()));
+        assert_eq!(result, vec![vec![1, 4], vec![2, 3]]);
+
+        let query = proto_vulcan_query!(|q| { integer_partitiono(0, q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([]));
+        assert!(iter.next().is_none());
+    }
+}
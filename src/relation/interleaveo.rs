@@ -0,0 +1,75 @@
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::user::User;
+
+/// A relation where `c` is an interleaving of lists `a` and `b`, such that the relative order
+/// of elements within `a` and within `b` is preserved in `c`.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::interleaveo;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| {
+///         interleaveo([1], [2], q)
+///     });
+///     let iter = query.run();
+///     let mut expected = vec![lterm!([1, 2]), lterm!([2, 1])];
+///     iter.for_each(|x| {
+///         let n = x.q.clone();
+///         assert!(expected.contains(&n));
+///         expected.retain(|y| &n != y);
+///     });
+///     assert_eq!(expected.len(), 0);
+/// }
+/// ```
+pub fn interleaveo<U, E, G>(a: LTerm<U, E>, b: LTerm<U, E>, c: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan_closure!(
+        match [a, b, c] {
+            [[], x, x] => ,
+            [[x | ax], [], [x | ax]] => ,
+            [[x | ax], [y | bx], [x | cx]] => interleaveo(ax, [y | bx], cx),
+            [[x | ax], [y | bx], [y | cx]] => interleaveo([x | ax], bx, cx),
+        }
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::interleaveo;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_interleaveo_1() {
+        let query = proto_vulcan_query!(|q| { interleaveo([1], [2, 3], q) });
+        let iter = query.run();
+        let mut expected = vec![lterm!([1, 2, 3]), lterm!([2, 1, 3]), lterm!([2, 3, 1])];
+        iter.for_each(|x| {
+            let n = x.q.clone();
+            assert!(expected.contains(&n));
+            expected.retain(|y| &n != y);
+        });
+        assert_eq!(expected.len(), 0);
+    }
+
+    #[test]
+    fn test_interleaveo_2() {
+        let query = proto_vulcan_query!(|q| { interleaveo([1, 2], q, [3, 1, 4, 2]) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([3, 4]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_interleaveo_3() {
+        let query = proto_vulcan_query!(|q| { interleaveo([1, 2], [3], [2, 1, 3]) });
+        assert!(query.run().next().is_none());
+    }
+}
@@ -0,0 +1,118 @@
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, GoalCast, InferredGoal};
+use crate::lterm::LTerm;
+use crate::operator::conj::InferredConj;
+use crate::relation::{infdrange, plusfd};
+use crate::user::User;
+
+/// A relation where `n` is the length of list `l`.
+///
+/// The length is a finite-domain number. When `n` is known, `l` is a list of exactly `n`
+/// elements, where the unknown elements are fresh variables. When `l` is a proper list, `n` is
+/// its length. When neither is known, the lists are enumerated with increasing length; the
+/// enumeration is infinite also if `n` is constrained only by the goals after `lengtho`.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::lengtho;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| { lengtho([1, 2, 3], q) });
+///     assert_eq!(query.run().next().unwrap().q, 3);
+///
+///     let query = proto_vulcan_query!(|q| { lengtho(q, 2) });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().q.iter().count(), 2);
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn lengtho<U, E, G>(l: LTerm<U, E>, n: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan_closure!(
+        match l {
+            [] => n == 0,
+            [_ | rest] => |m| {
+                decrementfd(n, m),
+                lengtho(rest, m),
+            }
+        }
+    )
+}
+
+/// A relation where finite-domain number `m` is `n - 1`, and both are non-negative.
+///
+/// Used by the list relations that count elements down to zero.
+pub(crate) fn decrementfd<U, E, G>(n: LTerm<U, E>, m: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    // The upper bound leaves room for `m + 1`.
+    let range = 0..=isize::MAX - 1;
+    InferredConj::from_array(&[
+        infdrange(m.clone(), &range).cast_into(),
+        infdrange(n.clone(), &range).cast_into(),
+        plusfd(m, 1.into(), n).cast_into(),
+    ])
+}
+
+#[cfg(test)]
+mod test {
+    use super::lengtho;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_lengtho_1() {
+        let query = proto_vulcan_query!(|q| { |a, b, c| { lengtho([a, b, c], q) } });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, 3);
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| { lengtho([], q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, 0);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_lengtho_2() {
+        let query = proto_vulcan_query!(|q| { lengtho(q, 2) });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        let cells: Vec<LTerm> = result.q.iter().cloned().collect();
+        assert_eq!(cells.len(), 2);
+        assert!(cells.iter().all(|cell| cell.is_var()));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| { lengtho([1, 2], 3) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_lengtho_3() {
+        // Partial lists and enumeration of lengths
+        let query = proto_vulcan_query!(|q, n| { |x| { q == [1, 2 | x], lengtho(q, n) } });
+        let mut iter = query.run();
+        let lengths: Vec<isize> = (0..3)
+            .map(|_| iter.next().unwrap().n.get_number().unwrap())
+            .collect();
+        assert_eq!(lengths, vec![2, 3, 4]);
+
+        let query = proto_vulcan_query!(|q, n| {
+            |x| {
+                q == [1, 2 | x],
+                n == 3,
+                lengtho(q, n),
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q.iter().count(), 3);
+        assert!(iter.next().is_none());
+    }
+}
@@ -0,0 +1,103 @@
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::user::User;
+
+/// A relation where `value` is bound to `var` in environment `env`.
+///
+/// The environment is a list of `[name, value]`-bindings, where the most recent binding is
+/// first. Only the first binding of `var` is found, and the later bindings are shadowed by it.
+/// Shadowing is enforced with disequality constraints on the skipped names, therefore the
+/// relation is sound also when the names are not yet known. The relation fails if `var` is not
+/// bound in `env`.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::lookupo;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| {
+///         lookupo("x", [["y", 1], ["x", 2], ["x", 3]], q)
+///     });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().q, 2);
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn lookupo<U, E, G>(
+    var: LTerm<U, E>,
+    env: LTerm<U, E>,
+    value: LTerm<U, E>,
+) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan_closure!(
+        match env {
+            [[name, v] | _] => {
+                name == var,
+                v == value,
+            },
+            [[name, _] | rest] => {
+                name != var,
+                lookupo(var, rest, value),
+            }
+        }
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::lookupo;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_lookupo_1() {
+        let query = proto_vulcan_query!(|q| { lookupo("x", [["x", 1], ["y", 2], ["x", 3]], q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, 1);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_lookupo_2() {
+        let query = proto_vulcan_query!(|q| { lookupo("z", [["x", 1], ["y", 2]], q) });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|q| { lookupo("z", [], q) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_lookupo_3() {
+        // Enumerating the names finds only the visible bindings
+        let query = proto_vulcan_query!(|name, value| {
+            lookupo(name, [["x", 1], ["y", 2], ["x", 3]], value)
+        });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.name, "x");
+        assert_eq!(result.value, 1);
+        let result = iter.next().unwrap();
+        assert_eq!(result.name, "y");
+        assert_eq!(result.value, 2);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_lookupo_4() {
+        // A shadowing binding with a name that is only later known
+        let query = proto_vulcan_query!(|q| {
+            |n| {
+                lookupo("x", [[n, 1], ["x", 2]], q),
+                n == "x",
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, 1);
+        assert!(iter.next().is_none());
+    }
+}
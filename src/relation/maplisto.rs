@@ -0,0 +1,124 @@
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::user::User;
+use std::rc::Rc;
+
+type Relation3<U, E, G> =
+    Rc<dyn Fn(LTerm<U, E>, LTerm<U, E>, LTerm<U, E>) -> InferredGoal<U, E, G>>;
+
+fn maplist3<U, E, G>(
+    f: Relation3<U, E, G>,
+    a: LTerm<U, E>,
+    b: LTerm<U, E>,
+    c: LTerm<U, E>,
+) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan_closure!(
+        match [a, b, c] {
+            [[], [], []] => ,
+            [[x | xs], [y | ys], [z | zs]] => {
+                f(x, y, z),
+                maplist3({Rc::clone(&f)}, xs, ys, zs),
+            }
+        }
+    )
+}
+
+/// A relation where `f` holds for each of the corresponding elements of lists `a`, `b` and
+/// `c`.
+///
+/// The lists have equal length, and the relation fails if the lengths differ. This is the
+/// analog of Prolog `maplist/4`.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::{maplisto3, plusfd};
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| {
+///         maplisto3(|x, y, z| proto_vulcan!(plusfd(x, y, z)), [1, 2], [3, 4], q)
+///     });
+///     assert!(query.run().next().unwrap().q == lterm!([4, 6]));
+/// }
+/// ```
+pub fn maplisto3<U, E, G, F>(
+    f: F,
+    a: LTerm<U, E>,
+    b: LTerm<U, E>,
+    c: LTerm<U, E>,
+) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+    F: Fn(LTerm<U, E>, LTerm<U, E>, LTerm<U, E>) -> G + 'static,
+{
+    let f = move |x, y, z| InferredGoal::new(f(x, y, z));
+    maplist3(Rc::new(f), a, b, c)
+}
+
+#[cfg(test)]
+mod test {
+    use super::maplisto3;
+    use crate::operator::dfs;
+    use crate::prelude::*;
+    use crate::relation::plusfd;
+
+    #[test]
+    fn test_maplisto3_1() {
+        let query = proto_vulcan_query!(|q| {
+            maplisto3(|x, y, z| proto_vulcan!(plusfd(x, y, z)), [1, 2], [3, 4], q)
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([4, 6]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_maplisto3_2() {
+        // Lengths differ
+        let query = proto_vulcan_query!(|q| {
+            maplisto3(|x, y, z| proto_vulcan!(plusfd(x, y, z)), [1, 2], [3], q)
+        });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|q| {
+            maplisto3(|x, y, z| proto_vulcan!(plusfd(x, y, z)), [1], [3], [4, 5])
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_maplisto3_3() {
+        // Runs backwards
+        let query = proto_vulcan_query!(|q| {
+            maplisto3(
+                |x, y, z| proto_vulcan!([x, y] == z),
+                q,
+                ["a", "b"],
+                [[1, "a"], [2, "b"]],
+            )
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([1, 2]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_maplisto3_dfs() {
+        let query = proto_vulcan_query!(|q| {
+            dfs {
+                maplisto3(|x, y, z| proto_vulcan!(plusfd(x, y, z)), [1, 2], [3, 4], q),
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([4, 6]));
+        assert!(iter.next().is_none());
+    }
+}
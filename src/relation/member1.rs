@@ -5,6 +5,11 @@ use crate::user::User;
 
 /// A relation that succeeds once if `x` is in list `l`.
 ///
+/// Unlike `member`, which succeeds for each occurrence of `x`, the repeated occurrences of a
+/// value are skipped: an element is a solution only if it is not equal to any of the
+/// elements before it. Therefore, if `x` is ground, the relation succeeds at most once, and
+/// if `x` is fresh, it succeeds once for each distinct value of the list.
+///
 /// # Example
 /// ```rust
 /// extern crate proto_vulcan;
@@ -145,4 +150,16 @@ mod tests {
         assert_eq!(iter.next().unwrap().q, lterm!(3));
         assert!(iter.next().is_none());
     }
+
+    #[test]
+    fn test_member1_11() {
+        // A ground element is found once
+        let query = proto_vulcan_query!(|q| { q == 1, member1(q, [1, 1, 2]) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, 1);
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| { q == 3, member1(q, [1, 1, 2]) });
+        assert!(query.run().next().is_none());
+    }
 }
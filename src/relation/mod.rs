@@ -34,6 +34,14 @@
 //!
 //! # fn main() {}
 //! ```
+#[cfg(feature = "extras")]
+#[doc(hidden)]
+pub mod absento;
+
+#[cfg(feature = "extras")]
+#[doc(hidden)]
+pub mod all_equalo;
+
 #[cfg(feature = "extras")]
 #[doc(hidden)]
 pub mod always;
@@ -42,10 +50,22 @@ pub mod always;
 #[doc(hidden)]
 pub mod append;
 
+#[cfg(feature = "extras")]
+#[doc(hidden)]
+pub mod assoc;
+
+#[cfg(feature = "extras")]
+#[doc(hidden)]
+pub mod compound_listo;
+
 #[cfg(feature = "extras")]
 #[doc(hidden)]
 pub mod cons;
 
+#[cfg(feature = "extras")]
+#[doc(hidden)]
+pub mod digitso;
+
 #[cfg(feature = "core")]
 #[doc(hidden)]
 pub mod diseq;
@@ -54,6 +74,10 @@ pub mod diseq;
 #[doc(hidden)]
 pub mod distinct;
 
+#[cfg(all(feature = "extras", feature = "clpfd"))]
+#[doc(hidden)]
+pub mod drop;
+
 #[cfg(feature = "extras")]
 #[doc(hidden)]
 pub mod empty;
@@ -70,6 +94,30 @@ pub mod fail;
 #[doc(hidden)]
 pub mod first;
 
+#[cfg(feature = "extras")]
+#[doc(hidden)]
+pub mod flatten;
+
+#[cfg(all(feature = "extras", feature = "clpfd"))]
+#[doc(hidden)]
+pub mod integer_partitiono;
+
+#[cfg(feature = "extras")]
+#[doc(hidden)]
+pub mod interleaveo;
+
+#[cfg(all(feature = "extras", feature = "clpfd"))]
+#[doc(hidden)]
+pub mod length;
+
+#[cfg(feature = "extras")]
+#[doc(hidden)]
+pub mod lookupo;
+
+#[cfg(feature = "extras")]
+#[doc(hidden)]
+pub mod maplisto;
+
 #[cfg(feature = "extras")]
 #[doc(hidden)]
 pub mod member1;
@@ -78,10 +126,18 @@ pub mod member1;
 #[doc(hidden)]
 pub mod member;
 
+#[cfg(feature = "extras")]
+#[doc(hidden)]
+pub mod nats;
+
 #[cfg(feature = "extras")]
 #[doc(hidden)]
 pub mod never;
 
+#[cfg(feature = "extras")]
+#[doc(hidden)]
+pub mod palindromeo;
+
 #[cfg(feature = "extras")]
 #[doc(hidden)]
 pub mod permute;
@@ -90,14 +146,50 @@ pub mod permute;
 #[doc(hidden)]
 pub mod rember;
 
+#[cfg(feature = "extras")]
+#[doc(hidden)]
+pub mod replaceo;
+
 #[cfg(feature = "extras")]
 #[doc(hidden)]
 pub mod rest;
 
+#[cfg(feature = "extras")]
+#[doc(hidden)]
+pub mod reverse;
+
+#[cfg(all(feature = "extras", feature = "clpz"))]
+#[doc(hidden)]
+pub mod rleo;
+
+#[cfg(all(feature = "extras", feature = "clpz"))]
+#[doc(hidden)]
+pub mod rotateo;
+
+#[cfg(feature = "extras")]
+#[doc(hidden)]
+pub mod same_shapeo;
+
+#[cfg(feature = "extras")]
+#[doc(hidden)]
+pub mod split;
+
+#[cfg(feature = "extras")]
+#[doc(hidden)]
+pub mod str_lteo;
+
 #[cfg(feature = "core")]
 #[doc(hidden)]
 pub mod succeed;
 
+#[cfg(all(feature = "extras", feature = "clpfd"))]
+#[doc(hidden)]
+pub mod take;
+
+#[cfg(feature = "extras")]
+#[doc(hidden)]
+pub mod typeo;
+
 // CLP(FD)
 #[cfg(feature = "clpfd")]
 pub mod clpfd;
@@ -115,22 +207,46 @@ pub use diseq::diseq;
 #[doc(inline)]
 pub use eq::eq;
 
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use absento::absento;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use all_equalo::all_equalo;
+
 #[cfg(feature = "extras")]
 #[doc(inline)]
 pub use always::always;
 
 #[cfg(feature = "extras")]
 #[doc(inline)]
-pub use append::append;
+pub use append::{append, append_bounded};
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use assoc::assoco;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use compound_listo::compound_listo;
 
 #[cfg(feature = "extras")]
 #[doc(inline)]
 pub use cons::cons;
 
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use digitso::digitso;
+
 #[cfg(feature = "extras")]
 #[doc(inline)]
 pub use distinct::distinct;
 
+#[cfg(all(feature = "extras", feature = "clpfd"))]
+#[doc(inline)]
+pub use drop::dropo;
+
 #[cfg(feature = "extras")]
 #[doc(inline)]
 pub use empty::empty;
@@ -139,6 +255,30 @@ pub use empty::empty;
 #[doc(inline)]
 pub use first::first;
 
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use flatten::flatteno;
+
+#[cfg(all(feature = "extras", feature = "clpfd"))]
+#[doc(inline)]
+pub use integer_partitiono::integer_partitiono;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use interleaveo::interleaveo;
+
+#[cfg(all(feature = "extras", feature = "clpfd"))]
+#[doc(inline)]
+pub use length::lengtho;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use lookupo::lookupo;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use maplisto::maplisto3;
+
 #[cfg(feature = "extras")]
 #[doc(inline)]
 pub use member1::member1;
@@ -147,10 +287,18 @@ pub use member1::member1;
 #[doc(inline)]
 pub use member::member;
 
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use nats::{nats, natso};
+
 #[cfg(feature = "extras")]
 #[doc(inline)]
 pub use never::never;
 
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use palindromeo::palindromeo;
+
 #[cfg(feature = "extras")]
 #[doc(inline)]
 pub use permute::permute;
@@ -159,10 +307,46 @@ pub use permute::permute;
 #[doc(inline)]
 pub use rember::rember;
 
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use replaceo::replaceo;
+
 #[cfg(feature = "extras")]
 #[doc(inline)]
 pub use rest::rest;
 
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use reverse::reverseo;
+
+#[cfg(all(feature = "extras", feature = "clpz"))]
+#[doc(inline)]
+pub use rleo::rleo;
+
+#[cfg(all(feature = "extras", feature = "clpz"))]
+#[doc(inline)]
+pub use rotateo::rotateo;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use same_shapeo::same_shapeo;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use split::splito;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use str_lteo::{char_lteo, str_lteo};
+
+#[cfg(all(feature = "extras", feature = "clpfd"))]
+#[doc(inline)]
+pub use take::takeo;
+
+#[cfg(feature = "extras")]
+#[doc(inline)]
+pub use typeo::{numbero, stringo, symbolo};
+
 #[cfg(feature = "core")]
 #[doc(inline)]
 pub use fail::fail;
@@ -171,14 +355,26 @@ pub use fail::fail;
 #[doc(inline)]
 pub use succeed::succeed;
 
+#[cfg(feature = "clpfd")]
+#[doc(inline)]
+pub use clpfd::absfd::absfd;
+
 #[cfg(feature = "clpfd")]
 #[doc(inline)]
 pub use clpfd::diseqfd::diseqfd;
 
+#[cfg(feature = "clpfd")]
+#[doc(inline)]
+pub use clpfd::disjointo::disjointo;
+
 #[cfg(feature = "clpfd")]
 #[doc(inline)]
 pub use clpfd::distinctfd::distinctfd;
 
+#[cfg(feature = "clpfd")]
+#[doc(inline)]
+pub use clpfd::divfd::divfd;
+
 #[cfg(feature = "clpfd")]
 #[doc(inline)]
 pub use clpfd::infd::infd;
@@ -187,6 +383,10 @@ pub use clpfd::infd::infd;
 #[doc(inline)]
 pub use clpfd::infd::infdrange;
 
+#[cfg(feature = "clpfd")]
+#[doc(inline)]
+pub use clpfd::infd::infdrange_exclusive;
+
 #[cfg(feature = "clpfd")]
 #[doc(inline)]
 pub use clpfd::ltefd::ltefd;
@@ -199,14 +399,34 @@ pub use clpfd::ltfd::ltfd;
 #[doc(inline)]
 pub use clpfd::minusfd::minusfd;
 
+#[cfg(feature = "clpfd")]
+#[doc(inline)]
+pub use clpfd::modfd::modfd;
+
 #[cfg(feature = "clpfd")]
 #[doc(inline)]
 pub use clpfd::plusfd::plusfd;
 
+#[cfg(feature = "clpfd")]
+#[doc(inline)]
+pub use clpfd::reifyeq::reifyeq;
+
+#[cfg(feature = "clpfd")]
+#[doc(inline)]
+pub use clpfd::scaleo::scaleo;
+
+#[cfg(feature = "clpfd")]
+#[doc(inline)]
+pub use clpfd::sumfd::sumfd;
+
 #[cfg(feature = "clpfd")]
 #[doc(inline)]
 pub use clpfd::timesfd::timesfd;
 
+#[cfg(feature = "clpz")]
+#[doc(inline)]
+pub use clpz::minusz::minusz;
+
 #[cfg(feature = "clpz")]
 #[doc(inline)]
 pub use clpz::plusz::plusz;
@@ -0,0 +1,176 @@
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, Goal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::solver::{Solve, Solver};
+use crate::state::{ConstraintError, State};
+use crate::stream::{LazyStream, Stream};
+use crate::user::User;
+use std::rc::Rc;
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct Nats<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    start: LTerm<U, E>,
+    x: LTerm<U, E>,
+}
+
+impl<U, E> Nats<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new<G: AnyGoal<U, E>>(start: LTerm<U, E>, x: LTerm<U, E>) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(Nats { start, x })))
+    }
+}
+
+impl<U, E> Solve<U, E> for Nats<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        let start = match state.smap_ref().walk(&self.start).get_number() {
+            Some(start) => start,
+            None => return solver.fail(ConstraintError::new()),
+        };
+
+        let xwalk = state.smap_ref().walk(&self.x).clone();
+        if !xwalk.is_var() {
+            // Already bound, no need to generate the numbers.
+            return match xwalk.get_number() {
+                Some(x) if x >= start => Stream::unit(Box::new(state)),
+                _ => solver.fail(ConstraintError::new()),
+            };
+        }
+
+        // The numbers after `start` are generated only when the stream is advanced past the
+        // first answer. The stream ends at the largest `isize`.
+        let next = match start.checked_add(1) {
+            Some(next) => next,
+            None => {
+                return match state.unify(&self.x, &LTerm::from(start)) {
+                    Ok(state) => Stream::unit(Box::new(state)),
+                    Err(error) => solver.fail(error),
+                }
+            }
+        };
+        let rest: Goal<U, E> = Goal::dynamic(Rc::new(Nats {
+            start: LTerm::from(next),
+            x: self.x.clone(),
+        }));
+        let lazy = LazyStream::delay(Stream::pause(Box::new(state.clone()), rest));
+        match state.unify(&self.x, &LTerm::from(start)) {
+            Ok(state) => Stream::cons(Box::new(state), lazy),
+            Err(_) => Stream::lazy(lazy),
+        }
+    }
+}
+
+/// A relation where `x` is an integer greater than or equal to `start`.
+///
+/// If `x` is fresh, the numbers `start, start + 1, ...` are generated lazily one at a time,
+/// therefore the relation can be used for generate-and-test over an unbounded range of
+/// integers. The numbers end at `isize::MAX`. The relation fails if `start` is not a number.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::nats;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| {
+///         nats(10, q),
+///     });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().q, 10);
+///     assert_eq!(iter.next().unwrap().q, 11);
+/// }
+/// ```
+pub fn nats<U, E, G>(start: LTerm<U, E>, x: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    Nats::new(start, x)
+}
+
+/// A relation where `x` is a natural number, starting from zero.
+///
+/// Equivalent to `nats(0, x)`.
+pub fn natso<U, E, G>(x: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    Nats::new(LTerm::from(0), x)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{nats, natso};
+    use crate::prelude::*;
+
+    #[test]
+    fn test_natso_1() {
+        let query = proto_vulcan_query!(|q| { natso(q) });
+        let naturals: Vec<isize> = query
+            .run()
+            .take(5)
+            .map(|result| result.q.get_number().unwrap())
+            .collect();
+        assert_eq!(naturals, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_nats_1() {
+        // Generate and test
+        let query = proto_vulcan_query!(|q| {
+            nats(-2, q),
+            conde {
+                q == -3,
+                q == 0,
+                q == [1],
+            },
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, 0);
+    }
+
+    #[test]
+    fn test_nats_2() {
+        // Bound terms are checked without generating
+        let query = proto_vulcan_query!(|q| {
+            nats(3, 5),
+            nats(3, 3),
+            q == true,
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, true);
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| { nats(3, 2) });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|q| { nats(q, 2) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_nats_3() {
+        // The numbers end at the largest isize
+        let start = LTerm::from(isize::MAX - 1);
+        let query = proto_vulcan_query!(|q| { nats(start, q) });
+        let numbers: Vec<isize> = query
+            .run()
+            .map(|result| result.q.get_number().unwrap())
+            .collect();
+        assert_eq!(numbers, vec![isize::MAX - 1, isize::MAX]);
+    }
+}
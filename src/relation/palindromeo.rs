@@ -0,0 +1,74 @@
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::relation::append;
+use crate::user::User;
+
+/// A relation that succeeds when `list` is equal to its reverse.
+///
+/// The first and the last element of the list are unified, and the relation recurses into the
+/// middle of the list. When run with a list of fresh elements, the relation constrains the
+/// elements such that the list becomes a palindrome.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::palindromeo;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| {
+///         |x| {
+///             palindromeo([1, x, q]),
+///         }
+///     });
+///     assert!(query.run().next().unwrap().q == 1);
+/// }
+/// ```
+pub fn palindromeo<U, E, G>(list: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan_closure!(
+        match list {
+            [] => ,
+            [_] => ,
+            [x | rest] => |middle| {
+                append(middle, [x], rest),
+                palindromeo(middle),
+            }
+        }
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::palindromeo;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_palindromeo_1() {
+        let query = proto_vulcan_query!(|q| { palindromeo([1, 2, 1]) });
+        let mut iter = query.run();
+        assert!(iter.next().unwrap().q.is_any());
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_palindromeo_2() {
+        let query = proto_vulcan_query!(|q| { palindromeo([1, 2, 3]) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_palindromeo_3() {
+        let query = proto_vulcan_query!(|a, b, c, d| { palindromeo([a, b, c, d]) });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(*result.a, *result.d);
+        assert_eq!(*result.b, *result.c);
+        assert_ne!(*result.a, *result.b);
+        assert!(iter.next().is_none());
+    }
+}
@@ -0,0 +1,97 @@
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::operator::cond;
+use crate::user::User;
+
+/// A relation where `out` is equal to `list` with every occurrence of `old` replaced by `new`.
+///
+/// Elements that are not equal to `old` are kept with a disequality constraint, therefore the
+/// relation is sound also when `old` or the elements of `list` are not yet known.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::replaceo;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| {
+///         replaceo(2, 9, [1, 2, 2, 3], q)
+///     });
+///     assert!(query.run().next().unwrap().q == lterm!([1, 9, 9, 3]));
+/// }
+/// ```
+pub fn replaceo<U, E, G>(
+    old: LTerm<U, E>,
+    new: LTerm<U, E>,
+    list: LTerm<U, E>,
+    out: LTerm<U, E>,
+) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan_closure!(
+        match [list, out] {
+            [[], []] => ,
+            [[x | xs], [y | ys]] => {
+                cond {
+                    [x == old, y == new],
+                    [x != old, y == x],
+                },
+                replaceo(old, new, xs, ys),
+            }
+        }
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::replaceo;
+    use crate::operator::dfs;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_replaceo_1() {
+        let query = proto_vulcan_query!(|q| { replaceo(2, 9, [1, 2, 2, 3], q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([1, 9, 9, 3]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_replaceo_2() {
+        // Elements that are not yet known are either replaced or constrained to differ
+        let query = proto_vulcan_query!(|x, q| { replaceo(2, 9, [1, x], q) });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.x, 2);
+        assert_eq!(result.q, lterm!([1, 9]));
+        let result = iter.next().unwrap();
+        assert!(result.x.is_any_except(&2));
+        assert_eq!(result.q, lterm!([1, result.x]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_replaceo_3() {
+        // The replaced value can be searched for
+        let query = proto_vulcan_query!(|old| { replaceo(old, 9, [1, 2, 2, 3], [1, 9, 9, 3]) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().old, 2);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_replaceo_dfs() {
+        let query = proto_vulcan_query!(|q| {
+            dfs {
+                replaceo(2, 9, [1, 2, 3], q),
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([1, 9, 3]));
+        assert!(iter.next().is_none());
+    }
+}
@@ -55,4 +55,10 @@ mod test {
         let query = proto_vulcan_query!(|q| { rest([1, [2, 3]], q) });
         assert!(query.run().next().unwrap().q == lterm!([[2, 3]]));
     }
+
+    #[test]
+    fn test_rest_5() {
+        let query = proto_vulcan_query!(|q| { rest(1, q) });
+        assert!(query.run().next().is_none());
+    }
 }
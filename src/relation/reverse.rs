@@ -0,0 +1,99 @@
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::user::User;
+
+/// A relation where `r` is the list `l` reversed.
+///
+/// The elements of `l` are moved one at a time onto an accumulator, and `r` is consumed in
+/// step with `l`, therefore the search is finite when either one of the lists has known
+/// length. When neither of the lengths is known, the lists are enumerated with increasing
+/// length.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::reverseo;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| { reverseo(q, [3, 2, 1]) });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().q, lterm!([1, 2, 3]));
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn reverseo<U, E, G>(l: LTerm<U, E>, r: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    let steps = r.clone();
+    reverse_acco(l, LTerm::empty_list(), r, steps)
+}
+
+/// `r` is the reverse of `l` appended with `acc`. Each element of `l` consumes one element of
+/// `steps`, which bounds the length of `l` by the length of `r`.
+fn reverse_acco<U, E, G>(
+    l: LTerm<U, E>,
+    acc: LTerm<U, E>,
+    r: LTerm<U, E>,
+    steps: LTerm<U, E>,
+) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan_closure!(match [l, steps] {
+        [[], []] => acc == r,
+        [[x | rest], [_ | steps_rest]] => reverse_acco(rest, [x | acc], r, steps_rest),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::reverseo;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_reverseo_1() {
+        let query = proto_vulcan_query!(|q| { reverseo([1, 2, 3], q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([3, 2, 1]));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| { reverseo(q, [3, 2, 1]) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([1, 2, 3]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_reverseo_2() {
+        // Partially instantiated lists
+        let query = proto_vulcan_query!(|x, y| { reverseo([1, x, 3], [y, 2, 1]) });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.x, 2);
+        assert_eq!(result.y, 3);
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| { reverseo([], q) });
+        assert_eq!(query.run().next().unwrap().q, lterm!([]));
+
+        let query = proto_vulcan_query!(|q| { reverseo([1, 2], [1, 2]) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_reverseo_3() {
+        // Both lists fresh
+        let query = proto_vulcan_query!(|l, r| { reverseo(l, r) });
+        let mut iter = query.run();
+        let lengths: Vec<usize> = (0..3)
+            .map(|_| iter.next().unwrap().l.iter().count())
+            .collect();
+        assert_eq!(lengths, vec![0, 1, 2]);
+    }
+}
@@ -0,0 +1,153 @@
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::relation::plusz;
+use crate::state::ConstraintError;
+use crate::stream::Stream;
+use crate::user::User;
+
+/// A relation where list `l` does not begin with `x`.
+fn not_headed<U, E, G>(x: LTerm<U, E>, l: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan!(
+        match l {
+            [] => ,
+            [y | _] => y != x,
+        }
+    )
+}
+
+/// Fails if `n` is a number less than one. A count that is not yet known is accepted.
+fn positive<U, E, G>(n: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan!(fngoal move |solver, state| {
+        match state.smap_ref().walk(&n).get_number() {
+            Some(count) if count < 1 => solver.fail(ConstraintError::new()),
+            _ => Stream::unit(Box::new(state)),
+        }
+    })
+}
+
+/// A relation where `list` begins with a run of exactly `n` elements equal to `x`, followed by
+/// `tail`.
+fn run<U, E, G>(
+    x: LTerm<U, E>,
+    n: LTerm<U, E>,
+    list: LTerm<U, E>,
+    tail: LTerm<U, E>,
+) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan_closure!(
+        match [list, n] {
+            [[y | rest], 1] => {
+                y == x,
+                rest == tail,
+                not_headed(x, tail),
+            },
+            [[y | rest], _] => |m| {
+                positive(n),
+                y == x,
+                n != 1,
+                plusz(m, 1, n),
+                run(x, m, rest, tail),
+            }
+        }
+    )
+}
+
+/// A relation where `encoded` is the run-length encoding of `list`, as a list of
+/// `[value, count]`-pairs.
+///
+/// Adjacent runs in `encoded` have distinct values, and the counts are positive. The relation
+/// can be used both for encoding and decoding.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::rleo;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| {
+///         rleo(['a', 'a', 'b'], q)
+///     });
+///     assert!(query.run().next().unwrap().q == lterm!([['a', 2], ['b', 1]]));
+/// }
+/// ```
+pub fn rleo<U, E, G>(list: LTerm<U, E>, encoded: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan_closure!(
+        match encoded {
+            [] => list == [],
+            [[x, n] | rest] => |tail| {
+                run(x, n, list, tail),
+                rleo(tail, rest),
+            }
+        }
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::rleo;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_rleo_1() {
+        let query = proto_vulcan_query!(|q| { rleo(['a', 'a', 'b'], q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([['a', 2], ['b', 1]]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_rleo_2() {
+        let query = proto_vulcan_query!(|q| { rleo(q, [[1, 3], [2, 1], [1, 2]]) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([1, 1, 1, 2, 1, 1]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_rleo_3() {
+        let query = proto_vulcan_query!(|q| { rleo([], q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_rleo_4() {
+        // Adjacent runs of the same value are not a valid encoding
+        let query = proto_vulcan_query!(|q| { rleo(q, [[1, 1], [1, 1]]) });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|q| { rleo([1, 1], [[1, 1], [1, 1]]) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_rleo_5() {
+        // Counts must be positive
+        let query = proto_vulcan_query!(|q| { rleo(q, [[1, 0]]) });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|q| { rleo(q, [[1, 2], [2, -1]]) });
+        assert!(query.run().next().is_none());
+    }
+}
@@ -0,0 +1,111 @@
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::relation::append;
+use crate::relation::plusz;
+use crate::user::User;
+
+/// A relation where `n` is the length of list `l`.
+fn prefix_length<U, E, G>(l: LTerm<U, E>, n: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan_closure!(
+        match l {
+            [] => n == 0,
+            [_ | rest] => |m| {
+                plusz(m, 1, n),
+                prefix_length(rest, m),
+            }
+        }
+    )
+}
+
+/// A relation where `rotated` is `list` rotated left by `n` positions, with `0 <= n < len`.
+///
+/// The rotation is found by splitting the `list` at position `n` with `append`, and then
+/// appending the two parts in reverse order. If `n` is fresh, all rotations are enumerated.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::rotateo;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| {
+///         rotateo(1, [1, 2, 3], q)
+///     });
+///     assert!(query.run().next().unwrap().q == lterm!([2, 3, 1]));
+/// }
+/// ```
+pub fn rotateo<U, E, G>(
+    n: LTerm<U, E>,
+    list: LTerm<U, E>,
+    rotated: LTerm<U, E>,
+) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan!(
+        match list {
+            [] => {
+                n == 0,
+                rotated == [],
+            },
+            [_ | _] => |front, back| {
+                append(front, back, list),
+                back == [_ | _],
+                append(back, front, rotated),
+                prefix_length(front, n),
+            }
+        }
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::rotateo;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_rotateo_1() {
+        let query = proto_vulcan_query!(|q| { rotateo(1, [1, 2, 3], q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([2, 3, 1]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_rotateo_2() {
+        let query = proto_vulcan_query!(|n, q| { rotateo(n, [1, 2, 3], q) });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.n, 0);
+        assert_eq!(result.q, lterm!([1, 2, 3]));
+        let result = iter.next().unwrap();
+        assert_eq!(result.n, 1);
+        assert_eq!(result.q, lterm!([2, 3, 1]));
+        let result = iter.next().unwrap();
+        assert_eq!(result.n, 2);
+        assert_eq!(result.q, lterm!([3, 1, 2]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_rotateo_3() {
+        let query = proto_vulcan_query!(|q| { rotateo(3, [1, 2, 3], q) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_rotateo_4() {
+        let query = proto_vulcan_query!(|n| { rotateo(n, [1, 2, 3], [3, 1, 2]) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().n, 2);
+        assert!(iter.next().is_none());
+    }
+}
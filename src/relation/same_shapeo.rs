@@ -0,0 +1,275 @@
+use crate::compound::CompoundObject;
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::{LTerm, LTermInner};
+use crate::solver::{Solve, Solver};
+use crate::state::{Constraint, ConstraintError, SResult, State};
+use crate::stream::Stream;
+use crate::user::User;
+use std::rc::Rc;
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct SameShape<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    a: LTerm<U, E>,
+    b: LTerm<U, E>,
+}
+
+impl<U, E> SameShape<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new<G: AnyGoal<U, E>>(a: LTerm<U, E>, b: LTerm<U, E>) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(SameShape { a, b })))
+    }
+}
+
+fn same_shape<U, E>(state: State<U, E>, u: &LTerm<U, E>, v: &LTerm<U, E>) -> SResult<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    let uwalk = state.smap_ref().walk(u).clone();
+    let vwalk = state.smap_ref().walk(v).clone();
+    if uwalk == vwalk {
+        return Ok(state);
+    }
+
+    match (uwalk.as_ref(), vwalk.as_ref()) {
+        (LTermInner::Var(_, _), LTermInner::Empty) => state.unify(&uwalk, &vwalk),
+        (LTermInner::Empty, LTermInner::Var(_, _)) => state.unify(&vwalk, &uwalk),
+        (LTermInner::Var(_, _), LTermInner::Cons(vhead, vtail)) => {
+            // The variable must be a list cell, and its head and tail have the shapes of
+            // the head and tail of the other list.
+            let (head, tail) = (LTerm::any(), LTerm::any());
+            let state = state.unify(&uwalk, &LTerm::cons(head.clone(), tail.clone()))?;
+            let state = same_shape(state, &head, vhead)?;
+            same_shape(state, &tail, vtail)
+        }
+        (LTermInner::Cons(_, _), LTermInner::Var(_, _)) => same_shape(state, &vwalk, &uwalk),
+        (LTermInner::Var(_, _), _) | (_, LTermInner::Var(_, _)) => {
+            // The shape of the variable is not known until it is instantiated.
+            Ok(state.with_constraint(SameShapeConstraint::new(uwalk, vwalk)))
+        }
+        (LTermInner::Empty, LTermInner::Empty) => Ok(state),
+        (LTermInner::Cons(uhead, utail), LTermInner::Cons(vhead, vtail)) => {
+            let state = same_shape(state, uhead, vhead)?;
+            same_shape(state, utail, vtail)
+        }
+        (LTermInner::Compound(ucf), LTermInner::Compound(vcf)) => {
+            same_shape_compound(state, ucf.as_ref(), vcf.as_ref())
+        }
+        (LTermInner::Empty, _)
+        | (_, LTermInner::Empty)
+        | (LTermInner::Cons(_, _), _)
+        | (_, LTermInner::Cons(_, _))
+        | (LTermInner::Compound(_), _)
+        | (_, LTermInner::Compound(_)) => Err(ConstraintError::new()),
+        // Both are leaves
+        _ => Ok(state),
+    }
+}
+
+fn same_shape_compound<U, E>(
+    mut state: State<U, E>,
+    ucompound: &dyn CompoundObject<U, E>,
+    vcompound: &dyn CompoundObject<U, E>,
+) -> SResult<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    if ucompound.type_id() != vcompound.type_id() {
+        return Err(ConstraintError::new());
+    }
+
+    let mut uchildren = ucompound.children();
+    let mut vchildren = vcompound.children();
+    loop {
+        match (uchildren.next(), vchildren.next()) {
+            (Some(uchild), Some(vchild)) => match (uchild.as_term(), vchild.as_term()) {
+                (Some(uterm), Some(vterm)) => state = same_shape(state, uterm, vterm)?,
+                (None, None) => state = same_shape_compound(state, uchild, vchild)?,
+                _ => return Err(ConstraintError::new()),
+            },
+            (None, None) => return Ok(state),
+            _ => return Err(ConstraintError::new()),
+        }
+    }
+}
+
+impl<U, E> Solve<U, E> for SameShape<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        match state.run_constraint(SameShapeConstraint::new(self.a.clone(), self.b.clone())) {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(error) => solver.fail(error),
+        }
+    }
+}
+
+/// A relation where terms `a` and `b` have identical list and compound structure, regardless
+/// of the values of their leaves.
+///
+/// For example `[1, [2, 3]]` and `[x, [y, z]]` have the same shape, but `[1, 2]` does not.
+/// Compound terms have the same shape if they are of the same type and their children have
+/// the same shape. If one of the terms is a fresh variable and the other a list, the variable
+/// is bound to a list of the same shape with fresh variables as leaves. Where a variable is
+/// compared with a leaf, a compound term or another variable, the comparison is kept as a
+/// constraint until the variable is instantiated.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::same_shapeo;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| {
+///         same_shapeo([1, [2, 3]], q)
+///     });
+///     let result = query.run().next().unwrap();
+///     assert_eq!(result.q.iter().count(), 2);
+///     assert_eq!(result.q.tail().unwrap().head().unwrap().iter().count(), 2);
+/// }
+/// ```
+pub fn same_shapeo<U, E, G>(a: LTerm<U, E>, b: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    SameShape::new(a, b)
+}
+
+/// Same-shape constraint
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct SameShapeConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    a: LTerm<U, E>,
+    b: LTerm<U, E>,
+}
+
+impl<U, E> SameShapeConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new(a: LTerm<U, E>, b: LTerm<U, E>) -> Rc<dyn Constraint<U, E>> {
+        Rc::new(SameShapeConstraint { a, b })
+    }
+}
+
+impl<U, E> Constraint<U, E> for SameShapeConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn run(self: Rc<Self>, state: State<U, E>) -> SResult<U, E> {
+        same_shape(state, &self.a, &self.b)
+    }
+
+    fn operands(&self) -> Vec<LTerm<U, E>> {
+        vec![self.a.clone(), self.b.clone()]
+    }
+}
+
+impl<U, E> std::fmt::Display for SameShapeConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "same_shapeo({}, {})", self.a, self.b)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::same_shapeo;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_same_shapeo_1() {
+        let query = proto_vulcan_query!(|q| {
+            |x, y, z| {
+                same_shapeo([1, [2, 3]], [x, [y, z]]),
+                q == true,
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, true);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_same_shapeo_2() {
+        let query = proto_vulcan_query!(|x, y, z| { same_shapeo([1, 2], [x, [y, z]]) });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|q| { same_shapeo([1, 2], [1, 2, 3]) });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|q| { same_shapeo([], 1) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_same_shapeo_3() {
+        // A fresh variable gets the shape of the other term
+        let query = proto_vulcan_query!(|q| {
+            same_shapeo(q, [1, [2, 3]]),
+            q == [4, [5, 6]],
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([4, [5, 6]]));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| {
+            same_shapeo(q, [1, [2, 3]]),
+            q == [4, 5],
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_same_shapeo_4() {
+        // A variable compared with a leaf is checked when it is instantiated
+        let query = proto_vulcan_query!(|x| {
+            same_shapeo(x, 1),
+            x == [1],
+        });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|x| {
+            same_shapeo(x, 1),
+            x == 2,
+        });
+        assert_eq!(query.run().next().unwrap().x, 2);
+
+        let query = proto_vulcan_query!(|x, y| {
+            same_shapeo([x], [y]),
+            x == [1, 2],
+            y == [3],
+        });
+        assert!(query.run().next().is_none());
+
+        let query = proto_vulcan_query!(|x, y| {
+            same_shapeo([x], [y]),
+            x == [1, 2],
+            y == [3, 4],
+        });
+        assert_eq!(query.run().count(), 1);
+    }
+}
@@ -0,0 +1,82 @@
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::relation::append;
+use crate::user::User;
+
+/// A relation where `a` and `b` are a split of list `l`, such that `l` is `b` appended to `a`.
+///
+/// This is `append(a, b, l)` with the arguments ordered for splitting. When `l` is a proper
+/// list, the splits are enumerated from the shortest prefix `a` to the longest, and there are
+/// `len(l) + 1` of them. The relation also runs forwards: when `a` is a proper list, `l` is
+/// the concatenation of `a` and `b`. If neither `l` nor `a` is a proper list, the number of
+/// solutions is infinite.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::splito;
+/// fn main() {
+///     let query = proto_vulcan_query!(|a, b| { splito([1, 2], a, b) });
+///     let mut iter = query.run();
+///     let result = iter.next().unwrap();
+///     assert_eq!(result.a, lterm!([]));
+///     assert_eq!(result.b, lterm!([1, 2]));
+///     let result = iter.next().unwrap();
+///     assert_eq!(result.a, lterm!([1]));
+///     assert_eq!(result.b, lterm!([2]));
+///     let result = iter.next().unwrap();
+///     assert_eq!(result.a, lterm!([1, 2]));
+///     assert_eq!(result.b, lterm!([]));
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn splito<U, E, G>(l: LTerm<U, E>, a: LTerm<U, E>, b: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan!(append(a, b, l))
+}
+
+#[cfg(test)]
+mod test {
+    use super::splito;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_splito_1() {
+        let query = proto_vulcan_query!(|a, b| { splito([1, 2, 3], a, b) });
+        let splits: Vec<String> = query.run().map(|r| format!("{} {}", r.a, r.b)).collect();
+        assert_eq!(
+            splits,
+            vec!["[] [1, 2, 3]", "[1] [2, 3]", "[1, 2] [3]", "[1, 2, 3] []"]
+        );
+
+        let query = proto_vulcan_query!(|a, b| { splito([], a, b) });
+        let mut iter = query.run();
+        let result = iter.next().unwrap();
+        assert_eq!(result.a, lterm!([]));
+        assert_eq!(result.b, lterm!([]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_splito_2() {
+        // Forwards, the parts are concatenated
+        let query = proto_vulcan_query!(|q| { splito(q, [1, 2], [3]) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([1, 2, 3]));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| { splito([1, 2, 3], [1], q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([2, 3]));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| { splito([1, 2, 3], [2], q) });
+        assert!(query.run().next().is_none());
+    }
+}
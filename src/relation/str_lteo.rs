@@ -0,0 +1,277 @@
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::{LTerm, LTermInner};
+use crate::lvalue::LValue;
+use crate::solver::{Solve, Solver};
+use crate::state::{Constraint, ConstraintError, SResult, State};
+use crate::stream::Stream;
+use crate::user::User;
+use std::rc::Rc;
+
+/// Kinds of values that can be ordered with `LexicalLteConstraint`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum OrderKind {
+    /// Lexicographic ordering of `LValue::String`
+    String,
+    /// Code-point ordering of `LValue::Char`
+    Char,
+}
+
+impl std::fmt::Display for OrderKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            OrderKind::String => write!(f, "str_lteo"),
+            OrderKind::Char => write!(f, "char_lteo"),
+        }
+    }
+}
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct LexicalLte<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    u: LTerm<U, E>,
+    v: LTerm<U, E>,
+    kind: OrderKind,
+}
+
+impl<U, E> LexicalLte<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn new<G: AnyGoal<U, E>>(
+        u: LTerm<U, E>,
+        v: LTerm<U, E>,
+        kind: OrderKind,
+    ) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(LexicalLte { u, v, kind })))
+    }
+}
+
+impl<U, E> Solve<U, E> for LexicalLte<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        match state.run_constraint(LexicalLteConstraint::new(
+            self.u.clone(),
+            self.v.clone(),
+            self.kind,
+        )) {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(error) => solver.fail(error),
+        }
+    }
+}
+
+/// A relation where strings `u` and `v` are in lexicographic order, `u <= v`.
+///
+/// The relation fails if either of the terms is bound to something else than a string. If
+/// either of the terms is not yet known, the check is deferred until both are bound.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::str_lteo;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| {
+///         str_lteo(q, "banana"),
+///         conde {
+///             q == "cherry",
+///             q == "apple",
+///         }
+///     });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().q, "apple");
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn str_lteo<U, E, G>(u: LTerm<U, E>, v: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    LexicalLte::new(u, v, OrderKind::String)
+}
+
+/// A relation where characters `u` and `v` are in code-point order, `u <= v`.
+///
+/// The relation fails if either of the terms is bound to something else than a character.
+/// If either of the terms is not yet known, the check is deferred until both are bound.
+pub fn char_lteo<U, E, G>(u: LTerm<U, E>, v: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    LexicalLte::new(u, v, OrderKind::Char)
+}
+
+/// Lexical ordering constraint
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct LexicalLteConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    u: LTerm<U, E>,
+    v: LTerm<U, E>,
+    kind: OrderKind,
+}
+
+impl<U, E> LexicalLteConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn new(u: LTerm<U, E>, v: LTerm<U, E>, kind: OrderKind) -> Rc<dyn Constraint<U, E>> {
+        Rc::new(LexicalLteConstraint { u, v, kind })
+    }
+
+    /// Checks that the walked term is a variable or a value of the ordered kind.
+    fn is_valid(&self, u: &LTerm<U, E>) -> bool {
+        matches!(
+            (self.kind, u.as_ref()),
+            (_, LTermInner::Var(_, _))
+                | (OrderKind::String, LTermInner::Val(LValue::String(_)))
+                | (OrderKind::Char, LTermInner::Val(LValue::Char(_)))
+        )
+    }
+}
+
+impl<U, E> Constraint<U, E> for LexicalLteConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn run(self: Rc<Self>, state: State<U, E>) -> SResult<U, E> {
+        let uwalk = state.smap_ref().walk(&self.u).clone();
+        let vwalk = state.smap_ref().walk(&self.v).clone();
+
+        if !self.is_valid(&uwalk) || !self.is_valid(&vwalk) {
+            return Err(ConstraintError::new());
+        }
+
+        match (uwalk.as_ref(), vwalk.as_ref()) {
+            (LTermInner::Val(LValue::String(u)), LTermInner::Val(LValue::String(v))) => {
+                if u <= v {
+                    Ok(state)
+                } else {
+                    Err(ConstraintError::new())
+                }
+            }
+            (LTermInner::Val(LValue::Char(u)), LTermInner::Val(LValue::Char(v))) => {
+                if u <= v {
+                    Ok(state)
+                } else {
+                    Err(ConstraintError::new())
+                }
+            }
+            _ => {
+                // At least one of the terms is not yet known, keep the constraint for later.
+                Ok(state.with_constraint(self))
+            }
+        }
+    }
+
+    fn operands(&self) -> Vec<LTerm<U, E>> {
+        vec![self.u.clone(), self.v.clone()]
+    }
+}
+
+impl<U, E> std::fmt::Display for LexicalLteConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}({}, {})", self.kind, self.u, self.v)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{char_lteo, str_lteo};
+    use crate::prelude::*;
+
+    #[test]
+    fn test_str_lteo_1() {
+        let query = proto_vulcan_query!(|q| {
+            str_lteo("apple", "banana"),
+            q == true,
+        });
+        assert_eq!(query.run().next().unwrap().q, true);
+
+        let query = proto_vulcan_query!(|q| {
+            str_lteo("banana", "apple"),
+            q == true,
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_str_lteo_2() {
+        // Ordering is deferred until both terms are known
+        let query = proto_vulcan_query!(|x, y| {
+            str_lteo(x, y),
+            x == "banana",
+            conde {
+                y == "apple",
+                y == "banana",
+                y == "cherry",
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().y, "banana");
+        assert_eq!(iter.next().unwrap().y, "cherry");
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_str_lteo_3() {
+        // Terms that are not strings fail
+        let query = proto_vulcan_query!(|q| {
+            str_lteo(q, "apple"),
+            q == 'a',
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_char_lteo_1() {
+        let query = proto_vulcan_query!(|q| {
+            char_lteo('a', 'b'),
+            q == true,
+        });
+        assert_eq!(query.run().next().unwrap().q, true);
+
+        let query = proto_vulcan_query!(|q| {
+            char_lteo('b', 'a'),
+            q == true,
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_char_lteo_2() {
+        let query = proto_vulcan_query!(|q| {
+            char_lteo(q, 'b'),
+            conde {
+                q == 'c',
+                q == 'a',
+                q == "a",
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, 'a');
+        assert!(iter.next().is_none());
+    }
+}
@@ -0,0 +1,77 @@
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::LTerm;
+use crate::relation::length::decrementfd;
+use crate::user::User;
+
+/// A relation where `prefix` is the list of the first `n` elements of list `l`.
+///
+/// The count `n` is a finite-domain number, and `l` must have at least `n` elements. When `n`
+/// is fresh, the prefixes of `l` are enumerated from the shortest.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::takeo;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| { takeo(2, [1, 2, 3, 4], q) });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().q, lterm!([1, 2]));
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn takeo<U, E, G>(n: LTerm<U, E>, l: LTerm<U, E>, prefix: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    proto_vulcan_closure!(
+        match [n, l, prefix] {
+            [0, _, []] => ,
+            [_, [x | rest], [x | p]] => |m| {
+                decrementfd(n, m),
+                takeo(m, rest, p),
+            }
+        }
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::takeo;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_takeo_1() {
+        let query = proto_vulcan_query!(|q| { takeo(2, [1, 2, 3, 4], q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([1, 2]));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| { takeo(0, [1, 2], q) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([]));
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|q| { takeo(3, [1, 2], q) });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_takeo_2() {
+        // The count is found from the prefix, and all prefixes are enumerated
+        let query = proto_vulcan_query!(|q| { takeo(q, [1, 2, 3], [1, 2]) });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, 2);
+        assert!(iter.next().is_none());
+
+        let query = proto_vulcan_query!(|n, q| { takeo(n, [1, 2], q) });
+        let results: Vec<String> = query
+            .run()
+            .map(|result| format!("{} {}", result.n, result.q))
+            .collect();
+        assert_eq!(results, vec!["0 []", "1 [1]", "2 [1, 2]"]);
+    }
+}
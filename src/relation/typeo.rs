@@ -0,0 +1,334 @@
+use crate::engine::Engine;
+use crate::goal::{AnyGoal, InferredGoal};
+use crate::lterm::{LTerm, LTermInner};
+use crate::lvalue::LValue;
+use crate::solver::{Solve, Solver};
+use crate::state::{Constraint, ConstraintError, SResult, State};
+use crate::stream::Stream;
+use crate::user::User;
+use std::rc::Rc;
+
+/// Kinds of values that can be required with type constraints.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ValueKind {
+    /// `LValue::Number`, `LValue::Float` or `LValue::BigInt`
+    Number,
+    /// `LValue::String`
+    String,
+    /// `LValue::String` or `LValue::Char`
+    Symbol,
+    /// Any `LValue`
+    Value,
+}
+
+impl ValueKind {
+    /// Returns true if the value is of this kind.
+    pub fn contains(&self, value: &LValue) -> bool {
+        match (self, value) {
+            (ValueKind::Value, _)
+            | (ValueKind::Number, LValue::Number(_))
+            | (ValueKind::Number, LValue::Float(_))
+            | (ValueKind::String, LValue::String(_))
+            | (ValueKind::Symbol, LValue::String(_))
+            | (ValueKind::Symbol, LValue::Char(_)) => true,
+            #[cfg(feature = "bigint")]
+            (ValueKind::Number, LValue::BigInt(_)) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns true if there are values that are of both kinds.
+    pub fn is_compatible(&self, other: &ValueKind) -> bool {
+        match (self, other) {
+            (ValueKind::Value, _) | (_, ValueKind::Value) => true,
+            (ValueKind::Number, ValueKind::Number) => true,
+            (ValueKind::Number, _) | (_, ValueKind::Number) => false,
+            _ => true,
+        }
+    }
+}
+
+impl std::fmt::Display for ValueKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ValueKind::Number => write!(f, "numbero"),
+            ValueKind::String => write!(f, "stringo"),
+            ValueKind::Symbol => write!(f, "symbolo"),
+            ValueKind::Value => write!(f, "valueo"),
+        }
+    }
+}
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct Typeo<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    u: LTerm<U, E>,
+    kind: ValueKind,
+}
+
+impl<U, E> Typeo<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new<G: AnyGoal<U, E>>(u: LTerm<U, E>, kind: ValueKind) -> InferredGoal<U, E, G> {
+        InferredGoal::new(G::dynamic(Rc::new(Typeo { u, kind })))
+    }
+}
+
+impl<U, E> Solve<U, E> for Typeo<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn solve(&self, solver: &Solver<U, E>, state: State<U, E>) -> Stream<U, E> {
+        match state.run_constraint(TypeConstraint::new(self.u.clone(), self.kind)) {
+            Ok(state) => Stream::unit(Box::new(state)),
+            Err(error) => solver.fail(error),
+        }
+    }
+}
+
+/// A relation that constrains `u` to be a number: an integer, a floating-point number or, with
+/// the `bigint` feature, an arbitrary-precision integer.
+///
+/// Unlike an immediate type check, the constraint is kept until `u` is bound, and it rejects
+/// later bindings to non-numbers.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::numbero;
+/// fn main() {
+///     let query = proto_vulcan_query!(|q| {
+///         numbero(q),
+///         conde {
+///             q == "one",
+///             q == 1,
+///         }
+///     });
+///     let mut iter = query.run();
+///     assert_eq!(iter.next().unwrap().q, 1);
+///     assert!(iter.next().is_none());
+/// }
+/// ```
+pub fn numbero<U, E, G>(u: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    Typeo::new(u, ValueKind::Number)
+}
+
+/// A relation that constrains `u` to be a string.
+pub fn stringo<U, E, G>(u: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    Typeo::new(u, ValueKind::String)
+}
+
+/// A relation that constrains `u` to be a symbol. Symbols are represented with strings
+/// and characters.
+pub fn symbolo<U, E, G>(u: LTerm<U, E>) -> InferredGoal<U, E, G>
+where
+    U: User,
+    E: Engine<U>,
+    G: AnyGoal<U, E>,
+{
+    Typeo::new(u, ValueKind::Symbol)
+}
+
+/// Type constraint
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"))]
+pub struct TypeConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    u: LTerm<U, E>,
+    kind: ValueKind,
+}
+
+impl<U, E> TypeConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new(u: LTerm<U, E>, kind: ValueKind) -> Rc<dyn Constraint<U, E>> {
+        Rc::new(TypeConstraint { u, kind })
+    }
+}
+
+impl<U, E> Constraint<U, E> for TypeConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn run(self: Rc<Self>, state: State<U, E>) -> SResult<U, E> {
+        let uwalk = state.smap_ref().walk(&self.u).clone();
+        match uwalk.as_ref() {
+            LTermInner::Var(_, _) => {
+                // The variable is not yet bound. Fail if some other type constraint on the
+                // same variable cannot be fulfilled at the same time.
+                for c in state.cstore_ref().iter() {
+                    if let Some(other) = c.downcast_ref::<TypeConstraint<U, E>>() {
+                        if state.smap_ref().walk(&other.u) == &uwalk
+                            && !other.kind.is_compatible(&self.kind)
+                        {
+                            return Err(ConstraintError::new());
+                        }
+                    }
+                }
+                Ok(state.with_constraint(self))
+            }
+            LTermInner::Val(value) if self.kind.contains(value) => Ok(state),
+            _ => Err(ConstraintError::new()),
+        }
+    }
+
+    fn operands(&self) -> Vec<LTerm<U, E>> {
+        vec![self.u.clone()]
+    }
+}
+
+impl<U, E> std::fmt::Display for TypeConstraint<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}({})", self.kind, self.u)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{numbero, stringo, symbolo};
+    use crate::prelude::*;
+
+    #[test]
+    fn test_numbero_1() {
+        let query = proto_vulcan_query!(|q| {
+            numbero(q),
+            q == "one",
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_numbero_2() {
+        let query = proto_vulcan_query!(|q| {
+            |x| {
+                numbero(x),
+                q == [x],
+                q == [5],
+            }
+        });
+        let mut iter = query.run();
+        assert_eq!(iter.next().unwrap().q, lterm!([5]));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_numbero_3() {
+        let query = proto_vulcan_query!(|q| {
+            numbero(q),
+            q == [1],
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_numbero_4() {
+        let query = proto_vulcan_query!(|q| {
+            numbero(q),
+            q == 1.5,
+        });
+        assert_eq!(query.run().next().unwrap().q, lterm!(1.5));
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_numbero_bigint() {
+        let n: num_bigint::BigInt = "1234567890123456789012345678901234567890".parse().unwrap();
+        let u: LTerm = LTerm::from(n.clone());
+        let query = proto_vulcan_query!(|q| {
+            numbero(q),
+            q == u,
+        });
+        assert_eq!(query.run().next().unwrap().q.get_bigint(), Some(&n));
+    }
+
+    #[test]
+    fn test_stringo_1() {
+        let query = proto_vulcan_query!(|q| {
+            |x| {
+                stringo(x),
+                x == q,
+                q == 1,
+            }
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_stringo_2() {
+        let query = proto_vulcan_query!(|q| {
+            stringo(q),
+            q == "abc",
+        });
+        assert_eq!(query.run().next().unwrap().q, "abc");
+    }
+
+    #[test]
+    fn test_symbolo_1() {
+        let query = proto_vulcan_query!(|q| {
+            symbolo(q),
+            conde {
+                q == 'a',
+                q == "b",
+                q == 1,
+                q == true,
+            }
+        });
+        let iter = query.run();
+        let mut expected = vec![lterm!('a'), lterm!("b")];
+        iter.for_each(|x| {
+            let n = x.q.clone();
+            assert!(expected.contains(&n));
+            expected.retain(|y| &n != y);
+        });
+        assert_eq!(expected.len(), 0);
+    }
+
+    #[test]
+    fn test_typeo_1() {
+        let query = proto_vulcan_query!(|q| {
+            numbero(q),
+            symbolo(q),
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[test]
+    fn test_typeo_2() {
+        let query = proto_vulcan_query!(|q| {
+            |x| {
+                numbero(q),
+                stringo(x),
+                x == q,
+            }
+        });
+        assert!(query.run().next().is_none());
+    }
+}
@@ -1,14 +1,41 @@
 use crate::engine::Engine;
 use crate::goal::{DFSGoal, Goal};
-use crate::state::State;
-use crate::stream::{LazyStream, Stream};
+use crate::state::{ConstraintError, State};
+use crate::stream::{Lazy, LazyStream, Stream};
 use crate::user::User;
 use std::any::{Any, TypeId};
+use std::cell::{Cell, RefCell};
 use std::fmt;
+use std::rc::Rc;
 
 #[cfg(feature = "debugger")]
 use crate::debugger::Debugger;
 
+/// Statistics of the engine steps taken by a solver.
+///
+/// The statistics are shared with the solver, and they are updated as the search proceeds.
+#[derive(Clone, Debug, Default)]
+pub struct StepStats {
+    steps: Rc<Cell<usize>>,
+    limit_reached: Rc<Cell<bool>>,
+}
+
+impl StepStats {
+    /// Number of `Engine::step` invocations so far.
+    pub fn steps(&self) -> usize {
+        self.steps.get()
+    }
+
+    /// True if the search was stopped because the step limit was reached.
+    pub fn limit_reached(&self) -> bool {
+        self.limit_reached.get()
+    }
+}
+
+/// Callback that is called on every engine step with the lazy stream node being stepped,
+/// and the nesting depth of the step.
+pub type TraceFn<U, E> = Box<dyn FnMut(&Lazy<U, E>, usize)>;
+
 pub struct Solver<U, E>
 where
     U: User,
@@ -19,6 +46,12 @@ where
     #[cfg(feature = "debugger")]
     debugger: Debugger<U, E>,
     debug_enabled: bool,
+    record_failures: bool,
+    failures: RefCell<Vec<ConstraintError<U, E>>>,
+    step_limit: Option<usize>,
+    step_stats: StepStats,
+    trace: Option<RefCell<TraceFn<U, E>>>,
+    depth: Cell<usize>,
 }
 
 impl<U, E> Solver<U, E>
@@ -36,6 +69,75 @@ where
             #[cfg(feature = "debugger")]
             debugger,
             debug_enabled,
+            record_failures: false,
+            failures: RefCell::new(vec![]),
+            step_limit: None,
+            step_stats: StepStats::default(),
+            trace: None,
+            depth: Cell::new(0),
+        }
+    }
+
+    /// Enables or disables recording of the reasons of failed branches.
+    pub fn set_record_failures(&mut self, enabled: bool) {
+        self.record_failures = enabled;
+    }
+
+    /// Returns an empty stream for a branch that failed with `error`. If recording of
+    /// failures is enabled, the error is recorded.
+    pub fn fail(&self, error: ConstraintError<U, E>) -> Stream<U, E> {
+        if self.record_failures {
+            self.failures.borrow_mut().push(error);
+        }
+        Stream::empty()
+    }
+
+    /// Takes the recorded reasons of failed branches.
+    pub fn take_failures(&self) -> Vec<ConstraintError<U, E>> {
+        self.failures.replace(vec![])
+    }
+
+    /// Sets the maximum number of `Engine::step` invocations. When the limit is reached, the
+    /// stream being advanced is cut short as if it had no more solutions.
+    pub fn set_step_limit(&mut self, step_limit: Option<usize>) {
+        self.step_limit = step_limit;
+    }
+
+    /// Returns the statistics of the engine steps taken by the solver.
+    pub fn step_stats(&self) -> StepStats {
+        self.step_stats.clone()
+    }
+
+    /// Sets a callback that is called before every `Engine::step` with the lazy stream node
+    /// being stepped, and the nesting depth of the step. The depth is zero for the steps of
+    /// the search itself, and greater for the steps of the sub-searches that are run by goals
+    /// such as `onceo` and `naf` while a step is in progress.
+    pub fn set_trace(&mut self, trace: Option<TraceFn<U, E>>) {
+        self.trace = trace.map(RefCell::new);
+    }
+
+    // Takes one step with the engine, unless the step limit has been reached.
+    fn step(&self, lazy: Lazy<U, E>) -> Stream<U, E> {
+        let steps = self.step_stats.steps.get();
+        match self.step_limit {
+            Some(step_limit) if steps >= step_limit => {
+                self.step_stats.limit_reached.set(true);
+                Stream::empty()
+            }
+            _ => {
+                self.step_stats.steps.set(steps + 1);
+                match &self.trace {
+                    Some(trace) => {
+                        let depth = self.depth.get();
+                        (trace.borrow_mut())(&lazy, depth);
+                        self.depth.set(depth + 1);
+                        let stream = self.engine.step(self, lazy);
+                        self.depth.set(depth);
+                        stream
+                    }
+                    None => self.engine.step(self, lazy),
+                }
+            }
         }
     }
 
@@ -98,7 +200,7 @@ where
                     }
                     return Some(state);
                 }
-                Stream::Lazy(LazyStream(lazy)) => *stream = self.engine.step(self, *lazy),
+                Stream::Lazy(LazyStream(lazy)) => *stream = self.step(*lazy),
                 Stream::Cons(state, lazy_stream) => {
                     *stream = Stream::Lazy(lazy_stream);
                     #[cfg(feature = "debugger")]
@@ -111,6 +213,19 @@ where
         }
     }
 
+    /// Solves `goal` starting from `initial_state`, and returns the first state in which the
+    /// goal succeeds, or `None` if there is no solution.
+    ///
+    /// The returned state is not reified.
+    pub fn solve_first(
+        &mut self,
+        goal: &Goal<U, E>,
+        initial_state: State<U, E>,
+    ) -> Option<State<U, E>> {
+        let mut stream = self.start(goal, initial_state);
+        self.next(&mut stream).map(|state| *state)
+    }
+
     /// Returns a reference to next element in the stream, if any.
     pub fn peek<'a>(&self, stream: &'a mut Stream<U, E>) -> Option<&'a Box<State<U, E>>> {
         loop {
@@ -118,7 +233,7 @@ where
                 Stream::Lazy(_) => {
                     if let Stream::Lazy(LazyStream(lazy)) = std::mem::replace(stream, Stream::Empty)
                     {
-                        *stream = self.engine.step(self, *lazy);
+                        *stream = self.step(*lazy);
                     }
                 }
                 _ => return stream.head(),
@@ -126,6 +241,15 @@ where
         }
     }
 
+    /// Returns true if the stream produces at least one more solution.
+    ///
+    /// Lazy streams are advanced only until the first solution is found, and the remaining
+    /// stream, including the found solution, is kept in `stream` for later consumption. If the
+    /// stream is infinite without any solutions, this never returns.
+    pub fn is_productive(&self, stream: &mut Stream<U, E>) -> bool {
+        self.peek(stream).is_some()
+    }
+
     /// Truncates the stream leaving at most one element, and returns a reference to
     /// the remaining element if any.
     pub fn trunc<'a>(&self, stream: &'a mut Stream<U, E>) -> Option<&'a Box<State<U, E>>> {
@@ -133,7 +257,7 @@ where
             match std::mem::replace(stream, Stream::Empty) {
                 Stream::Empty => return None,
                 Stream::Lazy(LazyStream(lazy)) => {
-                    *stream = self.engine.step(self, *lazy);
+                    *stream = self.step(*lazy);
                 }
                 Stream::Unit(a) | Stream::Cons(a, _) => {
                     *stream = Stream::Unit(a);
@@ -195,3 +319,61 @@ where
         self.as_any().downcast_ref::<T>()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Solver;
+    use crate::goal::GoalCast;
+    use crate::prelude::*;
+    use crate::relation::{eq, member};
+    use crate::state::State;
+
+    type TestGoal = Goal<DefaultUser, DefaultEngine<DefaultUser>>;
+
+    #[test]
+    fn test_solver_solve_first_1() {
+        let mut solver = Solver::new((), false);
+        let x = LTerm::var("x");
+        let goal: TestGoal = eq(x.clone(), LTerm::from(1)).cast_into();
+        let state = solver
+            .solve_first(&goal, State::new(DefaultUser::new()))
+            .unwrap();
+        assert_eq!(state.smap_ref().walk(&x), &LTerm::from(1));
+    }
+
+    #[test]
+    fn test_solver_solve_first_2() {
+        let mut solver = Solver::new((), false);
+        let goal: TestGoal = eq(LTerm::from(1), LTerm::from(2)).cast_into();
+        assert!(solver
+            .solve_first(&goal, State::new(DefaultUser::new()))
+            .is_none());
+    }
+
+    #[test]
+    fn test_solver_is_productive_1() {
+        let mut solver = Solver::new((), false);
+        let x = LTerm::var("x");
+        let goal: TestGoal = proto_vulcan!(member(x, [1, 2]));
+        let mut stream = solver.start(&goal, State::new(DefaultUser::new()));
+        assert!(solver.is_productive(&mut stream));
+        // The solutions are still available in the stream
+        let state = solver.next(&mut stream).unwrap();
+        assert_eq!(state.smap_ref().walk(&x), &LTerm::from(1));
+        assert!(solver.is_productive(&mut stream));
+        let state = solver.next(&mut stream).unwrap();
+        assert_eq!(state.smap_ref().walk(&x), &LTerm::from(2));
+        assert!(!solver.is_productive(&mut stream));
+        assert!(solver.next(&mut stream).is_none());
+    }
+
+    #[test]
+    fn test_solver_is_productive_2() {
+        let solver = Solver::new((), false);
+        let x = LTerm::var("x");
+        let goal: TestGoal = proto_vulcan!([member(x, [1, 2]), x == 3]);
+        let mut stream = solver.start(&goal, State::new(DefaultUser::new()));
+        assert!(!solver.is_productive(&mut stream));
+        assert!(stream.is_empty());
+    }
+}
@@ -0,0 +1,553 @@
+//! Checkpointing of states to a serializable form
+use crate::engine::Engine;
+use crate::lterm::{LTerm, LTermInner};
+use crate::lvalue::LValue;
+#[cfg(feature = "clpfd")]
+use crate::relation::clpfd::{
+    absfd::AbsFdConstraint, diseqfd::DiseqFdConstraint, disjointo::DisjointFdConstraint,
+    distinctfd::DistinctFd2Constraint, distinctfd::DistinctFdConstraint, divfd::DivFdConstraint,
+    ltefd::LessThanOrEqualFdConstraint, minusfd::MinusFdConstraint, modfd::ModFdConstraint,
+    plusfd::PlusFdConstraint, reifyeq::ReifyEqConstraint, scaleo::ScaleFdConstraint,
+    sumfd::SumFdConstraint, timesfd::TimesFdConstraint,
+};
+use crate::relation::diseq::DisequalityConstraint;
+use crate::state::{Constraint, FiniteDomain, SMap, State};
+use crate::user::User;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::rc::Rc;
+
+/// Serializable form of a term. Variables are referred to by their index in the checkpoint.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+enum Term {
+    Bool(bool),
+    Number(isize),
+    Char(char),
+    String(String),
+    Var(usize),
+    Empty,
+    Cons(Box<Term>, Box<Term>),
+}
+
+/// Serializable form of a supported constraint.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+enum ConstraintSnapshot {
+    Diseq(Vec<(Term, Term)>),
+    #[cfg(feature = "clpfd")]
+    DiseqFd(Term, Term),
+    #[cfg(feature = "clpfd")]
+    DisjointFd(Term, Term),
+    #[cfg(feature = "clpfd")]
+    DistinctFd(Term),
+    #[cfg(feature = "clpfd")]
+    LessThanOrEqualFd(Term, Term),
+    #[cfg(feature = "clpfd")]
+    PlusFd(Term, Term, Term),
+    #[cfg(feature = "clpfd")]
+    MinusFd(Term, Term, Term),
+    #[cfg(feature = "clpfd")]
+    TimesFd(Term, Term, Term),
+    #[cfg(feature = "clpfd")]
+    DivFd(Term, Term, Term),
+    #[cfg(feature = "clpfd")]
+    ModFd(Term, Term, Term),
+    #[cfg(feature = "clpfd")]
+    AbsFd(Term, Term),
+    #[cfg(feature = "clpfd")]
+    ScaleFd(Term, isize, Term),
+    #[cfg(feature = "clpfd")]
+    SumFd(Term, Term),
+    #[cfg(feature = "clpfd")]
+    ReifyEq(Term, Term, Term),
+}
+
+/// Reason why a state could not be checkpointed or restored.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CheckpointError {
+    /// The state contains a term that cannot be serialized, such as a user term or
+    /// a compound term.
+    UnsupportedTerm(String),
+    /// The state contains a constraint that cannot be serialized.
+    UnsupportedConstraint(String),
+    /// The checkpoint refers to a variable that it does not declare.
+    UnknownVariable(usize),
+    /// The constraints of the restored state are not satisfiable.
+    Inconsistent,
+}
+
+impl std::fmt::Display for CheckpointError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CheckpointError::UnsupportedTerm(term) => {
+                write!(f, "term {} cannot be checkpointed", term)
+            }
+            CheckpointError::UnsupportedConstraint(constraint) => {
+                write!(f, "constraint {} cannot be checkpointed", constraint)
+            }
+            CheckpointError::UnknownVariable(index) => {
+                write!(f, "checkpoint refers to an unknown variable {}", index)
+            }
+            CheckpointError::Inconsistent => write!(f, "restored constraints are not satisfiable"),
+        }
+    }
+}
+
+impl std::error::Error for CheckpointError {}
+
+/// A serializable snapshot of a `State`.
+///
+/// The checkpoint contains the substitution, the domain store and the constraint store of the
+/// state, the domains recorded for the query variables before enumeration, and a list of root terms, such as the query variables, that are needed for
+/// continuing the search from the restored state. Supported constraints are tree
+/// disequalities and the CLP(FD) constraints; other constraints, user terms and compound terms
+/// cannot be checkpointed. The user state is not part of the checkpoint.
+///
+/// # Example
+/// ```rust
+/// extern crate proto_vulcan;
+/// use proto_vulcan::prelude::*;
+/// use proto_vulcan::relation::{infdrange, ltfd};
+/// use proto_vulcan::solver::Solver;
+/// use proto_vulcan::state::{Checkpoint, State};
+/// fn main() {
+///     let x = LTerm::var("x");
+///     let y = LTerm::var("y");
+///     let goal: Goal<DefaultUser, DefaultEngine<DefaultUser>> =
+///         proto_vulcan!([infdrange([x, y], &(0..=9)), ltfd(x, y)]);
+///     let mut solver = Solver::new((), false);
+///     let state = solver.solve_first(&goal, State::new(DefaultUser::new())).unwrap();
+///     let checkpoint = state.checkpoint(&[x, y]).unwrap();
+///
+///     // The checkpoint can be serialized and restored in another process.
+///     let (state, roots): (State, _) = checkpoint.restore(DefaultUser::new()).unwrap();
+///     let (x, y) = (roots[0].clone(), roots[1].clone());
+///     let goal: Goal<DefaultUser, DefaultEngine<DefaultUser>> = proto_vulcan!(y == 1);
+///     let state = solver.solve_first(&goal, state).unwrap();
+///     assert_eq!(state.smap_ref().walk(&x), &0);
+/// }
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Names of the variables; the index of a name is the index of the variable.
+    vars: Vec<String>,
+    roots: Vec<Term>,
+    substitution: Vec<(usize, Term)>,
+    domains: Vec<(usize, FiniteDomain)>,
+    constraints: Vec<ConstraintSnapshot>,
+    #[serde(default)]
+    query_domains: Vec<(usize, FiniteDomain)>,
+}
+
+/// A restored state and the root terms of its checkpoint.
+pub type Restored<U, E> = (State<U, E>, Vec<LTerm<U, E>>);
+
+/// Translates terms of a state to their serializable form, numbering the variables in the
+/// order of appearance.
+struct Encoder<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    vars: IndexMap<LTerm<U, E>, String>,
+}
+
+impl<U, E> Encoder<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn var(&mut self, u: &LTerm<U, E>) -> Result<usize, CheckpointError> {
+        match u.as_ref() {
+            LTermInner::Var(_, name) => {
+                let entry = self.vars.entry(u.clone());
+                let index = entry.index();
+                entry.or_insert_with(|| name.to_string());
+                Ok(index)
+            }
+            _ => Err(CheckpointError::UnsupportedTerm(u.to_string())),
+        }
+    }
+
+    fn term(&mut self, u: &LTerm<U, E>) -> Result<Term, CheckpointError> {
+        match u.as_ref() {
+            LTermInner::Val(LValue::Bool(b)) => Ok(Term::Bool(*b)),
+            LTermInner::Val(LValue::Number(n)) => Ok(Term::Number(*n)),
+            LTermInner::Val(LValue::Char(c)) => Ok(Term::Char(*c)),
+            LTermInner::Val(LValue::String(s)) => Ok(Term::String(s.clone())),
+            LTermInner::Var(_, _) => Ok(Term::Var(self.var(u)?)),
+            LTermInner::Empty => Ok(Term::Empty),
+            LTermInner::Cons(head, tail) => Ok(Term::Cons(
+                Box::new(self.term(head)?),
+                Box::new(self.term(tail)?),
+            )),
+            _ => Err(CheckpointError::UnsupportedTerm(u.to_string())),
+        }
+    }
+
+    fn constraint(
+        &mut self,
+        constraint: &Rc<dyn Constraint<U, E>>,
+    ) -> Result<ConstraintSnapshot, CheckpointError> {
+        if let Some(diseq) = constraint.downcast_ref::<DisequalityConstraint<U, E>>() {
+            let mut pairs = vec![];
+            for (u, v) in diseq.smap_ref().iter() {
+                pairs.push((self.term(u)?, self.term(v)?));
+            }
+            return Ok(ConstraintSnapshot::Diseq(pairs));
+        }
+
+        #[cfg(feature = "clpfd")]
+        {
+            if let Some(distinct) = constraint.downcast_ref::<DistinctFd2Constraint<U, E>>() {
+                return Ok(ConstraintSnapshot::DistinctFd(self.term(&distinct.list())?));
+            }
+
+            if let Some(sum) = constraint.downcast_ref::<SumFdConstraint<U, E>>() {
+                let (list, s) = (self.term(sum.list())?, self.term(sum.sum())?);
+                return Ok(ConstraintSnapshot::SumFd(list, s));
+            }
+
+            let operands = constraint
+                .operands()
+                .iter()
+                .map(|u| self.term(u))
+                .collect::<Result<Vec<Term>, CheckpointError>>()?;
+            let mut operands = operands.into_iter();
+            let mut next = || {
+                operands
+                    .next()
+                    .ok_or_else(|| CheckpointError::UnsupportedConstraint(constraint.to_string()))
+            };
+            if constraint.is::<DiseqFdConstraint<U, E>>() {
+                return Ok(ConstraintSnapshot::DiseqFd(next()?, next()?));
+            } else if constraint.is::<DisjointFdConstraint<U, E>>() {
+                return Ok(ConstraintSnapshot::DisjointFd(next()?, next()?));
+            } else if constraint.is::<DistinctFdConstraint<U, E>>() {
+                return Ok(ConstraintSnapshot::DistinctFd(next()?));
+            } else if constraint.is::<LessThanOrEqualFdConstraint<U, E>>() {
+                return Ok(ConstraintSnapshot::LessThanOrEqualFd(next()?, next()?));
+            } else if constraint.is::<PlusFdConstraint<U, E>>() {
+                return Ok(ConstraintSnapshot::PlusFd(next()?, next()?, next()?));
+            } else if constraint.is::<MinusFdConstraint<U, E>>() {
+                return Ok(ConstraintSnapshot::MinusFd(next()?, next()?, next()?));
+            } else if constraint.is::<TimesFdConstraint<U, E>>() {
+                return Ok(ConstraintSnapshot::TimesFd(next()?, next()?, next()?));
+            } else if constraint.is::<DivFdConstraint<U, E>>() {
+                return Ok(ConstraintSnapshot::DivFd(next()?, next()?, next()?));
+            } else if constraint.is::<ModFdConstraint<U, E>>() {
+                return Ok(ConstraintSnapshot::ModFd(next()?, next()?, next()?));
+            } else if constraint.is::<ReifyEqConstraint<U, E>>() {
+                return Ok(ConstraintSnapshot::ReifyEq(next()?, next()?, next()?));
+            } else if constraint.is::<AbsFdConstraint<U, E>>() {
+                return Ok(ConstraintSnapshot::AbsFd(next()?, next()?));
+            } else if constraint.is::<ScaleFdConstraint<U, E>>() {
+                let x = next()?;
+                if let Term::Number(k) = next()? {
+                    return Ok(ConstraintSnapshot::ScaleFd(x, k, next()?));
+                }
+            }
+        }
+
+        Err(CheckpointError::UnsupportedConstraint(
+            constraint.to_string(),
+        ))
+    }
+}
+
+/// Translates the serialized terms back to terms, with a fresh variable for each variable of
+/// the checkpoint.
+struct Decoder<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    vars: Vec<LTerm<U, E>>,
+}
+
+impl<U, E> Decoder<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn new(names: &[String]) -> Decoder<U, E> {
+        let vars = names
+            .iter()
+            .map(|name| {
+                if name == "_" {
+                    LTerm::any()
+                } else {
+                    LTerm::var(name)
+                }
+            })
+            .collect();
+        Decoder { vars }
+    }
+
+    fn var(&self, index: usize) -> Result<LTerm<U, E>, CheckpointError> {
+        self.vars
+            .get(index)
+            .cloned()
+            .ok_or(CheckpointError::UnknownVariable(index))
+    }
+
+    fn term(&self, t: &Term) -> Result<LTerm<U, E>, CheckpointError> {
+        match t {
+            Term::Bool(b) => Ok(LTerm::from(*b)),
+            Term::Number(n) => Ok(LTerm::from(*n)),
+            Term::Char(c) => Ok(LTerm::from(*c)),
+            Term::String(s) => Ok(LTerm::from(s.as_str())),
+            Term::Var(index) => self.var(*index),
+            Term::Empty => Ok(LTerm::empty_list()),
+            Term::Cons(head, tail) => Ok(LTerm::cons(self.term(head)?, self.term(tail)?)),
+        }
+    }
+
+    fn constraint(
+        &self,
+        snapshot: &ConstraintSnapshot,
+    ) -> Result<Rc<dyn Constraint<U, E>>, CheckpointError> {
+        Ok(match snapshot {
+            ConstraintSnapshot::Diseq(pairs) => {
+                let mut smap = SMap::new();
+                for (u, v) in pairs.iter() {
+                    smap.extend(self.term(u)?, self.term(v)?);
+                }
+                DisequalityConstraint::new(smap)
+            }
+            #[cfg(feature = "clpfd")]
+            ConstraintSnapshot::DiseqFd(u, v) => {
+                DiseqFdConstraint::new(self.term(u)?, self.term(v)?)
+            }
+            #[cfg(feature = "clpfd")]
+            ConstraintSnapshot::DisjointFd(u, v) => {
+                DisjointFdConstraint::new(self.term(u)?, self.term(v)?)
+            }
+            #[cfg(feature = "clpfd")]
+            ConstraintSnapshot::DistinctFd(u) => DistinctFdConstraint::new(self.term(u)?),
+            #[cfg(feature = "clpfd")]
+            ConstraintSnapshot::LessThanOrEqualFd(u, v) => {
+                LessThanOrEqualFdConstraint::new(self.term(u)?, self.term(v)?)
+            }
+            #[cfg(feature = "clpfd")]
+            ConstraintSnapshot::PlusFd(u, v, w) => {
+                PlusFdConstraint::new(self.term(u)?, self.term(v)?, self.term(w)?)
+            }
+            #[cfg(feature = "clpfd")]
+            ConstraintSnapshot::MinusFd(u, v, w) => {
+                MinusFdConstraint::new(self.term(u)?, self.term(v)?, self.term(w)?)
+            }
+            #[cfg(feature = "clpfd")]
+            ConstraintSnapshot::TimesFd(u, v, w) => {
+                TimesFdConstraint::new(self.term(u)?, self.term(v)?, self.term(w)?)
+            }
+            #[cfg(feature = "clpfd")]
+            ConstraintSnapshot::DivFd(u, v, w) => {
+                DivFdConstraint::new(self.term(u)?, self.term(v)?, self.term(w)?)
+            }
+            #[cfg(feature = "clpfd")]
+            ConstraintSnapshot::ModFd(u, v, w) => {
+                ModFdConstraint::new(self.term(u)?, self.term(v)?, self.term(w)?)
+            }
+            #[cfg(feature = "clpfd")]
+            ConstraintSnapshot::AbsFd(u, v) => AbsFdConstraint::new(self.term(u)?, self.term(v)?),
+            #[cfg(feature = "clpfd")]
+            ConstraintSnapshot::ScaleFd(x, k, y) => {
+                ScaleFdConstraint::new(self.term(x)?, *k, self.term(y)?)
+            }
+            #[cfg(feature = "clpfd")]
+            ConstraintSnapshot::SumFd(list, s) => {
+                SumFdConstraint::new(self.term(list)?, self.term(s)?)
+            }
+            #[cfg(feature = "clpfd")]
+            ConstraintSnapshot::ReifyEq(b, x, y) => {
+                ReifyEqConstraint::new(self.term(b)?, self.term(x)?, self.term(y)?)
+            }
+        })
+    }
+}
+
+impl<U, E> State<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    /// Takes a serializable checkpoint of the state, from which the search can be resumed
+    /// later, possibly in another process. The `roots` are the terms that are needed for
+    /// continuing the search, such as the query variables; they are returned in the same order
+    /// when the checkpoint is restored.
+    pub fn checkpoint(&self, roots: &[LTerm<U, E>]) -> Result<Checkpoint, CheckpointError> {
+        let mut encoder = Encoder {
+            vars: IndexMap::new(),
+        };
+
+        let roots = roots
+            .iter()
+            .map(|u| encoder.term(u))
+            .collect::<Result<Vec<Term>, CheckpointError>>()?;
+
+        let mut substitution = vec![];
+        for (x, v) in self.smap_ref().iter() {
+            substitution.push((encoder.var(x)?, encoder.term(v)?));
+        }
+
+        let mut domains = vec![];
+        for (x, domain) in self.dstore_ref().iter() {
+            domains.push((encoder.var(x)?, domain.as_ref().clone()));
+        }
+
+        let mut query_domains = vec![];
+        for (x, domain) in self.query_domains.iter() {
+            query_domains.push((encoder.var(x)?, domain.as_ref().clone()));
+        }
+
+        let constraints = self
+            .cstore_ref()
+            .iter()
+            .map(|c| encoder.constraint(c))
+            .collect::<Result<Vec<ConstraintSnapshot>, CheckpointError>>()?;
+
+        Ok(Checkpoint {
+            vars: encoder.vars.into_iter().map(|(_, name)| name).collect(),
+            roots,
+            substitution,
+            domains,
+            constraints,
+            query_domains,
+        })
+    }
+}
+
+impl Checkpoint {
+    /// Restores the checkpointed state with `user_state`, and returns it together with the
+    /// root terms of the checkpoint.
+    ///
+    /// The variables of the restored state are fresh variables, and the restored roots must be
+    /// used in place of the original ones. The constraints are run when restored, therefore
+    /// restoring fails with `Inconsistent` if the checkpoint has been modified so that its
+    /// constraints are no longer satisfiable.
+    pub fn restore<U, E>(&self, user_state: U) -> Result<Restored<U, E>, CheckpointError>
+    where
+        U: User,
+        E: Engine<U>,
+    {
+        let decoder: Decoder<U, E> = Decoder::new(&self.vars);
+
+        let mut smap = SMap::new();
+        for (x, v) in self.substitution.iter() {
+            smap.extend(decoder.var(*x)?, decoder.term(v)?);
+        }
+
+        let mut dstore = IndexMap::new();
+        for (x, domain) in self.domains.iter() {
+            dstore.insert(decoder.var(*x)?, Rc::new(domain.clone()));
+        }
+
+        let mut state = State::new(user_state).with_smap(smap).with_dstore(dstore);
+        for (x, domain) in self.query_domains.iter() {
+            state.record_query_domain(decoder.var(*x)?, Rc::new(domain.clone()));
+        }
+        for snapshot in self.constraints.iter() {
+            let constraint = decoder.constraint(snapshot)?;
+            state = state
+                .run_constraint(constraint)
+                .map_err(|_| CheckpointError::Inconsistent)?;
+        }
+
+        let roots = self
+            .roots
+            .iter()
+            .map(|t| decoder.term(t))
+            .collect::<Result<Vec<LTerm<U, E>>, CheckpointError>>()?;
+
+        Ok((state, roots))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Checkpoint, CheckpointError};
+    use crate::prelude::*;
+    use crate::relation::clpfd::distinctfd::distinctfd;
+    use crate::relation::clpfd::infd::infdrange;
+    use crate::relation::clpfd::plusfd::plusfd;
+    use crate::relation::typeo::numbero;
+    use crate::solver::Solver;
+    use crate::state::State;
+
+    type TestGoal = Goal<DefaultUser, DefaultEngine<DefaultUser>>;
+    type TestState = State<DefaultUser, DefaultEngine<DefaultUser>>;
+
+    fn solve(goal: TestGoal, state: TestState) -> Option<TestState> {
+        let mut solver = Solver::new((), false);
+        solver.solve_first(&goal, state)
+    }
+
+    #[test]
+    fn test_checkpoint_1() {
+        let x = LTerm::var("x");
+        let y = LTerm::var("y");
+        let z = LTerm::var("z");
+        let w = LTerm::var("w");
+        let goal = proto_vulcan!([
+            infdrange([x, y, z], &(1..=5)),
+            plusfd(x, y, z),
+            distinctfd([x, y, z]),
+            w != [x, "a"],
+            x == 2,
+        ]);
+        let state = solve(goal, State::new(DefaultUser::new())).unwrap();
+
+        let checkpoint = state.checkpoint(&[x, y, z, w]).unwrap();
+        let json = serde_json::to_string(&checkpoint).unwrap();
+        let checkpoint: Checkpoint = serde_json::from_str(&json).unwrap();
+        let (restored, roots): (TestState, _) = checkpoint.restore(DefaultUser::new()).unwrap();
+        assert_eq!(restored.to_string(), state.to_string());
+        assert_eq!(restored.constraint_count(), state.constraint_count());
+        assert_eq!(restored.domain_count(), state.domain_count());
+
+        let (x, y, z, w) = (
+            roots[0].clone(),
+            roots[1].clone(),
+            roots[2].clone(),
+            roots[3].clone(),
+        );
+        assert_eq!(restored.smap_ref().walk(&x), &2);
+
+        // The constraints of the restored state are still in effect
+        let goal = proto_vulcan!(y == 2);
+        assert!(solve(goal, restored.clone()).is_none());
+        let goal = proto_vulcan!(w == [2, "a"]);
+        assert!(solve(goal, restored.clone()).is_none());
+        let goal = proto_vulcan!(y == 3);
+        let state = solve(goal, restored).unwrap();
+        assert_eq!(state.smap_ref().walk(&z), &5);
+    }
+
+    #[test]
+    fn test_checkpoint_2() {
+        let x = LTerm::var("x");
+        let goal = proto_vulcan!(numbero(x));
+        let state = solve(goal, State::new(DefaultUser::new())).unwrap();
+        match state.checkpoint(&[x]) {
+            Err(CheckpointError::UnsupportedConstraint(_)) => (),
+            _ => panic!("typeo-constraint must not be checkpointed"),
+        }
+    }
+
+    #[test]
+    fn test_checkpoint_3() {
+        // The domains recorded for the query variables are checkpointed
+        let x = LTerm::var("x");
+        let goal = proto_vulcan!(infdrange(x, &(1..=3)));
+        let mut state = solve(goal, State::new(DefaultUser::new())).unwrap();
+        let domain = state.dstore_ref().get(&x).cloned().unwrap();
+        state.record_query_domain(x.clone(), domain);
+
+        let checkpoint = state.checkpoint(&[x]).unwrap();
+        let json = serde_json::to_string(&checkpoint).unwrap();
+        let checkpoint: Checkpoint = serde_json::from_str(&json).unwrap();
+        let (restored, roots): (TestState, _) = checkpoint.restore(DefaultUser::new()).unwrap();
+        let domain = restored.query_domain(&roots[0]).unwrap();
+        assert_eq!((domain.min(), domain.max()), (1, 3));
+    }
+}
@@ -93,3 +93,86 @@ where
     U: User,
     E: Engine<U>,
 {}
+
+/// Reason of a failure to satisfy the constraints of a state.
+///
+/// If the failure was caused by a constraint, the error identifies the violated constraint
+/// and its operands. Failures of unification are not caused by any constraint.
+#[derive(Derivative)]
+#[derivative(Debug(bound = "U: User"), Clone(bound = "U: User"))]
+pub struct ConstraintError<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    constraint: Option<Rc<dyn Constraint<U, E>>>,
+}
+
+impl<U, E> ConstraintError<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    pub fn new() -> ConstraintError<U, E> {
+        ConstraintError { constraint: None }
+    }
+
+    /// Sets the violated constraint, unless the error already identifies a constraint.
+    ///
+    /// Constraints may run other constraints, and the innermost constraint is the most
+    /// specific reason of the failure.
+    pub fn or_constraint(self, constraint: Rc<dyn Constraint<U, E>>) -> ConstraintError<U, E> {
+        match self.constraint {
+            Some(_) => self,
+            None => ConstraintError {
+                constraint: Some(constraint),
+            },
+        }
+    }
+
+    /// Returns the violated constraint, if the failure was caused by a constraint.
+    pub fn constraint(&self) -> Option<&Rc<dyn Constraint<U, E>>> {
+        self.constraint.as_ref()
+    }
+
+    /// Returns the operands of the violated constraint.
+    pub fn operands(&self) -> Vec<LTerm<U, E>> {
+        match &self.constraint {
+            Some(constraint) => constraint.operands(),
+            None => vec![],
+        }
+    }
+}
+
+impl<U, E> Default for ConstraintError<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn default() -> ConstraintError<U, E> {
+        ConstraintError::new()
+    }
+}
+
+impl<U, E> From<()> for ConstraintError<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn from(_: ()) -> ConstraintError<U, E> {
+        ConstraintError::new()
+    }
+}
+
+impl<U, E> Display for ConstraintError<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.constraint {
+            Some(constraint) => write!(f, "violated constraint {:?}", constraint),
+            None => write!(f, "unification failed"),
+        }
+    }
+}
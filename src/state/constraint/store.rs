@@ -4,12 +4,12 @@ use crate::relation::diseq::DisequalityConstraint;
 use crate::state::constraint::Constraint;
 use crate::engine::Engine;
 use crate::state::User;
-use std::collections::HashSet;
+use indexmap::IndexSet;
 use std::rc::Rc;
 
 #[derive(Derivative)]
 #[derivative(Debug(bound="U: User"), Clone(bound="U: User"))]
-pub struct ConstraintStore<U, E>(HashSet<Rc<dyn Constraint<U, E>>>)
+pub struct ConstraintStore<U, E>(IndexSet<Rc<dyn Constraint<U, E>>>)
 where
     U: User,
     E: Engine<U>;
@@ -20,7 +20,7 @@ where
     E: Engine<U>,
 {
     pub fn new() -> ConstraintStore<U, E> {
-        ConstraintStore(HashSet::new())
+        ConstraintStore(IndexSet::new())
     }
 
     /// Remove irrelevant constraints
@@ -63,8 +63,8 @@ where
     /// Add new constraint `c` while keeping the store normalized
     pub fn push_and_normalize(&mut self, newc: Rc<dyn Constraint<U, E>>) {
         if let Some(tree_newc) = newc.downcast_ref::<DisequalityConstraint<U, E>>() {
-            let mut normalized = HashSet::new();
-            for storec in self.0.drain() {
+            let mut normalized = IndexSet::new();
+            for storec in self.0.drain(..) {
                 // All non-subsumable constraints are always carried along
                 if let Some(tree_storec) = storec.downcast_ref::<DisequalityConstraint<U, E>>() {
                     if !tree_storec.subsumes(tree_newc) && !tree_newc.subsumes(tree_storec) {
@@ -100,8 +100,13 @@ where
         self.0.is_empty()
     }
 
+    /// Returns the number of constraints in the store.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
     pub fn take(&mut self, u: &Rc<dyn Constraint<U, E>>) -> Option<Rc<dyn Constraint<U, E>>> {
-        self.0.take(u)
+        self.0.swap_take(u)
     }
 
     pub fn insert(&mut self, key: Rc<dyn Constraint<U, E>>) -> bool {
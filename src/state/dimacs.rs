@@ -0,0 +1,307 @@
+//! Export of boolean finite domain models in DIMACS CNF format
+use crate::engine::Engine;
+use crate::lterm::LTerm;
+use crate::relation::clpfd::diseqfd::DiseqFdConstraint;
+use crate::relation::clpfd::distinctfd::DistinctFd2Constraint;
+use crate::relation::clpfd::ltefd::LessThanOrEqualFdConstraint;
+use crate::relation::clpfd::minusfd::MinusFdConstraint;
+use crate::relation::clpfd::plusfd::PlusFdConstraint;
+use crate::relation::clpfd::timesfd::TimesFdConstraint;
+use crate::relation::diseq::DisequalityConstraint;
+use crate::state::State;
+use crate::user::User;
+use std::io::{self, Write};
+
+/// Constraint operand translated to CNF: either an index of a boolean variable or a constant.
+#[derive(Clone, Copy)]
+enum Operand {
+    Var(usize),
+    Const(isize),
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Adds a clause for each assignment of the boolean variables of `operands` that does not
+/// satisfy `predicate`.
+fn add_clauses<P>(operands: &[Operand], predicate: P, clauses: &mut Vec<Vec<isize>>)
+where
+    P: Fn(&[isize]) -> bool,
+{
+    let mut vars: Vec<usize> = operands
+        .iter()
+        .filter_map(|operand| match operand {
+            Operand::Var(index) => Some(*index),
+            Operand::Const(_) => None,
+        })
+        .collect();
+    vars.sort_unstable();
+    vars.dedup();
+
+    for assignment in 0..(1usize << vars.len()) {
+        let value_of = |index: usize| {
+            let position = vars.binary_search(&index).unwrap();
+            ((assignment >> position) & 1) as isize
+        };
+        let values: Vec<isize> = operands
+            .iter()
+            .map(|operand| match operand {
+                Operand::Var(index) => value_of(*index),
+                Operand::Const(value) => *value,
+            })
+            .collect();
+        if !predicate(&values) {
+            // DIMACS variables are numbered from 1
+            let clause = vars
+                .iter()
+                .map(|index| {
+                    let literal = *index as isize + 1;
+                    if value_of(*index) == 1 {
+                        -literal
+                    } else {
+                        literal
+                    }
+                })
+                .collect();
+            clauses.push(clause);
+        }
+    }
+}
+
+impl<U, E> State<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    /// Writes the finite domain constraints on boolean variables `vars` as DIMACS CNF.
+    ///
+    /// The variable `vars[i]` becomes DIMACS variable `i + 1`, with value 1 as true and
+    /// value 0 as false. Domains of the variables must be subsets of `{0, 1}`. The supported
+    /// constraints are `ltefd`, `diseqfd`, `plusfd`, `minusfd`, `timesfd`, `distinctfd` and
+    /// tree disequalities, which are translated by excluding each violating assignment of
+    /// their operands with a clause. Returns `InvalidData`-error if the constraints cannot
+    /// be expressed as pure SAT over `vars`.
+    ///
+    /// # Example
+    /// ```rust
+    /// extern crate proto_vulcan;
+    /// use proto_vulcan::prelude::*;
+    /// use proto_vulcan::relation::{diseqfd, infd};
+    /// use proto_vulcan::solver::Solver;
+    /// use proto_vulcan::state::State;
+    /// fn main() {
+    ///     let x = LTerm::var("x");
+    ///     let y = LTerm::var("y");
+    ///     let goal: Goal<DefaultUser, DefaultEngine<DefaultUser>> =
+    ///         proto_vulcan!([infd([x, y], &[0, 1]), diseqfd(x, y)]);
+    ///     let mut solver = Solver::new((), false);
+    ///     let state = solver.solve_first(&goal, State::new(DefaultUser::new())).unwrap();
+    ///     let mut cnf = vec![];
+    ///     state.to_dimacs(&[x, y], &mut cnf).unwrap();
+    ///     assert_eq!(String::from_utf8(cnf).unwrap(), "p cnf 2 2\n1 2 0\n-1 -2 0\n");
+    /// }
+    /// ```
+    pub fn to_dimacs<W: Write>(&self, vars: &[LTerm<U, E>], w: &mut W) -> io::Result<()> {
+        let smap = self.smap_ref();
+        let vars: Vec<LTerm<U, E>> = vars.iter().map(|v| smap.walk(v).clone()).collect();
+        let operand = |u: &LTerm<U, E>| -> io::Result<Operand> {
+            let uwalk = smap.walk(u);
+            if let Some(value) = uwalk.get_number() {
+                return Ok(Operand::Const(value));
+            }
+            match vars.iter().position(|v| v == uwalk) {
+                Some(index) => Ok(Operand::Var(index)),
+                None => Err(invalid_data(format!(
+                    "term {} is not one of the boolean variables",
+                    uwalk
+                ))),
+            }
+        };
+
+        let mut clauses: Vec<Vec<isize>> = vec![];
+
+        for (index, v) in vars.iter().enumerate() {
+            let literal = index as isize + 1;
+            let values: Vec<isize> = match v.get_number() {
+                Some(value) => vec![value],
+                None if v.is_var() => match self.dstore_ref().get(v) {
+                    Some(domain) => domain.iter().collect(),
+                    None => vec![0, 1],
+                },
+                None => {
+                    return Err(invalid_data(format!("term {} is not boolean", v)));
+                }
+            };
+            if values.iter().any(|value| *value != 0 && *value != 1) {
+                return Err(invalid_data(format!(
+                    "term {} has values that are not boolean",
+                    v
+                )));
+            }
+            if !values.contains(&0) {
+                clauses.push(vec![literal]);
+            } else if !values.contains(&1) {
+                clauses.push(vec![-literal]);
+            }
+        }
+
+        for constraint in self.cstore_ref().iter() {
+            let operands = constraint
+                .operands()
+                .iter()
+                .map(&operand)
+                .collect::<io::Result<Vec<Operand>>>();
+
+            if constraint.is::<LessThanOrEqualFdConstraint<U, E>>() {
+                add_clauses(&operands?, |v| v[0] <= v[1], &mut clauses);
+            } else if constraint.is::<DiseqFdConstraint<U, E>>() {
+                add_clauses(&operands?, |v| v[0] != v[1], &mut clauses);
+            } else if constraint.is::<PlusFdConstraint<U, E>>() {
+                add_clauses(&operands?, |v| v[0] + v[1] == v[2], &mut clauses);
+            } else if constraint.is::<MinusFdConstraint<U, E>>() {
+                add_clauses(&operands?, |v| v[0] - v[1] == v[2], &mut clauses);
+            } else if constraint.is::<TimesFdConstraint<U, E>>() {
+                add_clauses(&operands?, |v| v[0] * v[1] == v[2], &mut clauses);
+            } else if constraint.is::<DistinctFd2Constraint<U, E>>() {
+                let operands = operands?;
+                for (i, a) in operands.iter().enumerate() {
+                    for b in operands.iter().skip(i + 1) {
+                        add_clauses(&[*a, *b], |v| v[0] != v[1], &mut clauses);
+                    }
+                }
+            } else if let Some(diseq) = constraint.downcast_ref::<DisequalityConstraint<U, E>>() {
+                // At least one of the substitutions in the disequality must not hold.
+                let mut operands = vec![];
+                for (u, v) in diseq.smap_ref().iter() {
+                    operands.push(operand(u)?);
+                    operands.push(operand(v)?);
+                }
+                add_clauses(
+                    &operands,
+                    |v| v.chunks(2).any(|pair| pair[0] != pair[1]),
+                    &mut clauses,
+                );
+            } else {
+                return Err(invalid_data(format!(
+                    "constraint {:?} cannot be expressed in CNF",
+                    constraint
+                )));
+            }
+        }
+
+        writeln!(w, "p cnf {} {}", vars.len(), clauses.len())?;
+        for clause in clauses.iter() {
+            for literal in clause.iter() {
+                write!(w, "{} ", literal)?;
+            }
+            writeln!(w, "0")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::relation::clpfd::infd::infd;
+    use crate::relation::clpfd::ltefd::ltefd;
+    use crate::relation::clpfd::plusfd::plusfd;
+    use crate::relation::typeo::numbero;
+    use crate::solver::Solver;
+    use crate::state::State;
+
+    type TestGoal = Goal<DefaultUser, DefaultEngine<DefaultUser>>;
+    type TestState = State<DefaultUser, DefaultEngine<DefaultUser>>;
+
+    fn solve(goal: TestGoal) -> TestState {
+        let mut solver = Solver::new((), false);
+        solver
+            .solve_first(&goal, State::new(DefaultUser::new()))
+            .unwrap()
+    }
+
+    fn dimacs(state: &TestState, vars: &[LTerm]) -> String {
+        let mut cnf = vec![];
+        state.to_dimacs(vars, &mut cnf).unwrap();
+        String::from_utf8(cnf).unwrap()
+    }
+
+    #[test]
+    fn test_to_dimacs_1() {
+        let x = LTerm::var("x");
+        let y = LTerm::var("y");
+        let z = LTerm::var("z");
+        let goal = proto_vulcan!([infd([x, y, z], &[0, 1]), ltefd(x, y), plusfd(x, y, z)]);
+        let state = solve(goal);
+        let cnf = dimacs(&state, &[x, y, z]);
+        let mut lines = cnf.lines();
+        assert_eq!(lines.next(), Some("p cnf 3 6"));
+        let mut clauses: Vec<&str> = lines.collect();
+        clauses.sort_unstable();
+        assert_eq!(
+            clauses,
+            vec![
+                "-1 -2 -3 0",
+                "-1 -2 3 0",
+                "-1 2 0",
+                "-1 2 3 0",
+                "1 -2 3 0",
+                "1 2 -3 0"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_dimacs_2() {
+        // Bound variables and singleton domains become unit clauses
+        let x = LTerm::var("x");
+        let y = LTerm::var("y");
+        let goal = proto_vulcan!([infd([x, y], &[0, 1]), x == 1, infd(y, &[0])]);
+        let state = solve(goal);
+        assert_eq!(dimacs(&state, &[x, y]), "p cnf 2 2\n1 0\n-2 0\n");
+    }
+
+    #[test]
+    fn test_to_dimacs_3() {
+        // Tree disequality between booleans
+        let x = LTerm::var("x");
+        let y = LTerm::var("y");
+        let goal = proto_vulcan!([x != y]);
+        let state = solve(goal);
+        assert_eq!(dimacs(&state, &[x, y]), "p cnf 2 2\n1 2 0\n-1 -2 0\n");
+    }
+
+    #[test]
+    fn test_to_dimacs_4() {
+        // Domains that are not boolean cannot be exported
+        let x = LTerm::var("x");
+        let goal = proto_vulcan!(infd(x, &[0, 1, 2]));
+        let state = solve(goal);
+        let mut cnf = vec![];
+        let err = state.to_dimacs(&[x], &mut cnf).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_to_dimacs_5() {
+        // Constraints without CNF translation cannot be exported
+        let x = LTerm::var("x");
+        let goal = proto_vulcan!(numbero(x));
+        let state = solve(goal);
+        let mut cnf = vec![];
+        let err = state.to_dimacs(&[x], &mut cnf).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_to_dimacs_6() {
+        // Constraints on variables outside of the boolean set cannot be exported
+        let x = LTerm::var("x");
+        let y = LTerm::var("y");
+        let goal = proto_vulcan!([infd([x, y], &[0, 1]), ltefd(x, y)]);
+        let state = solve(goal);
+        let mut cnf = vec![];
+        assert!(state.to_dimacs(&[x], &mut cnf).is_err());
+    }
+}
@@ -1,14 +1,16 @@
 use std::borrow::Borrow;
 use std::cmp::{max, min};
 use std::iter::Iterator;
-use std::ops::RangeInclusive;
+use std::ops::{Range, RangeInclusive};
 use std::slice::Iter;
 use std::vec::IntoIter;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FiniteDomain {
     Interval(RangeInclusive<isize>),
     Sparse(Vec<isize>),
+    Empty,
 }
 
 impl FiniteDomain {
@@ -16,6 +18,7 @@ impl FiniteDomain {
         match self {
             FiniteDomain::Interval(r) => (r.end() - r.start()).saturating_add(1) == 1,
             FiniteDomain::Sparse(v) => v.len() == 1,
+            FiniteDomain::Empty => false,
         }
     }
 
@@ -27,76 +30,109 @@ impl FiniteDomain {
         }
     }
 
+    /// Returns the number of values in the domain. The count saturates at `usize::MAX`.
+    pub fn len(&self) -> usize {
+        match self {
+            FiniteDomain::Interval(r) => {
+                let count = (*r.end() as i128 - *r.start() as i128 + 1).max(0);
+                if count > usize::MAX as i128 {
+                    usize::MAX
+                } else {
+                    count as usize
+                }
+            }
+            FiniteDomain::Sparse(v) => v.len(),
+            FiniteDomain::Empty => 0,
+        }
+    }
+
+    /// Returns `true` if the domain has no values. A variable with an empty domain cannot
+    /// take any value, and processing such a domain fails.
+    pub fn is_empty(&self) -> bool {
+        matches!(self, FiniteDomain::Empty)
+    }
+
+    /// Returns the number of values in the domain; the same as `len`.
+    pub fn count(&self) -> usize {
+        self.len()
+    }
+
+    /// Returns all of the values in the domain in ascending order.
+    ///
+    /// Intervals are expanded into a vector of all of their values, including the ones at
+    /// the limits of `isize`.
+    pub fn to_vec(&self) -> Vec<isize> {
+        match self {
+            FiniteDomain::Interval(r) => r.clone().collect(),
+            FiniteDomain::Sparse(v) => v.clone(),
+            FiniteDomain::Empty => vec![],
+        }
+    }
+
+    /// Returns the smallest value of the domain.
+    ///
+    /// # Panics
+    /// Panics if the domain is empty.
     pub fn min(&self) -> isize {
         match self {
             FiniteDomain::Interval(r) => *r.start(),
             FiniteDomain::Sparse(v) => v.first().copied().unwrap(),
+            FiniteDomain::Empty => panic!("Empty finite domain has no minimum"),
         }
     }
 
+    /// Returns the largest value of the domain.
+    ///
+    /// # Panics
+    /// Panics if the domain is empty.
     pub fn max(&self) -> isize {
         match self {
             FiniteDomain::Interval(r) => *r.end(),
             FiniteDomain::Sparse(v) => v.last().copied().unwrap(),
+            FiniteDomain::Empty => panic!("Empty finite domain has no maximum"),
         }
     }
 
-    pub fn copy_before<P: FnMut(&isize) -> bool>(&self, mut predicate: P) -> Option<FiniteDomain> {
+    pub fn copy_before<P: FnMut(&isize) -> bool>(&self, mut predicate: P) -> FiniteDomain {
         match self {
             FiniteDomain::Interval(r) => match r.clone().into_iter().find(predicate) {
-                Some(u) => {
-                    let r = *r.start()..=u.saturating_sub(1);
-                    if r.is_empty() {
-                        None
-                    } else {
-                        Some(FiniteDomain::Interval(r))
-                    }
-                }
-                None => Some(self.clone()),
+                Some(u) => FiniteDomain::from(*r.start()..=u.saturating_sub(1)),
+                None => self.clone(),
             },
-            FiniteDomain::Sparse(v) => {
-                let v: Vec<isize> = v.iter().copied().take_while(|u| !predicate(u)).collect();
-                if v.is_empty() {
-                    None
-                } else {
-                    Some(FiniteDomain::Sparse(v))
-                }
-            }
+            FiniteDomain::Sparse(v) => FiniteDomain::from(
+                v.iter()
+                    .copied()
+                    .take_while(|u| !predicate(u))
+                    .collect::<Vec<_>>(),
+            ),
+            FiniteDomain::Empty => FiniteDomain::Empty,
         }
     }
 
-    pub fn drop_before<P: FnMut(&isize) -> bool>(&self, mut predicate: P) -> Option<FiniteDomain> {
+    pub fn drop_before<P: FnMut(&isize) -> bool>(&self, mut predicate: P) -> FiniteDomain {
         match self {
             FiniteDomain::Interval(r) => match r.clone().into_iter().find(predicate) {
-                Some(u) => {
-                    let r = u..=*r.end();
-                    Some(FiniteDomain::Interval(r))
-                }
-                None => None,
+                Some(u) => FiniteDomain::Interval(u..=*r.end()),
+                None => FiniteDomain::Empty,
             },
-            FiniteDomain::Sparse(v) => {
-                let v: Vec<isize> = v.iter().copied().skip_while(|u| !predicate(u)).collect();
-                if v.is_empty() {
-                    None
-                } else {
-                    Some(FiniteDomain::Sparse(v))
-                }
-            }
+            FiniteDomain::Sparse(v) => FiniteDomain::from(
+                v.iter()
+                    .copied()
+                    .skip_while(|u| !predicate(u))
+                    .collect::<Vec<_>>(),
+            ),
+            FiniteDomain::Empty => FiniteDomain::Empty,
         }
     }
 
-    pub fn intersect<T: Borrow<FiniteDomain>>(&self, other: T) -> Option<FiniteDomain> {
+    pub fn intersect<T: Borrow<FiniteDomain>>(&self, other: T) -> FiniteDomain {
         match (self, other.borrow()) {
             (FiniteDomain::Interval(rself), FiniteDomain::Interval(rother)) => {
                 // Intersection between two interval domains always results in
                 // another interval domain.
                 let max_start = max(*rself.start(), *rother.start());
                 let min_end = min(*rself.end(), *rother.end());
-                if max_start <= min_end {
-                    Some(FiniteDomain::Interval(max_start..=min_end))
-                } else {
-                    None
-                }
+                FiniteDomain::from(max_start..=min_end)
             }
             (FiniteDomain::Sparse(v), FiniteDomain::Interval(r))
             | (FiniteDomain::Interval(r), FiniteDomain::Sparse(v)) => {
@@ -108,13 +144,9 @@ impl FiniteDomain {
                     .skip_while(|u| u < r.start())
                     .take_while(|u| u <= r.end())
                     .collect::<Vec<isize>>();
-
-                if intersection.is_empty() {
-                    None
-                } else {
-                    Some(FiniteDomain::Sparse(intersection))
-                }
+                FiniteDomain::from(intersection)
             }
+            (FiniteDomain::Empty, _) | (_, FiniteDomain::Empty) => FiniteDomain::Empty,
             _ => {
                 let mut intersection = vec![];
                 let mut siter = self.iter();
@@ -133,17 +165,12 @@ impl FiniteDomain {
                         _ => break,
                     }
                 }
-
-                if intersection.is_empty() {
-                    None
-                } else {
-                    Some(FiniteDomain::Sparse(intersection))
-                }
+                FiniteDomain::from(intersection)
             }
         }
     }
 
-    pub fn diff<T: Borrow<FiniteDomain>>(&self, other: T) -> Option<FiniteDomain> {
+    pub fn diff<T: Borrow<FiniteDomain>>(&self, other: T) -> FiniteDomain {
         let mut difference = vec![];
         let mut siter = self.iter();
         let mut oiter = other.borrow().iter();
@@ -170,15 +197,73 @@ impl FiniteDomain {
             }
         }
 
-        if difference.is_empty() {
-            None
+        FiniteDomain::from(difference)
+    }
+
+    pub fn union<T: Borrow<FiniteDomain>>(&self, other: T) -> FiniteDomain {
+        if self.is_empty() {
+            return other.borrow().clone();
+        } else if other.borrow().is_empty() {
+            return self.clone();
+        }
+
+        if let (FiniteDomain::Interval(rself), FiniteDomain::Interval(rother)) =
+            (self, other.borrow())
+        {
+            // Union of overlapping or adjacent interval domains is another interval domain,
+            // and the intervals do not need to be iterated over.
+            let max_start = max(*rself.start(), *rother.start());
+            let min_end = min(*rself.end(), *rother.end());
+            if max_start <= min_end.saturating_add(1) {
+                let min_start = min(*rself.start(), *rother.start());
+                let max_end = max(*rself.end(), *rother.end());
+                return FiniteDomain::Interval(min_start..=max_end);
+            }
+        }
+
+        let mut union = vec![];
+        let mut siter = self.iter();
+        let mut oiter = other.borrow().iter();
+        let mut maybe_s = siter.next();
+        let mut maybe_o = oiter.next();
+        loop {
+            match (maybe_s, maybe_o) {
+                (Some(s), Some(o)) if s < o => {
+                    maybe_s = siter.next();
+                    union.push(s);
+                }
+                (Some(s), Some(o)) if s == o => {
+                    maybe_s = siter.next();
+                    maybe_o = oiter.next();
+                    union.push(s);
+                }
+                (_, Some(o)) => {
+                    maybe_o = oiter.next();
+                    union.push(o);
+                }
+                (Some(s), None) => {
+                    maybe_s = siter.next();
+                    union.push(s);
+                }
+                (None, None) => break,
+            }
+        }
+
+        // If there are no gaps between the values, the union collapses into an interval.
+        let first = union[0];
+        let last = union[union.len() - 1];
+        if last.checked_sub(first) == Some(union.len() as isize - 1) {
+            FiniteDomain::Interval(first..=last)
         } else {
-            Some(FiniteDomain::Sparse(difference))
+            FiniteDomain::Sparse(union)
         }
     }
 
     pub fn is_disjoint<T: Borrow<FiniteDomain>>(&self, other: T) -> bool {
         let other = other.borrow();
+        if self.is_empty() || other.is_empty() {
+            return true;
+        }
         if self.min() > other.max() || self.max() < other.min() {
             return true;
         }
@@ -205,6 +290,7 @@ impl FiniteDomain {
         match self {
             FiniteDomain::Interval(r) => r.contains(&u),
             FiniteDomain::Sparse(v) => v.binary_search(&u).is_ok(),
+            FiniteDomain::Empty => false,
         }
     }
 
@@ -212,6 +298,7 @@ impl FiniteDomain {
         match self {
             FiniteDomain::Interval(r) => FiniteDomainIter::IntervalIter(r.clone().into_iter()),
             FiniteDomain::Sparse(v) => FiniteDomainIter::SparseIter(v.iter()),
+            FiniteDomain::Empty => FiniteDomainIter::SparseIter([].iter()),
         }
     }
 
@@ -219,13 +306,14 @@ impl FiniteDomain {
         match self {
             FiniteDomain::Interval(r) => FiniteDomainIntoIter::IntervalIter(r.clone().into_iter()),
             FiniteDomain::Sparse(v) => FiniteDomainIntoIter::SparseIter(v.clone().into_iter()),
+            FiniteDomain::Empty => FiniteDomainIntoIter::SparseIter(vec![].into_iter()),
         }
     }
 }
 
 impl PartialEq for FiniteDomain {
     fn eq(&self, other: &FiniteDomain) -> bool {
-        self.diff(other).is_none()
+        self.diff(other).is_empty()
     }
 }
 
@@ -281,7 +369,7 @@ impl DoubleEndedIterator for FiniteDomainIntoIter {
 impl From<Vec<isize>> for FiniteDomain {
     fn from(mut v: Vec<isize>) -> FiniteDomain {
         if v.is_empty() {
-            panic!("Cannot construct empty finite domain");
+            return FiniteDomain::Empty;
         }
         v.sort();
         FiniteDomain::Sparse(v)
@@ -290,13 +378,33 @@ impl From<Vec<isize>> for FiniteDomain {
 
 impl From<RangeInclusive<isize>> for FiniteDomain {
     fn from(r: RangeInclusive<isize>) -> FiniteDomain {
-        FiniteDomain::Interval(r)
+        if r.is_empty() {
+            FiniteDomain::Empty
+        } else {
+            FiniteDomain::Interval(r)
+        }
     }
 }
 
 impl From<&RangeInclusive<isize>> for FiniteDomain {
     fn from(r: &RangeInclusive<isize>) -> FiniteDomain {
-        FiniteDomain::Interval(r.clone())
+        FiniteDomain::from(r.clone())
+    }
+}
+
+impl From<Range<isize>> for FiniteDomain {
+    fn from(r: Range<isize>) -> FiniteDomain {
+        if r.is_empty() {
+            FiniteDomain::Empty
+        } else {
+            FiniteDomain::Interval(r.start..=r.end - 1)
+        }
+    }
+}
+
+impl From<&Range<isize>> for FiniteDomain {
+    fn from(r: &Range<isize>) -> FiniteDomain {
+        FiniteDomain::from(r.clone())
     }
 }
 
@@ -313,6 +421,25 @@ impl From<&[isize]> for FiniteDomain {
     }
 }
 
+impl std::fmt::Display for FiniteDomain {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            FiniteDomain::Interval(r) => write!(f, "{{{}..={}}}", r.start(), r.end()),
+            FiniteDomain::Sparse(v) => {
+                write!(f, "{{")?;
+                for (count, x) in v.iter().enumerate() {
+                    if count > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", x)?;
+                }
+                write!(f, "}}")
+            }
+            FiniteDomain::Empty => write!(f, "{{}}"),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -333,47 +460,47 @@ mod test {
     fn test_finitedomain_2() {
         // copy_before interval
         let fd = FiniteDomain::from(1..=8);
-        let before = fd.copy_before(|x| *x > 6).unwrap();
+        let before = fd.copy_before(|x| *x > 6);
         assert_eq!(before.min(), 1);
         assert_eq!(before.max(), 6);
 
         // If the predicate is never true in the finite domain, copy all
-        let before = fd.copy_before(|x| *x < 0).unwrap();
+        let before = fd.copy_before(|x| *x < 0);
         assert_eq!(before, fd);
 
         // If the predicate is always true, then copy none
-        assert!(fd.copy_before(|x| *x > -1).is_none());
+        assert!(fd.copy_before(|x| *x > -1).is_empty());
     }
 
     #[test]
     fn test_finitedomain_3() {
         // copy_before sparse
         let fd = FiniteDomain::from(vec![1, 2, 3, 4, 5, 6, 7, 8]);
-        let before = fd.copy_before(|x| *x > 6).unwrap();
+        let before = fd.copy_before(|x| *x > 6);
         assert_eq!(before.min(), 1);
         assert_eq!(before.max(), 6);
 
         // If the predicate is never true in the finite domain, copy all
-        let before = fd.copy_before(|x| *x < 0).unwrap();
+        let before = fd.copy_before(|x| *x < 0);
         assert_eq!(before, fd);
 
         // If the predicate is always true, then copy none
-        assert!(fd.copy_before(|x| *x > -1).is_none());
+        assert!(fd.copy_before(|x| *x > -1).is_empty());
     }
 
     #[test]
     fn test_finitedomain_4() {
         // drop_before interval
         let fd = FiniteDomain::from(1..=8);
-        let before = fd.drop_before(|x| *x > 6).unwrap();
+        let before = fd.drop_before(|x| *x > 6);
         assert_eq!(before.min(), 7);
         assert_eq!(before.max(), 8);
 
         // If the predicate is never true in the finite domain, copy none
-        assert!(fd.drop_before(|x| *x > 10).is_none());
+        assert!(fd.drop_before(|x| *x > 10).is_empty());
 
         // If the predicate is always true, then copy all
-        let after = fd.drop_before(|x| *x > 0).unwrap();
+        let after = fd.drop_before(|x| *x > 0);
         assert_eq!(after, fd);
     }
 
@@ -381,15 +508,15 @@ mod test {
     fn test_finitedomain_5() {
         // drop_before sparse
         let fd = FiniteDomain::from(vec![1, 2, 3, 4, 5, 6, 7, 8]);
-        let before = fd.drop_before(|x| *x > 6).unwrap();
+        let before = fd.drop_before(|x| *x > 6);
         assert_eq!(before.min(), 7);
         assert_eq!(before.max(), 8);
 
         // If the predicate is never true in the finite domain, copy none
-        assert!(fd.drop_before(|x| *x > 10).is_none());
+        assert!(fd.drop_before(|x| *x > 10).is_empty());
 
         // If the predicate is always true, then copy all
-        let after = fd.drop_before(|x| *x > 0).unwrap();
+        let after = fd.drop_before(|x| *x > 0);
         assert_eq!(after, fd);
     }
 
@@ -401,11 +528,11 @@ mod test {
         let c = FiniteDomain::from(10..=12);
 
         // Intersection of overlapping intervals is an interval
-        let isect = a.intersect(&b).unwrap();
+        let isect = a.intersect(&b);
         assert_eq!(isect, FiniteDomain::from(4..=6));
 
-        // Intesection of disjoint intervals is None
-        assert!(a.intersect(&c).is_none());
+        // Intesection of disjoint intervals is empty
+        assert!(a.intersect(&c).is_empty());
     }
 
     #[test]
@@ -416,15 +543,15 @@ mod test {
         let c = FiniteDomain::from(vec![10, 11, 12]);
 
         // Intersection of overlapping interval and sparse is a sparse
-        let isect = a.intersect(&b).unwrap();
+        let isect = a.intersect(&b);
         assert_eq!(isect, FiniteDomain::from(vec![4, 5, 6]));
 
-        let isect = b.intersect(&a).unwrap();
+        let isect = b.intersect(&a);
         assert_eq!(isect, FiniteDomain::from(vec![4, 5, 6]));
 
-        // Intesection of disjoint intervals is None
-        assert!(a.intersect(&c).is_none());
-        assert!(c.intersect(&a).is_none());
+        // Intesection of disjoint intervals is empty
+        assert!(a.intersect(&c).is_empty());
+        assert!(c.intersect(&a).is_empty());
     }
 
     #[test]
@@ -435,10 +562,124 @@ mod test {
         let c = FiniteDomain::from(vec![10, 11, 12]);
 
         // Intersection of overlapping sparse domains is a sparse
-        let isect = a.intersect(&b).unwrap();
+        let isect = a.intersect(&b);
         assert_eq!(isect, FiniteDomain::from(vec![4, 5, 6]));
 
-        // Intesection of disjoint intervals is None
-        assert!(a.intersect(&c).is_none());
+        // Intesection of disjoint intervals is empty
+        assert!(a.intersect(&c).is_empty());
+    }
+
+    #[test]
+    fn test_finitedomain_9() {
+        // union interval with interval
+        let a = FiniteDomain::from(1..=6);
+        let b = FiniteDomain::from(4..=8);
+        let c = FiniteDomain::from(9..=12);
+        let d = FiniteDomain::from(20..=21);
+
+        // Union of overlapping or adjacent intervals is an interval
+        assert!(matches!(a.union(&b), FiniteDomain::Interval(r) if r == (1..=8)));
+        assert!(matches!(c.union(&b), FiniteDomain::Interval(r) if r == (4..=12)));
+
+        // Union of disjoint intervals is a sparse
+        let union = a.union(&d);
+        assert!(
+            matches!(union, FiniteDomain::Sparse(ref v) if *v == vec![1, 2, 3, 4, 5, 6, 20, 21])
+        );
+    }
+
+    #[test]
+    fn test_finitedomain_10() {
+        // union interval with sparse
+        let a = FiniteDomain::from(1..=5);
+        let b = FiniteDomain::from(vec![6, 7]);
+        let c = FiniteDomain::from(vec![3, 8, 10]);
+
+        // Union without gaps collapses into an interval
+        assert!(matches!(a.union(&b), FiniteDomain::Interval(r) if r == (1..=7)));
+        assert!(matches!(b.union(&a), FiniteDomain::Interval(r) if r == (1..=7)));
+
+        let union = a.union(&c);
+        assert!(matches!(union, FiniteDomain::Sparse(ref v) if *v == vec![1, 2, 3, 4, 5, 8, 10]));
+    }
+
+    #[test]
+    fn test_finitedomain_11() {
+        // union sparse with sparse
+        let a = FiniteDomain::from(vec![1, 3, 5]);
+        let b = FiniteDomain::from(vec![3, 4, 9]);
+        let c = FiniteDomain::from(vec![2, 4]);
+
+        // Duplicates are removed
+        let union = a.union(&b);
+        assert!(matches!(union, FiniteDomain::Sparse(ref v) if *v == vec![1, 3, 4, 5, 9]));
+
+        assert!(matches!(a.union(&c), FiniteDomain::Interval(r) if r == (1..=5)));
+    }
+
+    #[test]
+    fn test_finitedomain_12() {
+        // len
+        assert_eq!(FiniteDomain::from(1..=6).len(), 6);
+        assert_eq!(FiniteDomain::from(-3..=-3).len(), 1);
+        assert_eq!(FiniteDomain::from(vec![1, 5, 9]).len(), 3);
+        assert_eq!(
+            FiniteDomain::from(isize::MIN..=isize::MAX).len(),
+            usize::MAX
+        );
+        assert!(!FiniteDomain::from(vec![1]).is_empty());
+        assert_eq!(FiniteDomain::Empty.len(), 0);
+    }
+
+    #[test]
+    fn test_finitedomain_13() {
+        // to_vec and count
+        assert_eq!(FiniteDomain::from(1..=4).to_vec(), vec![1, 2, 3, 4]);
+        assert_eq!(FiniteDomain::from(vec![-2, 0, 7]).to_vec(), vec![-2, 0, 7]);
+        assert_eq!(
+            FiniteDomain::from(isize::MAX - 1..=isize::MAX).to_vec(),
+            vec![isize::MAX - 1, isize::MAX]
+        );
+        assert_eq!(FiniteDomain::from(1..=4).count(), 4);
+        assert_eq!(FiniteDomain::from(vec![-2, 0, 7]).count(), 3);
+    }
+
+    #[test]
+    fn test_finitedomain_14() {
+        // Empty domain
+        assert!(FiniteDomain::from(vec![]).is_empty());
+        assert!(FiniteDomain::from(5..=4).is_empty());
+        assert!(FiniteDomain::from(1..=3)
+            .diff(&FiniteDomain::from(0..=5))
+            .is_empty());
+
+        let empty = FiniteDomain::Empty;
+        let a = FiniteDomain::from(vec![1, 3]);
+        assert!(!empty.is_singleton());
+        assert_eq!(empty.singleton_value(), None);
+        assert!(!empty.contains(0));
+        assert_eq!(empty.iter().count(), 0);
+        assert!(empty.to_vec().is_empty());
+        assert!(empty.intersect(&a).is_empty());
+        assert!(a.intersect(&empty).is_empty());
+        assert_eq!(a.diff(&empty), a);
+        assert!(empty.diff(&a).is_empty());
+        assert!(matches!(a.union(&empty), FiniteDomain::Sparse(ref v) if *v == vec![1, 3]));
+        assert!(a.is_disjoint(&empty));
+        assert_eq!(empty.to_string(), "{}");
+    }
+
+    #[test]
+    fn test_finitedomain_15() {
+        // From exclusive range
+        assert!(matches!(FiniteDomain::from(1..4), FiniteDomain::Interval(r) if r == (1..=3)));
+        assert!(
+            matches!(FiniteDomain::from(&(-2..-1)), FiniteDomain::Interval(r) if r == (-2..=-2))
+        );
+        assert!(FiniteDomain::from(3..3).is_empty());
+        assert!(matches!(
+            FiniteDomain::from(isize::MIN..isize::MAX),
+            FiniteDomain::Interval(r) if r == (isize::MIN..=isize::MAX - 1)
+        ));
     }
 }
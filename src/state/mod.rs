@@ -3,7 +3,7 @@ use crate::lterm::{LTerm, LTermInner};
 use crate::lvalue::LValue;
 use crate::relation::diseq::DisequalityConstraint;
 use crate::user::{DefaultUser, User};
-use std::collections::HashMap;
+use indexmap::IndexMap;
 use std::rc::Rc;
 
 mod substitution;
@@ -13,7 +13,7 @@ mod unification;
 pub use unification::unify_rec;
 
 pub mod constraint;
-pub use constraint::Constraint;
+pub use constraint::{Constraint, ConstraintError};
 
 pub mod fd;
 pub use fd::FiniteDomain;
@@ -25,7 +25,15 @@ pub mod map_sum;
 mod reification;
 pub use reification::reify;
 
-pub type SResult<U, E> = Result<State<U, E>, ()>;
+#[cfg(feature = "clpfd")]
+mod dimacs;
+
+#[cfg(feature = "serde")]
+mod checkpoint;
+#[cfg(feature = "serde")]
+pub use checkpoint::{Checkpoint, CheckpointError, Restored};
+
+pub type SResult<U, E> = Result<State<U, E>, ConstraintError<U, E>>;
 
 /// Logic program state
 ///
@@ -53,7 +61,10 @@ where
     cstore: Rc<ConstraintStore<U, E>>,
 
     /// The domain store
-    dstore: Rc<HashMap<LTerm<U, E>, Rc<FiniteDomain>>>,
+    dstore: Rc<IndexMap<LTerm<U, E>, Rc<FiniteDomain>>>,
+
+    /// Domains of the query variables, recorded before the domains are enumerated
+    query_domains: Rc<IndexMap<LTerm<U, E>, Rc<FiniteDomain>>>,
 
     pub user_state: U,
 }
@@ -67,7 +78,8 @@ where
         State {
             smap: Rc::new(SMap::new()),
             cstore: Rc::new(ConstraintStore::new()),
-            dstore: Rc::new(HashMap::new()),
+            dstore: Rc::new(IndexMap::new()),
+            query_domains: Rc::new(IndexMap::new()),
             user_state,
         }
     }
@@ -120,15 +132,36 @@ where
     }
 
     /// Return a reference to the domain store of the state
-    pub fn dstore_ref(&self) -> &HashMap<LTerm<U, E>, Rc<FiniteDomain>> {
+    pub fn dstore_ref(&self) -> &IndexMap<LTerm<U, E>, Rc<FiniteDomain>> {
         self.dstore.as_ref()
     }
 
-    pub fn dstore_to_mut(&mut self) -> &mut HashMap<LTerm<U, E>, Rc<FiniteDomain>> {
+    /// Returns the number of constraints in the constraint store.
+    pub fn constraint_count(&self) -> usize {
+        self.cstore.len()
+    }
+
+    /// Returns the number of variables with a finite domain in the domain store.
+    pub fn domain_count(&self) -> usize {
+        self.dstore.len()
+    }
+
+    pub fn dstore_to_mut(&mut self) -> &mut IndexMap<LTerm<U, E>, Rc<FiniteDomain>> {
         Rc::make_mut(&mut self.dstore)
     }
 
-    pub fn with_dstore(self, dstore: HashMap<LTerm<U, E>, Rc<FiniteDomain>>) -> State<U, E> {
+    /// Check if the variable `var` occurs in the term `t` under the current substitution.
+    ///
+    /// Both `var` and the subterms of `t`, including the children of compound terms, are
+    /// walked through the substitution map. If `var` is associated with a value, it is not
+    /// considered to occur in `t`.
+    pub fn occurs(&self, var: &LTerm<U, E>, t: &LTerm<U, E>) -> bool {
+        let smap = self.smap_ref();
+        let varwalk = smap.walk(var);
+        varwalk.is_var() && smap.occurs_check(varwalk, t)
+    }
+
+    pub fn with_dstore(self, dstore: IndexMap<LTerm<U, E>, Rc<FiniteDomain>>) -> State<U, E> {
         State {
             dstore: Rc::new(dstore),
             ..self
@@ -136,13 +169,29 @@ where
     }
 
     /// Get a cloned reference to the domain store fo the state
-    pub fn get_dstore(&self) -> Rc<HashMap<LTerm<U, E>, Rc<FiniteDomain>>> {
+    pub fn get_dstore(&self) -> Rc<IndexMap<LTerm<U, E>, Rc<FiniteDomain>>> {
         Rc::clone(&self.dstore)
     }
 
+    /// Returns the domain that the query variable `x` had when the goal of the query succeeded,
+    /// before the domains were enumerated into answers.
+    pub fn query_domain(&self, x: &LTerm<U, E>) -> Option<&Rc<FiniteDomain>> {
+        self.query_domains.get(x)
+    }
+
+    /// Records the domain of the query variable `x` before it is enumerated.
+    pub(crate) fn record_query_domain(&mut self, x: LTerm<U, E>, domain: Rc<FiniteDomain>) {
+        let _ = Rc::make_mut(&mut self.query_domains).insert(x, domain);
+    }
+
     /// Return the state with a new constraint
     pub fn with_constraint(mut self, constraint: Rc<dyn Constraint<U, E>>) -> State<U, E> {
         U::with_constraint(&mut self, &constraint);
+        if let Some(diseq) = constraint.downcast_ref::<DisequalityConstraint<U, E>>() {
+            U::on_disequality(&mut self, diseq);
+        } else if State::is_finite_domain(&constraint) {
+            U::on_fd_constraint(&mut self, &constraint);
+        }
         self.cstore_to_mut().push_and_normalize(constraint);
         self
     }
@@ -163,11 +212,17 @@ where
     /// Adds a new domain constraint for a variable `x`; or if the term is a value, then
     /// checks that the value is within the domain. If new domain constraint is added for a
     /// variable, it is updated to the domain store.
+    ///
+    /// Processing an empty domain, or a domain that has no values in common with the existing
+    /// domain of `x`, fails.
     pub fn process_domain(self, x: &LTerm<U, E>, domain: Rc<FiniteDomain>) -> SResult<U, E> {
+        if domain.is_empty() {
+            return Err(ConstraintError::new());
+        }
         match x.as_ref() {
             LTermInner::Var(_, _) => self.update_var_domain(x, domain),
             LTermInner::Val(LValue::Number(v)) if domain.contains(*v) => Ok(self),
-            _ => Err(()),
+            _ => Err(ConstraintError::new()),
         }
     }
 
@@ -177,17 +232,20 @@ where
     ///
     /// If the variable `x` is already constrained, then the resulting constraint is such that it
     /// fulfills both the old and the new constraint; i.e. it is an intersection of the domains.
-    /// If the domains are disjoint, the constraint fails and `None` is returned.
+    /// If the domains are disjoint, the constraint fails.
     ///
     /// Note: if domains are resolved into singletons, then they are converted into value
     ///       kind LTerms.
     fn update_var_domain(self, x: &LTerm<U, E>, domain: Rc<FiniteDomain>) -> SResult<U, E> {
         assert!(x.is_var());
         match self.dstore.get(x) {
-            Some(old_domain) => match old_domain.intersect(domain.as_ref()) {
-                Some(intersection) => self.resolve_storable_domain(x, Rc::new(intersection)),
-                None => Err(()), /* disjoint domains */
-            },
+            Some(old_domain) => {
+                let intersection = old_domain.intersect(domain.as_ref());
+                if intersection.is_empty() {
+                    return Err(ConstraintError::new()); /* disjoint domains */
+                }
+                self.resolve_storable_domain(x, Rc::new(intersection))
+            }
             None => self.resolve_storable_domain(x, domain),
         }
     }
@@ -210,7 +268,7 @@ where
                 self.smap_to_mut().extend(x.clone(), LTerm::from(n));
 
                 // Remove domain information from store
-                let _ = self.dstore_to_mut().remove(x);
+                let _ = self.dstore_to_mut().swap_remove(x);
 
                 // The substitution has been modified, re-run constraints.
                 self.run_constraints()
@@ -224,9 +282,9 @@ where
     }
 
     pub fn remove_domain(mut self, x: &LTerm<U, E>) -> SResult<U, E> {
-        match self.dstore_to_mut().remove(x) {
+        match self.dstore_to_mut().swap_remove(x) {
             Some(_) => Ok(self),
-            None => Err(()),
+            None => Err(ConstraintError::new()),
         }
     }
 
@@ -236,13 +294,14 @@ where
         x: &LTerm<U, E>,
         exclude: Rc<FiniteDomain>,
     ) -> SResult<U, E> {
-        assert!(x.is_list());
+        if !x.is_list() {
+            return Err(ConstraintError::new());
+        }
         let dstore = self.get_dstore();
         for y in x {
-            match dstore.get(&y) {
+            match dstore.get(y) {
                 Some(domain) => {
-                    match self.process_domain(&y, Rc::new(domain.diff(exclude.as_ref()).ok_or(())?))
-                    {
+                    match self.process_domain(&y, Rc::new(domain.diff(exclude.as_ref()))) {
                         Ok(state) => self = state,
                         Err(error) => return Err(error),
                     }
@@ -253,9 +312,18 @@ where
         Ok(self)
     }
 
+    /// Runs a single constraint on the current state. If the constraint fails, the returned
+    /// error identifies the failed constraint, unless a constraint that it ran in turn was
+    /// identified already. Otherwise the state is returned as updated by the constraint.
+    pub fn run_constraint(self, constraint: Rc<dyn Constraint<U, E>>) -> SResult<U, E> {
+        Rc::clone(&constraint)
+            .run(self)
+            .map_err(|error| error.or_constraint(constraint))
+    }
+
     /// Runs all constraints from the constraint store on the current state. If any of the
-    /// constraints fail, `None` is returned. Otherwise the state is returned with an updated
-    /// constraint store.
+    /// constraints fail, `Err(ConstraintError)` identifying the failed constraint is returned.
+    /// Otherwise the state is returned with an updated constraint store.
     pub fn run_constraints(mut self) -> SResult<U, E> {
         let mut constraints = self
             .cstore
@@ -269,7 +337,7 @@ where
         for constraint in constraints.drain(..) {
             self = match self.take_constraint(&constraint) {
                 (unconstrained_state, Some(constraint)) => {
-                    match constraint.run(unconstrained_state) {
+                    match unconstrained_state.run_constraint(constraint) {
                         Ok(constrained_state) => constrained_state,
                         Err(error) => return Err(error),
                     }
@@ -297,7 +365,7 @@ where
     /// domain- and constraint-stores is returned.
     ///
     /// If the resulting intersection domain is non-zero, the
-    /// substitution is not possible, the constraint fails and `None` is returned.
+    /// substitution is not possible, the constraint fails and an error is returned.
     fn process_extension_fd(mut self, extension: &SMap<U, E>) -> SResult<U, E> {
         let dstore = self.get_dstore();
         for (x, v) in extension.iter() {
@@ -335,15 +403,23 @@ where
         constraint.is::<crate::relation::clpfd::ltefd::LessThanOrEqualFdConstraint<U, E>>()
             || constraint.is::<crate::relation::clpfd::plusfd::PlusFdConstraint<U, E>>()
             || constraint.is::<crate::relation::clpfd::minusfd::MinusFdConstraint<U, E>>()
+            || constraint.is::<crate::relation::clpfd::divfd::DivFdConstraint<U, E>>()
+            || constraint.is::<crate::relation::clpfd::absfd::AbsFdConstraint<U, E>>()
+            || constraint.is::<crate::relation::clpfd::modfd::ModFdConstraint<U, E>>()
+            || constraint.is::<crate::relation::clpfd::reifyeq::ReifyEqConstraint<U, E>>()
+            || constraint.is::<crate::relation::clpfd::scaleo::ScaleFdConstraint<U, E>>()
+            || constraint.is::<crate::relation::clpfd::sumfd::SumFdConstraint<U, E>>()
             || constraint.is::<crate::relation::clpfd::timesfd::TimesFdConstraint<U, E>>()
             || constraint.is::<crate::relation::clpfd::diseqfd::DiseqFdConstraint<U, E>>()
             || constraint.is::<crate::relation::clpfd::distinctfd::DistinctFdConstraint<U, E>>()
             || constraint.is::<crate::relation::clpfd::distinctfd::DistinctFd2Constraint<U, E>>()
+            || constraint.is::<crate::relation::clpfd::disjointo::DisjointFdConstraint<U, E>>()
     }
 
     /// Verifies that all variables constrained by domain constraints have domains
-    /// associated with them.
-    pub fn verify_all_bound(&self) {
+    /// associated with them. If some constrained variable has no domain, the returned error
+    /// identifies the constraint of the variable.
+    pub fn verify_all_bound(&self) -> Result<(), ConstraintError<U, E>> {
         for constraint in self
             .cstore_ref()
             .iter()
@@ -352,13 +428,11 @@ where
             for u in &constraint.operands() {
                 let uwalk = self.smap_ref().walk(u);
                 if uwalk.is_var() && !self.dstore_ref().contains_key(uwalk) {
-                    panic!(
-                        "Error: Variable {:?} not bound to any domain. {:?}",
-                        u, self
-                    );
+                    return Err(ConstraintError::new().or_constraint(Rc::clone(constraint)));
                 }
             }
         }
+        Ok(())
     }
 
     pub fn unify(self, u: &LTerm<U, E>, v: &LTerm<U, E>) -> SResult<U, E> {
@@ -376,7 +450,7 @@ where
                 if extension.is_empty() {
                     // Unification succeeded without extending the current substitution, therefore
                     // disequality constraint fails.
-                    Err(())
+                    Err(ConstraintError::new())
                 } else {
                     // Unification succeeded with extended substitution map. Instead of adding the
                     // substitutions to the state, we add corresponding constraint to disequality
@@ -397,3 +471,222 @@ where
         U::reify(self);
     }
 }
+
+impl<U, E> std::fmt::Display for State<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "Substitutions:")?;
+        for (x, v) in self.smap_ref().iter() {
+            writeln!(f, "    {} = {}", x, v)?;
+        }
+        writeln!(f, "Domains:")?;
+        for (x, domain) in self.dstore_ref().iter() {
+            writeln!(f, "    {} in {}", x, domain)?;
+        }
+        writeln!(f, "Constraints:")?;
+        for constraint in self.cstore_ref().iter() {
+            let c = constraint.to_string();
+            if !c.is_empty() {
+                writeln!(f, "    {}", c)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::user::DefaultUser;
+
+    #[test]
+    fn test_state_display_1() {
+        let x = LTerm::var("x");
+        let y = LTerm::var("y");
+        let z = LTerm::var("z");
+        let state: State<DefaultUser, DefaultEngine<DefaultUser>> = State::new(DefaultUser::new())
+            .unify(&x, &LTerm::from(1))
+            .unwrap()
+            .process_domain(&y, Rc::new(FiniteDomain::from(&[2, 4, 6][..])))
+            .unwrap()
+            .disunify(&z, &LTerm::from(3))
+            .unwrap();
+
+        let s = state.to_string();
+        assert!(s.contains("Substitutions:\n    x = 1\n"));
+        assert!(s.contains("Domains:\n    y in {2, 4, 6}\n"));
+        assert!(s.contains("Constraints:\n    z != 3\n"));
+    }
+
+    #[test]
+    fn test_state_counts_1() {
+        let x = LTerm::var("x");
+        let y = LTerm::var("y");
+        let state: State<DefaultUser, DefaultEngine<DefaultUser>> = State::new(DefaultUser::new());
+        assert_eq!(state.constraint_count(), 0);
+        assert_eq!(state.domain_count(), 0);
+
+        let state = state
+            .process_domain(&x, Rc::new(FiniteDomain::from(&[1, 2, 3][..])))
+            .unwrap()
+            .process_domain(&y, Rc::new(FiniteDomain::from(&[2, 3, 4][..])))
+            .unwrap();
+        assert_eq!(state.constraint_count(), 0);
+        assert_eq!(state.domain_count(), 2);
+
+        let state = state.disunify(&x, &LTerm::from(5)).unwrap();
+        assert_eq!(state.constraint_count(), 1);
+
+        // Binding a variable removes its domain
+        let state = state.unify(&x, &LTerm::from(1)).unwrap();
+        assert_eq!(state.constraint_count(), 0);
+        assert_eq!(state.domain_count(), 1);
+    }
+
+    #[test]
+    fn test_state_occurs_1() {
+        let v0 = lterm!(_);
+        let v1 = lterm!(_);
+        let v2 = lterm!(_);
+        let state: State<DefaultUser, DefaultEngine<DefaultUser>> = State::new(DefaultUser::new());
+
+        // Variables do not occur in each other in an empty substitution
+        assert!(!state.occurs(&v0, &v1));
+        let state = state.unify(&v0, &v1).unwrap();
+
+        // Continuing variable substitution without forming a loop
+        assert!(!state.occurs(&v1, &v2));
+        let state = state.unify(&v1, &v2).unwrap();
+
+        // All of the variables now walk into the same variable
+        assert!(state.occurs(&v2, &v0));
+        assert!(state.occurs(&v0, &v2));
+    }
+
+    #[test]
+    fn test_state_occurs_2() {
+        let v0 = lterm!(_);
+        let v1 = lterm!(_);
+        let v2 = lterm!(_);
+        let v3 = lterm!(_);
+        let l = LTerm::cons(v1.clone(), v2.clone());
+        let state: State<DefaultUser, DefaultEngine<DefaultUser>> = State::new(DefaultUser::new());
+
+        assert!(!state.occurs(&v0, &l));
+        let state = state.unify(&v0, &l).unwrap();
+
+        // Continuing variable substitution without forming a loop
+        assert!(!state.occurs(&v1, &v3));
+        let state = state.unify(&v1, &v3).unwrap();
+
+        // The variable occurs in the list that the other variable is associated with
+        assert!(state.occurs(&v2, &v0));
+        assert!(state.occurs(&v3, &v0));
+        assert!(!state.occurs(&v0, &v3));
+    }
+
+    #[test]
+    fn test_state_occurs_3() {
+        let x = LTerm::var("x");
+        let y = LTerm::var("y");
+        let pair: LTerm<DefaultUser, DefaultEngine<DefaultUser>> = (lterm!(1), y.clone()).into();
+        let state: State<DefaultUser, DefaultEngine<DefaultUser>> = State::new(DefaultUser::new());
+
+        // Variables inside compound terms are found
+        assert!(state.occurs(&y, &pair));
+        assert!(!state.occurs(&x, &pair));
+
+        // A variable associated with a value does not occur anywhere
+        let state = state.unify(&y, &lterm!(2)).unwrap();
+        assert!(!state.occurs(&y, &pair));
+    }
+
+    #[test]
+    fn test_state_verify_all_bound_1() {
+        use crate::relation::clpfd::diseqfd::DiseqFdConstraint;
+        let x = LTerm::var("x");
+        let y = LTerm::var("y");
+        let state: State<DefaultUser, DefaultEngine<DefaultUser>> = State::new(DefaultUser::new())
+            .process_domain(&x, Rc::new(FiniteDomain::from(&[1, 2][..])))
+            .unwrap();
+        let state = DiseqFdConstraint::new(x.clone(), y.clone())
+            .run(state)
+            .unwrap();
+        let error = state.verify_all_bound().unwrap_err();
+        assert!(error
+            .constraint()
+            .unwrap()
+            .downcast_ref::<DiseqFdConstraint<_, _>>()
+            .is_some());
+        assert_eq!(error.operands(), vec![x.clone(), y.clone()]);
+
+        let state = state
+            .process_domain(&y, Rc::new(FiniteDomain::from(&[2, 3][..])))
+            .unwrap();
+        assert!(state.verify_all_bound().is_ok());
+    }
+
+    #[test]
+    fn test_state_verify_all_bound_2() {
+        // A query with a finite domain constrained variable without domain fails
+        // instead of panicking.
+        use crate::relation::clpfd::diseqfd::diseqfd;
+        use crate::relation::clpfd::infd::infd;
+        let query = proto_vulcan_query!(|x, y| {
+            infd(x, &[1, 2]),
+            diseqfd(x, y),
+        });
+        assert!(query.run().next().is_none());
+    }
+
+    #[derive(Debug, Clone, Default)]
+    struct CountingUser {
+        disequalities: usize,
+        fd_constraints: usize,
+    }
+
+    impl User for CountingUser {
+        type UserTerm = ();
+        type UserContext = ();
+
+        fn on_disequality<E: Engine<Self>>(
+            state: &mut State<Self, E>,
+            _constraint: &DisequalityConstraint<Self, E>,
+        ) {
+            state.user_state.disequalities += 1;
+        }
+
+        fn on_fd_constraint<E: Engine<Self>>(
+            state: &mut State<Self, E>,
+            constraint: &Rc<dyn Constraint<Self, E>>,
+        ) {
+            use crate::relation::clpfd::diseqfd::DiseqFdConstraint;
+            assert!(constraint.is::<DiseqFdConstraint<Self, E>>());
+            state.user_state.fd_constraints += 1;
+        }
+    }
+
+    #[test]
+    fn test_state_constraint_hooks_1() {
+        use crate::relation::clpfd::diseqfd::DiseqFdConstraint;
+        let x = LTerm::var("x");
+        let y = LTerm::var("y");
+        let state: State<CountingUser, DefaultEngine<CountingUser>> =
+            State::new(CountingUser::default())
+                .disunify(&x, &LTerm::from(1))
+                .unwrap()
+                .process_domain(&y, Rc::new(FiniteDomain::from(&[1, 2, 3][..])))
+                .unwrap();
+        assert_eq!(state.user_state.disequalities, 1);
+        assert_eq!(state.user_state.fd_constraints, 0);
+
+        let state = DiseqFdConstraint::new(x.clone(), y.clone())
+            .run(state)
+            .unwrap();
+        assert_eq!(state.user_state.disequalities, 1);
+        assert_eq!(state.user_state.fd_constraints, 1);
+    }
+}
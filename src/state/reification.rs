@@ -46,13 +46,41 @@ fn force_ans<U: User, E: Engine<U>>(x: LTerm<U, E>) -> Goal<U, E> {
     })
 }
 
+/// Records the domains of the query variables in the list `x` before the domains are
+/// enumerated, so that the query results can tell which values the variables could take.
+#[cfg(feature = "clpfd")]
+fn record_domains<U: User, E: Engine<U>>(x: LTerm<U, E>) -> Goal<U, E> {
+    proto_vulcan!(fngoal move |_engine, state| {
+        let mut state = state;
+        let xwalk = state.smap_ref().walk(&x).clone();
+        if xwalk.is_list() {
+            for v in xwalk.iter() {
+                let vwalk = state.smap_ref().walk(v).clone();
+                if let Some(domain) = state.dstore_ref().get(&vwalk).cloned() {
+                    state.record_query_domain(v.clone(), domain);
+                }
+            }
+        }
+        Stream::unit(Box::new(state))
+    })
+}
+
+#[cfg(not(feature = "clpfd"))]
+fn record_domains<U: User, E: Engine<U>>(_x: LTerm<U, E>) -> Goal<U, E> {
+    Goal::succeed()
+}
+
 #[cfg(feature = "clpfd")]
 fn enforce_constraints_fd<U: User, E: Engine<U>>(x: LTerm<U, E>) -> Goal<U, E> {
     proto_vulcan!([
         force_ans(x),
         fngoal | engine,
         state | {
-            state.verify_all_bound();
+            if let Err(error) = state.verify_all_bound() {
+                // A variable constrained by finite domain constraints has no domain, and
+                // cannot be enumerated.
+                return engine.fail(error);
+            }
             let bound_x = state.dstore_ref().keys().cloned().collect::<LTerm<U, E>>();
             proto_vulcan!( onceo { force_ans(bound_x) } ).solve(engine, state)
         }
@@ -78,6 +106,7 @@ fn enforce_constraints<U: User, E: Engine<U>>(x: LTerm<U, E>) -> Goal<U, E> {
 
 pub fn reify<U: User, E: Engine<U>>(x: LTerm<U, E>) -> Goal<U, E> {
     proto_vulcan!([
+        record_domains(x),
         enforce_constraints(x),
         fngoal move |_engine, state| {
             let smap = state.get_smap();
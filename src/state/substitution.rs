@@ -2,15 +2,157 @@ use crate::compound::CompoundObject;
 use crate::lterm::{LTerm, LTermInner};
 use crate::user::User;
 use crate::engine::Engine;
-use std::collections::HashMap;
+use std::hash::Hash;
 use std::ops::Deref;
 
+/// Persistent insertion-ordered map
+///
+/// Clones of the map share their structure, and a clone is extended without copying the
+/// whole map. The entries are iterated in the order in which the keys were first inserted.
+/// The read-only methods follow those of `IndexMap`, which the substitution map dereferences
+/// to.
+#[derive(Clone, Debug)]
+pub struct PersistentMap<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    entries: im_rc::HashMap<K, (usize, V)>,
+    order: im_rc::Vector<K>,
+}
+
+impl<K, V> PersistentMap<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    pub fn new() -> PersistentMap<K, V> {
+        PersistentMap {
+            entries: im_rc::HashMap::new(),
+            order: im_rc::Vector::new(),
+        }
+    }
+
+    /// Inserts a key-value pair into the map. If the key was already in the map, its value
+    /// is replaced, and the key keeps its position in the order of the map.
+    pub fn insert(&mut self, k: K, v: V) {
+        let index = self.order.len();
+        match self.entries.get_mut(&k) {
+            Some(entry) => entry.1 = v,
+            None => {
+                self.entries.insert(k.clone(), (index, v));
+                self.order.push_back(k);
+            }
+        }
+    }
+
+    pub fn get(&self, k: &K) -> Option<&V> {
+        self.entries.get(k).map(|(_, v)| v)
+    }
+
+    pub fn get_key_value(&self, k: &K) -> Option<(&K, &V)> {
+        self.get_full(k).map(|(_, k, v)| (k, v))
+    }
+
+    /// Returns the position of the key in the order of the map, and the entry.
+    pub fn get_full(&self, k: &K) -> Option<(usize, &K, &V)> {
+        self.entries
+            .get(k)
+            .map(|(index, v)| (*index, &self.order[*index], v))
+    }
+
+    pub fn get_index_of(&self, k: &K) -> Option<usize> {
+        self.entries.get(k).map(|(index, _)| *index)
+    }
+
+    /// Returns the entry at position `index` in the order of the map.
+    pub fn get_index(&self, index: usize) -> Option<(&K, &V)> {
+        self.order.get(index).map(|k| (k, &self.entries[k].1))
+    }
+
+    pub fn first(&self) -> Option<(&K, &V)> {
+        self.get_index(0)
+    }
+
+    pub fn last(&self) -> Option<(&K, &V)> {
+        self.len()
+            .checked_sub(1)
+            .and_then(|index| self.get_index(index))
+    }
+
+    pub fn contains_key(&self, k: &K) -> bool {
+        self.entries.contains_key(k)
+    }
+
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    /// Iterates over the entries of the map in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.order.iter().map(move |k| (k, &self.entries[k].1))
+    }
+
+    /// Iterates over the keys of the map in insertion order.
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.order.iter()
+    }
+
+    /// Iterates over the values of the map in insertion order.
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.order.iter().map(move |k| &self.entries[k].1)
+    }
+}
+
+impl<K, V> Default for PersistentMap<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    fn default() -> PersistentMap<K, V> {
+        PersistentMap::new()
+    }
+}
+
+impl<K, V> std::ops::Index<&K> for PersistentMap<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    type Output = V;
+
+    fn index(&self, k: &K) -> &V {
+        self.get(k).expect("PersistentMap: key not found")
+    }
+}
+
+impl<K, V> std::ops::Index<usize> for PersistentMap<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    type Output = V;
+
+    fn index(&self, index: usize) -> &V {
+        self.get_index(index)
+            .expect("PersistentMap: index out of bounds")
+            .1
+    }
+}
+
 /// Substitution Map
 ///
-/// Substitution maps track the binding of variables to terms.
+/// Substitution maps track the binding of variables to terms. The substitutions are kept in
+/// insertion order, so that iterating over the map is deterministic between runs. The map is
+/// persistent: cloning a substitution map is cheap, and extending a clone does not copy the
+/// substitutions that it shares with the original.
 #[derive(Derivative)]
-#[derivative(Debug(bound="U: User"), Clone(bound="U: User"))]
-pub struct SMap<U, E>(HashMap<LTerm<U, E>, LTerm<U, E>>)
+#[derivative(Debug(bound = "U: User"), Clone(bound = "U: User"))]
+pub struct SMap<U, E>(PersistentMap<LTerm<U, E>, LTerm<U, E>>)
 where
     U: User,
     E: Engine<U>;
@@ -22,7 +164,7 @@ where
 {
     /// Construct an an empty substitution map with no substitutions
     pub fn new() -> SMap<U, E> {
-        SMap(HashMap::new())
+        SMap(PersistentMap::new())
     }
 
     /// Extend substitution map with a new substitution
@@ -82,13 +224,26 @@ where
     /// Walks the substitution map recursively like `walk()`, but does not stop at lists, and
     /// instead recurses to do the deep walk also for the list elements. Returns a term which
     /// is a tree where all leaves are walked terms.
+    ///
+    /// Lists are walked iteratively along their spine, such that the depth of recursion does
+    /// not grow with the length of the list.
     pub fn walk_star(&self, v: &LTerm<U, E>) -> LTerm<U, E> {
-        let v = self.walk(v);
-        match v.as_ref() {
-            LTermInner::Cons(head, tail) => LTerm::cons(self.walk_star(head), self.walk_star(tail)),
-            LTermInner::Compound(compound) => compound.walk_star(self),
-            _ => v.clone(),
-        }
+        let mut heads = vec![];
+        let mut v = self.walk(v);
+        let tail = loop {
+            match v.as_ref() {
+                LTermInner::Cons(head, tail) => {
+                    heads.push(self.walk_star(head));
+                    v = self.walk(tail);
+                }
+                LTermInner::Compound(compound) => break compound.walk_star(self),
+                _ => break v.clone(),
+            }
+        };
+        heads
+            .into_iter()
+            .rev()
+            .fold(tail, |tail, head| LTerm::cons(head, tail))
     }
 
     /// Check that the variable `x` is not contained in the compound object `compound`.
@@ -103,29 +258,64 @@ where
     ///
     /// Occurs check is used to prevent unification of terms that would cause the variable to
     /// be contained in itself.
+    ///
+    /// Lists are checked iteratively along their spine, and only the list elements are checked
+    /// recursively.
     pub fn occurs_check(&self, x: &LTerm<U, E>, v: &LTerm<U, E>) -> bool {
-        match self.walk(v).as_ref() {
-            LTermInner::Var(vvar, _) => match x.as_ref() {
-                LTermInner::Var(xvar, _) => *vvar == *xvar,
-                _ => false,
-            },
-            LTermInner::Cons(head, tail) => {
-                self.occurs_check(x, head) || self.occurs_check(x, tail)
+        let mut v = self.walk(v);
+        loop {
+            match v.as_ref() {
+                LTermInner::Var(vvar, _) => {
+                    return match x.as_ref() {
+                        LTermInner::Var(xvar, _) => *vvar == *xvar,
+                        _ => false,
+                    }
+                }
+                LTermInner::Cons(head, tail) => {
+                    if self.occurs_check(x, head) {
+                        return true;
+                    }
+                    v = self.walk(tail);
+                }
+                LTermInner::Compound(compound) => {
+                    return self.occurs_check_compound(x, compound.as_ref())
+                }
+                _ => return false,
             }
-            LTermInner::Compound(compound) => self.occurs_check_compound(x, compound.as_ref()),
-            _ => false,
         }
     }
 
-    fn reify_compound(&self, compound: &dyn CompoundObject<U, E>) -> SMap<U, E> {
-        let mut smap = self.clone();
+    fn reify_compound(&self, compound: &dyn CompoundObject<U, E>, names: &mut SMap<U, E>) {
         for child in compound.children() {
             match child.as_term() {
-                Some(v) => smap = smap.reify(v),
-                None => smap = smap.reify_compound(child),
+                Some(v) => self.reify_names(v, names),
+                None => self.reify_compound(child, names),
             }
         }
-        smap
+    }
+
+    fn reify_names(&self, v: &LTerm<U, E>, names: &mut SMap<U, E>) {
+        let walkv = self.walk(v);
+        match walkv.as_ref() {
+            LTermInner::Var(_, _) if !names.0.contains_key(walkv) => {
+                // If it was not possible to find substitution that ends in a value, then we
+                // append substitution to a reified variable, which can have any value. The
+                // variables are numbered in the order they are encountered in the term.
+                let name = U::reified_name(names.0.len());
+                let reified = if name == "_" {
+                    LTerm::any()
+                } else {
+                    LTerm::var(name)
+                };
+                names.extend(walkv.clone(), reified);
+            }
+            LTermInner::Cons(head, tail) => {
+                self.reify_names(head, names);
+                self.reify_names(tail, names);
+            }
+            LTermInner::Compound(compound) => self.reify_compound(compound.as_ref(), names),
+            _ => (),
+        }
     }
 
     /// Reify substitution map
@@ -134,23 +324,18 @@ where
     /// have walkable values assigned to them in the substitution map. If the term or any subterm
     /// walks into a variable, a reified name is added to the substitution map. The reified name
     /// denotes that the the solution solves the logic query with any value of the variable.
+    /// The names of the reified variables are given by `User::reified_name`.
     ///
     /// This is typically used to generate a reifying substitution map from an empty map. The
     /// reifying map maps free variables to reified names. See State::reify().
     pub fn reify(&self, v: &LTerm<U, E>) -> SMap<U, E> {
-        let walkv = self.walk(v);
-        match walkv.as_ref() {
-            LTermInner::Var(_, _) => {
-                // If it was not possible to find substitution that ends in a value, then we
-                // append substitution to Any-variable, which can have any value.
-                let mut c = self.clone();
-                c.extend(walkv.clone(), LTerm::any());
-                c
-            }
-            LTermInner::Cons(head, tail) => self.reify(head).reify(tail),
-            LTermInner::Compound(compound) => self.reify_compound(compound.as_ref()),
-            _ => self.clone(),
+        let mut names = SMap::new();
+        self.reify_names(v, &mut names);
+        let mut smap = self.clone();
+        for (k, v) in names {
+            smap.extend(k, v);
         }
+        smap
     }
 
     fn is_anyvar_compound(&self, compound: &dyn CompoundObject<U, E>) -> bool {
@@ -204,10 +389,14 @@ where
     E: Engine<U>,
 {
     type Item = (LTerm<U, E>, LTerm<U, E>);
-    type IntoIter = ::std::collections::hash_map::IntoIter<LTerm<U, E>, LTerm<U, E>>;
+    type IntoIter = std::vec::IntoIter<(LTerm<U, E>, LTerm<U, E>)>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.0.into_iter()
+        self.0
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect::<Vec<_>>()
+            .into_iter()
     }
 }
 
@@ -216,7 +405,7 @@ where
     U: User,
     E: Engine<U>,
 {
-    type Target = HashMap<LTerm<U, E>, LTerm<U, E>>;
+    type Target = PersistentMap<LTerm<U, E>, LTerm<U, E>>;
 
     fn deref(&self) -> &Self::Target {
         &self.0
@@ -236,6 +425,54 @@ mod tests {
         assert!(smap.is_empty());
     }
 
+    #[test]
+    fn test_smap_persistent_1() {
+        // Extending a clone does not change the original, and the substitutions are iterated
+        // in insertion order also when the value of a variable is replaced.
+        let x = LTerm::var("x");
+        let y = LTerm::var("y");
+        let z = LTerm::var("z");
+        let mut smap = SMap::<DefaultUser, DefaultEngine<DefaultUser>>::new();
+        smap.extend(x.clone(), lterm!(1));
+        smap.extend(y.clone(), lterm!(2));
+
+        let mut clone = smap.clone();
+        clone.extend(z.clone(), lterm!(3));
+        clone.extend(x.clone(), lterm!(4));
+        assert_eq!(smap.len(), 2);
+        assert_eq!(smap.walk(&x), &lterm!(1));
+        assert!(!smap.contains_key(&z));
+        assert_eq!(clone.len(), 3);
+        assert_eq!(clone.walk(&x), &lterm!(4));
+
+        let keys: Vec<&LTerm<_, _>> = clone.keys().collect();
+        assert_eq!(keys, vec![&x, &y, &z]);
+        let pairs: Vec<(LTerm<_, _>, LTerm<_, _>)> = clone.into_iter().collect();
+        assert_eq!(pairs[0], (x, lterm!(4)));
+    }
+
+    #[test]
+    fn test_smap_persistent_2() {
+        // The substitutions are indexed in insertion order
+        let x = LTerm::var("x");
+        let y = LTerm::var("y");
+        let mut smap = SMap::<DefaultUser, DefaultEngine<DefaultUser>>::new();
+        assert!(smap.first().is_none());
+        smap.extend(x.clone(), lterm!(1));
+        smap.extend(y.clone(), lterm!(2));
+        smap.extend(x.clone(), lterm!(3));
+
+        assert_eq!(smap.get_index_of(&y), Some(1));
+        assert_eq!(smap.get_full(&x), Some((0, &x, &lterm!(3))));
+        assert_eq!(smap.get_index(1), Some((&y, &lterm!(2))));
+        assert_eq!(smap.first(), Some((&x, &lterm!(3))));
+        assert_eq!(smap.last(), Some((&y, &lterm!(2))));
+        assert_eq!(smap[&y], lterm!(2));
+        assert_eq!(smap[0], lterm!(3));
+        let values: Vec<&LTerm<_, _>> = smap.values().collect();
+        assert_eq!(values, vec![&lterm!(3), &lterm!(2)]);
+    }
+
     #[test]
     fn test_smap_extend() {
         let mut smap = SMap::<DefaultUser, DefaultEngine<DefaultUser>>::new();
@@ -252,6 +489,23 @@ mod tests {
         assert!(LTerm::ptr_eq(&w, &t));
     }
 
+    #[test]
+    fn test_smap_walk_star_long_list() {
+        // Walking a long list does not overflow the stack
+        let mut smap = SMap::<DefaultUser, DefaultEngine<DefaultUser>>::new();
+        let n = 100_000;
+        let x = lterm!(_);
+        let mut list = x.clone();
+        for i in (0..n).rev() {
+            list = LTerm::cons(LTerm::from(i), list);
+        }
+        smap.extend(x, LTerm::empty_list());
+
+        let walked = smap.walk_star(&list);
+        assert_eq!(walked.iter().count(), n as usize);
+        assert!(walked.iter().enumerate().all(|(i, t)| *t == i as isize));
+    }
+
     #[test]
     fn test_smap_occurs_check_1() {
         let mut smap = SMap::<DefaultUser, DefaultEngine<DefaultUser>>::new();
@@ -432,4 +686,47 @@ mod tests {
         assert!(r.walk(&v0).is_var());
         assert!(r.walk(&v1).is_var());
     }
+
+    #[test]
+    fn test_smap_reify_wildcard_1() {
+        // A free variable that walks into an unassociated wildcard variable is reified
+        let mut smap = SMap::<DefaultUser, DefaultEngine<DefaultUser>>::new();
+        let x = LTerm::var("x");
+        let w = LTerm::any();
+        smap.extend(x.clone(), w.clone());
+
+        let r = smap.reify(&x);
+        assert!(r.contains_key(&w));
+        assert!(r.is_anyvar(&x));
+    }
+
+    #[test]
+    fn test_smap_reify_compound_1() {
+        // A free variable that appears both bare and inside a compound object reifies to
+        // the same name.
+        let smap = SMap::<DefaultUser, DefaultEngine<DefaultUser>>::new();
+        let x = LTerm::var("x");
+        let y = LTerm::var("y");
+        let pair: LTerm<DefaultUser, DefaultEngine<DefaultUser>> = (x.clone(), y.clone()).into();
+        let t = LTerm::from_array(&[x.clone(), pair]);
+
+        let r = smap.reify(&t);
+        // Each free variable is assigned exactly one reified name
+        assert_eq!(r.len(), 2);
+
+        let w = r.walk_star(&t);
+        let bare = w.head().unwrap().clone();
+        assert!(bare.is_any());
+        let inner = w.tail().unwrap().head().unwrap().clone();
+        match inner.as_ref() {
+            LTermInner::Compound(object) => {
+                let children: Vec<&LTerm<_, _>> =
+                    object.children().map(|c| c.as_term().unwrap()).collect();
+                assert_eq!(children[0], &bare);
+                assert!(children[1].is_any());
+                assert_ne!(children[1], &bare);
+            }
+            _ => panic!("Expected compound"),
+        }
+    }
 }
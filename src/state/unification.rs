@@ -1,7 +1,7 @@
 use super::substitution::SMap;
 use crate::compound::CompoundObject;
 use crate::lterm::{LTerm, LTermInner};
-use crate::state::{SResult, State};
+use crate::state::{ConstraintError, SResult, State};
 use crate::engine::Engine;
 use crate::user::User;
 
@@ -28,7 +28,7 @@ where
             // The term u is a variable and the term v is something else. The variable u and
             // the term v can be unified by extending the substitution map.
             if state.smap_ref().occurs_check(&uwalk, &vwalk) {
-                Err(())
+                Err(ConstraintError::new())
             } else {
                 extension.extend(uwalk.clone(), vwalk.clone());
                 state.smap_to_mut().extend(uwalk, vwalk);
@@ -39,7 +39,7 @@ where
             // The term `v` is a variable and the term `u` is something else. The variable `v`
             // and the term `u` can be unified by extending the substitution map.
             if state.smap_ref().occurs_check(&vwalk, &uwalk) {
-                Err(())
+                Err(ConstraintError::new())
             } else {
                 extension.extend(vwalk.clone(), uwalk.clone());
                 state.smap_to_mut().extend(vwalk, uwalk);
@@ -63,7 +63,7 @@ where
         (LTermInner::Compound(ucf), LTermInner::Compound(vcf)) => {
             unify_rec_compound(state, extension, ucf.as_ref(), vcf.as_ref())
         }
-        _ => Err(()),
+        _ => Err(ConstraintError::new()),
     }
 }
 
@@ -79,7 +79,7 @@ where
     E: Engine<U>,
 {
     if ucompound.type_id() != vcompound.type_id() {
-        return Err(());
+        return Err(ConstraintError::new());
     }
 
     let mut uchildren = ucompound.children();
@@ -98,7 +98,7 @@ where
                 }
             }
             (None, None) => return Ok(state),
-            _ => return Err(()),
+            _ => return Err(ConstraintError::new()),
         }
     }
 }
@@ -355,4 +355,55 @@ mod tests {
         let mut extension = SMap::new();
         assert!(matches!(unify_rec(state, &mut extension, &v, &u), Err(_)));
     }
+
+    #[test]
+    fn test_unify_14() {
+        // Custom values of different concrete types do not unify
+        #[derive(Debug, PartialEq, Eq, Hash)]
+        struct Meters(isize);
+        impl std::fmt::Display for Meters {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}m", self.0)
+            }
+        }
+
+        #[derive(Debug, PartialEq, Eq, Hash)]
+        struct Seconds(isize);
+        impl std::fmt::Display for Seconds {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}s", self.0)
+            }
+        }
+
+        let state = State::<DefaultUser>::new(Default::default());
+        let u: LTerm = LTerm::from(LValue::custom(Meters(1)));
+        let v: LTerm = LTerm::from(LValue::custom(Meters(1)));
+        let w: LTerm = LTerm::from(LValue::custom(Seconds(1)));
+
+        let mut extension = SMap::new();
+        assert!(unify_rec(state.clone(), &mut extension, &u, &v).is_ok());
+        assert!(unify_rec(state.clone(), &mut extension, &u, &w).is_err());
+        assert!(unify_rec(state, &mut extension, &u, &lterm!(1)).is_err());
+    }
+
+    #[test]
+    fn test_unify_long_list() {
+        // The occurs check of a long list does not overflow the stack
+        let state = State::<DefaultUser, DefaultEngine<DefaultUser>>::new(Default::default());
+        let x = lterm!(_);
+        let list = LTerm::from_vec((0..50_000).map(LTerm::from).collect());
+        let state = state.unify(&x, &list).unwrap();
+        assert!(LTerm::ptr_eq(state.smap_ref().walk(&x), &list));
+
+        // The variable is found anywhere in the structure
+        let state = State::<DefaultUser, DefaultEngine<DefaultUser>>::new(Default::default());
+        let y = lterm!(_);
+        let l = LTerm::improper_from_vec(vec![lterm!(1), lterm!([2, y]), y.clone()]);
+        assert!(state.clone().unify(&y, &l).is_err());
+        let mut items: Vec<LTerm<DefaultUser, DefaultEngine<DefaultUser>>> =
+            (0..50_000).map(LTerm::from).collect();
+        items.push(y.clone());
+        let cyclic = LTerm::improper_from_vec(items);
+        assert!(state.unify(&y, &cyclic).is_err());
+    }
 }
@@ -370,6 +370,66 @@ impl<U: User, E: Engine<U>> Stream<U, E> {
     pub fn walk<'a>(&'a self) -> StreamWalker<'a, U, E> {
         StreamWalker::new(self)
     }
+
+    /// Returns a Graphviz DOT representation of the search tree of the stream.
+    ///
+    /// The lazy stream nodes are labeled by their `Lazy`-variant, and the states by `Unit`,
+    /// or by `Cons` when a lazy stream follows the state. The edges are labeled by the
+    /// branch they lead to. Empty streams are not shown.
+    pub fn to_dot(&self) -> String {
+        let mut nodes: Vec<&'static str> = vec![];
+        let mut edges: Vec<(usize, usize, &'static str)> = vec![];
+        // The node and the label of its next outgoing edge at each depth of the walk
+        let mut path: Vec<(usize, &'static str)> = vec![];
+        let mut walker = self.walk();
+        while let Some((depth, step)) = walker.next() {
+            let (label, branch) = match step {
+                StreamWalkStep::State(_) => ("Unit", "tail"),
+                StreamWalkStep::LazyStream(lazy_stream) => match &*lazy_stream.0 {
+                    Lazy::Bind(_, _) => ("Bind", "bound"),
+                    Lazy::MPlus(_, _) => ("MPlus", "left"),
+                    Lazy::Pause(_, _) => ("Pause", ""),
+                    Lazy::BindDFS(_, _) => ("BindDFS", "bound"),
+                    Lazy::MPlusDFS(_, _) => ("MPlusDFS", "left"),
+                    Lazy::PauseDFS(_, _) => ("PauseDFS", ""),
+                    Lazy::Delay(_) => ("Delay", "delayed"),
+                    Lazy::Iterator(_) => ("Iterator", ""),
+                },
+                StreamWalkStep::Backtrack(_) => {
+                    // The walk continues from the right branch of the node
+                    path.truncate(depth + 1);
+                    if let Some(last) = path.last_mut() {
+                        last.1 = "right";
+                    }
+                    continue;
+                }
+            };
+            let id = nodes.len();
+            nodes.push(label);
+            path.truncate(depth);
+            if let Some(&(parent, edge)) = path.last() {
+                if nodes[parent] == "Unit" {
+                    // A state that is followed by a lazy stream is a cons-cell
+                    nodes[parent] = "Cons";
+                }
+                edges.push((parent, id, edge));
+            }
+            path.push((id, branch));
+        }
+
+        let mut dot = String::from("digraph stream {\n");
+        for (id, label) in nodes.iter().enumerate() {
+            dot.push_str(&format!("    n{} [label=\"{}\"];\n", id, label));
+        }
+        for (from, to, label) in edges {
+            dot.push_str(&format!(
+                "    n{} -> n{} [label=\"{}\"];\n",
+                from, to, label
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
 }
 
 #[derive(Debug)]
@@ -388,35 +448,80 @@ where
     }
 
     fn step(&self, solver: &Solver<U, Self>, lazy: Lazy<U, Self>) -> Stream<U, Self> {
-        match lazy {
-            Lazy::MPlus(s1, s2) => {
-                let stream = self.step(solver, *s1.0);
-                Stream::mplus(stream, s2)
-            }
-            Lazy::Bind(s, goal) => {
-                let stream = self.step(solver, *s.0);
-                Stream::bind(stream, goal)
-            }
-            Lazy::Pause(state, goal) => solver.start(&goal, *state),
-            Lazy::MPlusDFS(s1, s2) => {
-                let stream = self.step(solver, *s1.0);
-                Stream::mplus_dfs(stream, s2)
-            }
-            Lazy::BindDFS(s, goal) => {
-                let stream = self.step(solver, *s.0);
-                Stream::bind_dfs(stream, goal)
-            }
-            Lazy::PauseDFS(state, goal) => solver.start_dfs(&goal, *state),
-            Lazy::Delay(stream) => stream,
-            Lazy::Iterator(mut iter) => {
-                // The point of iterator (at least for now) is to conserve used resources by
-                // deferring stream expansion; thus using DFS search to process the returned
-                // stream fully before asking for more from the iterator.
-                match iter.next(solver) {
-                    Some(stream) => Stream::mplus_dfs(stream, LazyStream::iterator(iter)),
-                    None => Stream::empty(),
-                }
+        step_lazy(solver, lazy)
+    }
+}
+
+/// Expands `lazy` by one step of the interleaving search. The nested lazy streams are stepped
+/// with the engine of `solver`.
+pub(crate) fn step_lazy<U, E>(solver: &Solver<U, E>, lazy: Lazy<U, E>) -> Stream<U, E>
+where
+    U: User,
+    E: Engine<U>,
+{
+    match lazy {
+        Lazy::MPlus(s1, s2) => {
+            let stream = solver.engine().step(solver, *s1.0);
+            Stream::mplus(stream, s2)
+        }
+        Lazy::Bind(s, goal) => {
+            let stream = solver.engine().step(solver, *s.0);
+            Stream::bind(stream, goal)
+        }
+        Lazy::Pause(state, goal) => solver.start(&goal, *state),
+        Lazy::MPlusDFS(s1, s2) => {
+            let stream = solver.engine().step(solver, *s1.0);
+            Stream::mplus_dfs(stream, s2)
+        }
+        Lazy::BindDFS(s, goal) => {
+            let stream = solver.engine().step(solver, *s.0);
+            Stream::bind_dfs(stream, goal)
+        }
+        Lazy::PauseDFS(state, goal) => solver.start_dfs(&goal, *state),
+        Lazy::Delay(stream) => stream,
+        Lazy::Iterator(mut iter) => {
+            // The point of iterator (at least for now) is to conserve used resources by
+            // deferring stream expansion; thus using DFS search to process the returned
+            // stream fully before asking for more from the iterator.
+            match iter.next(solver) {
+                Some(stream) => Stream::mplus_dfs(stream, LazyStream::iterator(iter)),
+                None => Stream::empty(),
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{LazyStream, Stream};
+    use crate::prelude::*;
+    use crate::state::State;
+
+    type TestStream = Stream<DefaultUser, DefaultEngine<DefaultUser>>;
+
+    #[test]
+    fn test_stream_to_dot_1() {
+        let unit = || TestStream::unit(Box::new(State::new(DefaultUser::new())));
+        let stream = TestStream::lazy_mplus(LazyStream::delay(unit()), LazyStream::delay(unit()));
+        let dot = stream.to_dot();
+        assert!(dot.starts_with("digraph stream {\n"));
+        assert_eq!(dot.matches("[label=\"Unit\"]").count(), 2);
+        assert!(dot.contains("n0 [label=\"MPlus\"];"));
+        assert!(dot.contains("n0 -> n1 [label=\"left\"];"));
+        assert!(dot.contains("n0 -> n3 [label=\"right\"];"));
+        assert!(dot.contains("n3 -> n4 [label=\"delayed\"];"));
+    }
+
+    #[test]
+    fn test_stream_to_dot_2() {
+        // A state followed by a lazy stream is a cons-cell
+        let state = Box::new(State::new(DefaultUser::new()));
+        let tail = LazyStream::delay(TestStream::unit(state.clone()));
+        let stream = TestStream::cons(state, tail);
+        let dot = stream.to_dot();
+        assert!(dot.contains("n0 [label=\"Cons\"];"));
+        assert!(dot.contains("n0 -> n1 [label=\"tail\"];"));
+        assert!(dot.contains("n2 [label=\"Unit\"];"));
+        assert_eq!(TestStream::empty().to_dot(), "digraph stream {\n}\n");
+    }
+}
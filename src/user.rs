@@ -16,8 +16,9 @@
 use crate::engine::Engine;
 use crate::goal::Goal;
 use crate::lterm::LTerm;
+use crate::relation::diseq::DisequalityConstraint;
 use crate::state::constraint::Constraint;
-use crate::state::{SMap, SResult, State};
+use crate::state::{ConstraintError, SMap, SResult, State};
 use std::fmt;
 use std::fmt::Debug;
 use std::hash::Hash;
@@ -45,7 +46,7 @@ pub trait User: Debug + Clone + Default + 'static {
         _uwalk: LTerm<Self, E>,
         _vwalk: LTerm<Self, E>,
     ) -> SResult<Self, E> {
-        Err(())
+        Err(ConstraintError::new())
     }
 
     /// Called before the constraint is added to the state
@@ -55,6 +56,22 @@ pub trait User: Debug + Clone + Default + 'static {
     ) {
     }
 
+    /// Called before a disequality constraint is added to the state, after `with_constraint`.
+    fn on_disequality<E: Engine<Self>>(
+        _state: &mut State<Self, E>,
+        _constraint: &DisequalityConstraint<Self, E>,
+    ) {
+    }
+
+    /// Called before a finite domain constraint is added to the state, after
+    /// `with_constraint`. The concrete constraint can be accessed with
+    /// `constraint.downcast_ref()`.
+    fn on_fd_constraint<E: Engine<Self>>(
+        _state: &mut State<Self, E>,
+        _constraint: &Rc<dyn Constraint<Self, E>>,
+    ) {
+    }
+
     /// Called after the constraint has been removed from the state
     fn take_constraint<E: Engine<Self>>(
         _state: &mut State<Self, E>,
@@ -71,6 +88,14 @@ pub trait User: Debug + Clone + Default + 'static {
     fn finalize<E: Engine<Self>>(_state: &mut State<Self, E>) {}
 
     fn reify<E: Engine<Self>>(_state: &mut State<Self, E>) {}
+
+    /// Name of the `index`th free variable of a reified term, numbered from zero in the order
+    /// the variables are encountered in the term. The default name `"_"` gives any-variables,
+    /// which are displayed as `_.<uid>`; any other name gives a variable that is displayed
+    /// with the returned name, such as `_.0`.
+    fn reified_name(_index: usize) -> &'static str {
+        "_"
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -0,0 +1,10 @@
+extern crate proto_vulcan;
+use proto_vulcan::prelude::*;
+
+#[compound]
+enum Expr {
+    Num(LTerm),
+    Nil,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+extern crate proto_vulcan;
+use proto_vulcan::prelude::*;
+
+fn main() {
+    let _query = proto_vulcan_query!(|q| {
+        match q {
+            [x, _] | [_, y] => x == 1,
+        }
+    });
+}
@@ -0,0 +1,8 @@
+extern crate proto_vulcan;
+use proto_vulcan::prelude::*;
+
+fn main() {
+    let _query = proto_vulcan_query!(|q| {
+        conde {}
+    });
+}
@@ -0,0 +1,11 @@
+extern crate proto_vulcan;
+use proto_vulcan::prelude::*;
+
+fn main() {
+    let _query = proto_vulcan_query!(|q, r| {
+        conde {
+            (q == 1, r == 2),
+            [q == 2, r == 3],
+        }
+    });
+}